@@ -0,0 +1,71 @@
+use crate::models::api_key::{ApiKey, NewApiKey};
+use crate::schema::api_keys::dsl;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_key_hash(
+    conn: &mut DynoDBPooledConnection,
+    key_hash: &str,
+) -> DynoResult<Option<ApiKey>> {
+    dsl::api_keys
+        .filter(dsl::key_hash.eq(key_hash))
+        .select(ApiKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<ApiKey>> {
+    dsl::api_keys
+        .select(ApiKey::as_select())
+        .order(dsl::id.desc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<ApiKey> {
+    dsl::api_keys
+        .find(id)
+        .select(ApiKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Api key is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewApiKey) -> DynoResult<i64> {
+    diesel::insert_into(dsl::api_keys)
+        .values(new)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// revoke the key, `false` when it is not exists or already revoked
+#[inline]
+#[allow(unused)]
+pub fn revoke(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<bool> {
+    diesel::update(dsl::api_keys.filter(dsl::id.eq(id).and(dsl::revoked_at.is_null())))
+        .set(dsl::revoked_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map(|affected| affected > 0)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn touch(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    diesel::update(dsl::api_keys.find(id))
+        .set(dsl::last_used_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
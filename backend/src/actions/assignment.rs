@@ -0,0 +1,282 @@
+use crate::models::assignment::{
+    Assignment, GradeSubmission, NewAssignment, NewSubmission, NewSubmissionDyno, Submission,
+    SubmissionResponse, UpdateAssignment,
+};
+use crate::schema::{assignments, submission_dynos, submissions, users};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<Assignment> {
+    assignments::table
+        .find(id)
+        .select(Assignment::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Assignment is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_class(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+) -> DynoResult<Vec<Assignment>> {
+    assignments::table
+        .filter(assignments::dsl::class_id.eq(class_id))
+        .select(Assignment::as_select())
+        .order(assignments::dsl::deadline.asc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewAssignment) -> DynoResult<i64> {
+    diesel::insert_into(assignments::table)
+        .values(new)
+        .returning(assignments::dsl::id)
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn update(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    mut updated: UpdateAssignment,
+) -> DynoResult<usize> {
+    updated.updated_at = Some(Utc::now().naive_utc());
+    diesel::update(assignments::table.find(id))
+        .set(updated)
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete the assignment with every submission of it, the submitted dynotests is kept
+#[allow(unused)]
+pub fn delete(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        let submitted = submissions::table
+            .filter(submissions::dsl::assignment_id.eq(id))
+            .select(submissions::dsl::id);
+        diesel::delete(
+            submission_dynos::table.filter(submission_dynos::dsl::submission_id.eq_any(submitted)),
+        )
+        .execute(conn)?;
+        diesel::delete(submissions::table.filter(submissions::dsl::assignment_id.eq(id)))
+            .execute(conn)?;
+        diesel::delete(assignments::table.find(id)).execute(conn)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+/// the dynotest is submitted into a submission that is already graded
+#[inline]
+#[allow(unused)]
+pub fn is_dyno_graded(conn: &mut DynoDBPooledConnection, dyno_id: i64) -> DynoResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        submission_dynos::table
+            .inner_join(submissions::table)
+            .filter(submission_dynos::dsl::dyno_id.eq(dyno_id))
+            .filter(submissions::dsl::graded_at.is_not_null()),
+    ))
+    .get_result(conn)
+    .map_err(DynoErr::database_error)
+}
+
+/// id of every user that has a submission to any assignment of the class
+#[inline]
+#[allow(unused)]
+pub fn select_submitter_ids(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+) -> DynoResult<Vec<i64>> {
+    submissions::table
+        .inner_join(assignments::table)
+        .filter(assignments::dsl::class_id.eq(class_id))
+        .select(submissions::dsl::user_id)
+        .distinct()
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// remove the dynotest from every submission, the submissions is kept
+#[inline]
+#[allow(unused)]
+pub fn delete_submission_dynos_by_dyno(
+    conn: &mut DynoDBPooledConnection,
+    dyno_id: i64,
+) -> DynoResult<usize> {
+    diesel::delete(submission_dynos::table.filter(submission_dynos::dsl::dyno_id.eq(dyno_id)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_submission_by_id(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+) -> DynoResult<Submission> {
+    submissions::table
+        .find(id)
+        .select(Submission::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Submission is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_submission(
+    conn: &mut DynoDBPooledConnection,
+    assignment_id: i64,
+    user_id: i64,
+) -> DynoResult<Option<Submission>> {
+    submissions::table
+        .filter(submissions::dsl::assignment_id.eq(assignment_id))
+        .filter(submissions::dsl::user_id.eq(user_id))
+        .select(Submission::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)
+}
+
+/// submissions of the assignment with the submitted dynotests,
+/// only the submission of `user_id` when it is `Some`
+#[allow(unused)]
+pub fn select_submissions(
+    conn: &mut DynoDBPooledConnection,
+    assignment_id: i64,
+    user_id: Option<i64>,
+) -> DynoResult<Vec<SubmissionResponse>> {
+    let mut query = submissions::table
+        .inner_join(users::table)
+        .filter(submissions::dsl::assignment_id.eq(assignment_id))
+        .select((Submission::as_select(), users::dsl::nim, users::dsl::name))
+        .order(users::dsl::nim.asc())
+        .into_boxed();
+    if let Some(user_id) = user_id {
+        query = query.filter(submissions::dsl::user_id.eq(user_id));
+    }
+    let submitted = query
+        .load::<(Submission, String, String)>(conn)
+        .map_err(DynoErr::database_error)?;
+
+    let ids = submitted.iter().map(|(x, ..)| x.id).collect::<Vec<_>>();
+    let dynos = submission_dynos::table
+        .filter(submission_dynos::dsl::submission_id.eq_any(ids))
+        .select((
+            submission_dynos::dsl::submission_id,
+            submission_dynos::dsl::dyno_id,
+        ))
+        .order(submission_dynos::dsl::dyno_id.asc())
+        .load::<(i64, i64)>(conn)
+        .map_err(DynoErr::database_error)?;
+
+    Ok(submitted
+        .into_iter()
+        .map(|(submission, nim, name)| SubmissionResponse {
+            dyno_ids: dynos
+                .iter()
+                .filter(|(id, _)| *id == submission.id)
+                .map(|(_, dyno_id)| *dyno_id)
+                .collect(),
+            submission,
+            nim,
+            name,
+        })
+        .collect())
+}
+
+/// insert or replace the submission of the user in one transaction, return the id of submission
+#[allow(unused)]
+pub fn submit(
+    conn: &mut DynoDBPooledConnection,
+    new: NewSubmission,
+    dyno_ids: Vec<i64>,
+) -> DynoResult<i64> {
+    conn.transaction(|conn| {
+        let existing = submissions::table
+            .filter(submissions::dsl::assignment_id.eq(new.assignment_id))
+            .filter(submissions::dsl::user_id.eq(new.user_id))
+            .select(submissions::dsl::id)
+            .first::<i64>(conn)
+            .optional()?;
+        let submission_id = match existing {
+            Some(id) => {
+                diesel::update(submissions::table.find(id))
+                    .set((
+                        submissions::dsl::analysis.eq(new.analysis),
+                        submissions::dsl::updated_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)?;
+                diesel::delete(
+                    submission_dynos::table.filter(submission_dynos::dsl::submission_id.eq(id)),
+                )
+                .execute(conn)?;
+                id
+            }
+            None => diesel::insert_into(submissions::table)
+                .values(new)
+                .returning(submissions::dsl::id)
+                .get_result(conn)?,
+        };
+        let links = dyno_ids
+            .into_iter()
+            .map(|dyno_id| NewSubmissionDyno {
+                submission_id,
+                dyno_id,
+            })
+            .collect::<Vec<_>>();
+        diesel::insert_into(submission_dynos::table)
+            .values(links)
+            .execute(conn)?;
+        Ok(submission_id)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn grade(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    graded_by: i64,
+    GradeSubmission { grade, comment }: GradeSubmission,
+) -> DynoResult<usize> {
+    diesel::update(submissions::table.find(id))
+        .set((
+            submissions::dsl::grade.eq(Some(grade)),
+            submissions::dsl::comment.eq(comment),
+            submissions::dsl::graded_by.eq(Some(graded_by)),
+            submissions::dsl::graded_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// `(assignment_id, user_id, grade)` of every submission in the class
+#[inline]
+#[allow(unused)]
+pub fn select_grades(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+) -> DynoResult<Vec<(i64, i64, Option<f32>)>> {
+    submissions::table
+        .inner_join(assignments::table)
+        .filter(assignments::dsl::class_id.eq(class_id))
+        .select((
+            submissions::dsl::assignment_id,
+            submissions::dsl::user_id,
+            submissions::dsl::grade,
+        ))
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -0,0 +1,74 @@
+use crate::models::audit_log::{AuditLog, AuditLogFilter, AuditLogResponse, NewAuditLog};
+use crate::schema::{audit_log, users};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{DynoErr, DynoResult};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// append the entry into the `audit_log`, the table can not be updated or deleted
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewAuditLog) -> DynoResult<i64> {
+    diesel::insert_into(audit_log::table)
+        .values(new)
+        .returning(audit_log::dsl::id)
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// [`AuditLog`] that match the [`AuditLogFilter`] with the nim and name of the actor,
+/// the newest first
+#[allow(unused)]
+pub fn select_filtered(
+    conn: &mut DynoDBPooledConnection,
+    filter: &AuditLogFilter,
+) -> DynoResult<Vec<AuditLogResponse>> {
+    let max = filter
+        .max
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let page = filter.page.unwrap_or(1).max(1);
+
+    let mut query = audit_log::table
+        .left_join(users::table)
+        .select((
+            AuditLog::as_select(),
+            users::dsl::nim.nullable(),
+            users::dsl::name.nullable(),
+        ))
+        .order(audit_log::dsl::id.desc())
+        .limit(max)
+        .offset((page - 1) * max)
+        .into_boxed();
+
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(audit_log::dsl::actor_id.eq(actor_id));
+    }
+    if let Some(action) = filter.action.as_deref().filter(|x| !x.is_empty()) {
+        let pattern = format!("{}%", action.replace(['%', '_'], ""));
+        query = query.filter(audit_log::dsl::action.like(pattern));
+    }
+    if let Some(target_type) = filter.target_type.as_deref().filter(|x| !x.is_empty()) {
+        query = query.filter(audit_log::dsl::target_type.eq(target_type.to_owned()));
+    }
+    if let Some(target_id) = filter.target_id {
+        query = query.filter(audit_log::dsl::target_id.eq(target_id));
+    }
+    if let Some(from) = filter.from.and_then(|x| x.and_hms_opt(0, 0, 0)) {
+        query = query.filter(audit_log::dsl::created_at.ge(from));
+    }
+    if let Some(to) = filter.to.and_then(|x| x.and_hms_opt(23, 59, 59)) {
+        query = query.filter(audit_log::dsl::created_at.le(to));
+    }
+
+    query
+        .load::<(AuditLog, Option<String>, Option<String>)>(conn)
+        .map(|x| {
+            x.into_iter()
+                .map(|(log, nim, name)| AuditLogResponse::new(log, nim, name))
+                .collect()
+        })
+        .map_err(DynoErr::database_error)
+}
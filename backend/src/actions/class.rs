@@ -0,0 +1,187 @@
+use crate::models::class::{
+    Class, ClassMemberResponse, EnrollMembers, NewClass, NewClassMember, UpdateClass,
+};
+use crate::schema::{class_members, classes, users};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<Class> {
+    classes::table
+        .find(id)
+        .select(Class::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Class is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<Class>> {
+    classes::table
+        .select(Class::as_select())
+        .order(classes::dsl::id.desc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// classes that is taught by the lecturer or has the user enrolled
+#[inline]
+#[allow(unused)]
+pub fn select_by_user(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<Vec<Class>> {
+    let enrolled = class_members::table
+        .filter(class_members::dsl::user_id.eq(user_id))
+        .select(class_members::dsl::class_id);
+    classes::table
+        .filter(
+            classes::dsl::lecturer_id
+                .eq(user_id)
+                .or(classes::dsl::id.eq_any(enrolled)),
+        )
+        .select(Class::as_select())
+        .order(classes::dsl::id.desc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewClass) -> DynoResult<i64> {
+    diesel::insert_into(classes::table)
+        .values(new)
+        .returning(classes::dsl::id)
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn update(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    mut updated: UpdateClass,
+) -> DynoResult<usize> {
+    updated.updated_at = Some(Utc::now().naive_utc());
+    diesel::update(classes::table.find(id))
+        .set(updated)
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete the class and every enrollment of it
+#[allow(unused)]
+pub fn delete(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        diesel::delete(class_members::table.filter(class_members::dsl::class_id.eq(id)))
+            .execute(conn)?;
+        diesel::delete(classes::table.find(id)).execute(conn)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_members(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+) -> DynoResult<Vec<ClassMemberResponse>> {
+    class_members::table
+        .inner_join(users::table)
+        .filter(class_members::dsl::class_id.eq(class_id))
+        .select((
+            class_members::dsl::user_id,
+            users::dsl::nim,
+            users::dsl::name,
+            class_members::dsl::group_name,
+            class_members::dsl::joined_at,
+        ))
+        .order((class_members::dsl::group_name.asc(), users::dsl::nim.asc()))
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// enroll every user in one transaction, already enrolled user only has the group replaced
+#[allow(unused)]
+pub fn enroll(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    EnrollMembers {
+        user_ids,
+        group_name,
+    }: EnrollMembers,
+) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        user_ids.into_iter().try_fold(0, |count, user_id| {
+            diesel::replace_into(class_members::table)
+                .values(NewClassMember {
+                    class_id,
+                    user_id,
+                    group_name: group_name.clone(),
+                })
+                .execute(conn)
+                .map(|x| count + x)
+        })
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn remove_member(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    user_id: i64,
+) -> DynoResult<usize> {
+    diesel::delete(class_members::table.find((class_id, user_id)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn is_member(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    user_id: i64,
+) -> DynoResult<bool> {
+    class_members::table
+        .find((class_id, user_id))
+        .select(class_members::dsl::user_id)
+        .first::<i64>(conn)
+        .optional()
+        .map_err(DynoErr::database_error)
+        .map(|x| x.is_some())
+}
+
+/// number of classes that is taught by `lecturer_id`
+#[inline]
+#[allow(unused)]
+pub fn count_by_lecturer(conn: &mut DynoDBPooledConnection, lecturer_id: i64) -> DynoResult<i64> {
+    classes::table
+        .filter(classes::dsl::lecturer_id.eq(lecturer_id))
+        .count()
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// the lecturer of the class or user with `dyno.read_all` can see the dynotest submitted to the
+/// assignments of the class and the history of its submitters
+#[allow(unused)]
+pub fn check_class_access(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    user_id: i64,
+    read_all: bool,
+) -> DynoResult<Class> {
+    let class = select_by_id(conn, class_id)?;
+    if read_all || class.lecturer_id == user_id {
+        Ok(class)
+    } else {
+        Err(DynoErr::forbidden_error(
+            "Class is only accessible by its lecturer",
+        ))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::models::comment::{Annotation, CommentResponse, NewAnnotation, NewComment};
+use crate::schema::{dyno_annotations, dyno_comments, users};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{DynoErr, DynoResult};
+
+/// every comment of the dynotest ordered by the time it is created
+#[inline]
+#[allow(unused)]
+pub fn select_comments(
+    conn: &mut DynoDBPooledConnection,
+    dyno_id: i64,
+) -> DynoResult<Vec<CommentResponse>> {
+    dyno_comments::table
+        .inner_join(users::table)
+        .filter(dyno_comments::dsl::dyno_id.eq(dyno_id))
+        .select((
+            dyno_comments::dsl::id,
+            dyno_comments::dsl::dyno_id,
+            dyno_comments::dsl::user_id,
+            dyno_comments::dsl::parent_id,
+            dyno_comments::dsl::body,
+            dyno_comments::dsl::updated_at,
+            dyno_comments::dsl::created_at,
+            users::dsl::nim,
+            users::dsl::name,
+        ))
+        .order(dyno_comments::dsl::id.asc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// `(dyno_id, user_id)` of the comment
+#[inline]
+#[allow(unused)]
+pub fn select_comment_owner(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<(i64, i64)> {
+    dyno_comments::table
+        .find(id)
+        .select((dyno_comments::dsl::dyno_id, dyno_comments::dsl::user_id))
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Comment is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert_comment(conn: &mut DynoDBPooledConnection, new: NewComment) -> DynoResult<i64> {
+    diesel::insert_into(dyno_comments::table)
+        .values(new)
+        .returning(dyno_comments::dsl::id)
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete the comment with every reply of it
+#[allow(unused)]
+pub fn delete_comment(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        let mut deleted = 0;
+        let mut ids = vec![id];
+        while !ids.is_empty() {
+            let replies = dyno_comments::table
+                .filter(dyno_comments::dsl::parent_id.eq_any(&ids))
+                .select(dyno_comments::dsl::id)
+                .load::<i64>(conn)?;
+            deleted +=
+                diesel::delete(dyno_comments::table.filter(dyno_comments::dsl::id.eq_any(&ids)))
+                    .execute(conn)?;
+            ids = replies;
+        }
+        Ok(deleted)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_annotations(
+    conn: &mut DynoDBPooledConnection,
+    dyno_id: i64,
+) -> DynoResult<Vec<Annotation>> {
+    dyno_annotations::table
+        .filter(dyno_annotations::dsl::dyno_id.eq(dyno_id))
+        .select(Annotation::as_select())
+        .order((
+            dyno_annotations::dsl::anchor.asc(),
+            dyno_annotations::dsl::position.asc(),
+        ))
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_annotation_by_id(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+) -> DynoResult<Annotation> {
+    dyno_annotations::table
+        .find(id)
+        .select(Annotation::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Annotation is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert_annotation(conn: &mut DynoDBPooledConnection, new: NewAnnotation) -> DynoResult<i64> {
+    diesel::insert_into(dyno_annotations::table)
+        .values(new)
+        .returning(dyno_annotations::dsl::id)
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn delete_annotation(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    diesel::delete(dyno_annotations::table.find(id))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete every comment and annotation of the dynotest
+#[allow(unused)]
+pub fn delete_by_dyno(conn: &mut DynoDBPooledConnection, dyno_id: i64) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        let comments =
+            diesel::delete(dyno_comments::table.filter(dyno_comments::dsl::dyno_id.eq(dyno_id)))
+                .execute(conn)?;
+        let annotations = diesel::delete(
+            dyno_annotations::table.filter(dyno_annotations::dsl::dyno_id.eq(dyno_id)),
+        )
+        .execute(conn)?;
+        Ok(comments + annotations)
+    })
+    .map_err(DynoErr::database_error)
+}
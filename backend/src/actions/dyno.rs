@@ -1,7 +1,60 @@
-use crate::models::dyno::{Dynos, NewDynos};
+use crate::models::{
+    dyno::{Dynos, NewDynos, StorageUsage},
+    info::DynoInfo,
+};
 use crate::DynoDBPooledConnection;
 use diesel::prelude::*;
-use dyno_core::{DynoErr, DynoResult};
+use diesel::sql_types::{BigInt, Bool};
+use dyno_core::{
+    chrono::{NaiveDate, NaiveDateTime},
+    DynoErr, DynoResult,
+};
+
+/// dynotest `d` is readable by the user `?1`: owner, `dyno.read_all` (`?2`), shared, or submitted
+/// to an assignment of a class `?1` teach. the only definition of the rule, used by [`is_readable`]
+/// and [`crate::actions::search::search`].
+pub(crate) const READABLE_DYNO: &str = "(d.user_id = ?1 OR ?2 OR d.shared \
+    OR EXISTS (SELECT 1 FROM submission_dynos sd \
+        JOIN submissions s ON s.id = sd.submission_id \
+        JOIN assignments a ON a.id = s.assignment_id \
+        JOIN classes c ON c.id = a.class_id \
+        WHERE sd.dyno_id = d.id AND c.lecturer_id = ?1))";
+
+#[derive(QueryableByName)]
+struct Readable {
+    #[diesel(sql_type = Bool)]
+    readable: bool,
+}
+
+/// the dynotest is readable by `user_id`, see [`READABLE_DYNO`]
+#[inline]
+#[allow(unused)]
+pub fn is_readable(
+    conn: &mut DynoDBPooledConnection,
+    dyno_id: i64,
+    user_id: i64,
+    read_all: bool,
+) -> DynoResult<bool> {
+    diesel::sql_query(format!(
+        "SELECT EXISTS (SELECT 1 FROM dynos d WHERE d.id = ?3 AND {READABLE_DYNO}) AS readable"
+    ))
+    .bind::<BigInt, _>(user_id)
+    .bind::<Bool, _>(read_all)
+    .bind::<BigInt, _>(dyno_id)
+    .get_result::<Readable>(conn)
+    .map(|x| x.readable)
+    .map_err(DynoErr::database_error)
+}
+
+/// filter used when selecting many [`Dynos`] at once, every `None` field is ignored.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default)]
+pub struct DynoFilter {
+    pub user_id: Option<i64>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub verified: Option<bool>,
+}
 
 #[inline]
 #[allow(unused)]
@@ -78,24 +131,168 @@ pub fn insert(conn: &mut DynoDBPooledConnection, new: NewDynos) -> DynoResult<i6
         .map_err(DynoErr::database_error)
 }
 
+/// first [`Dynos`] that has the same `data_checksum`, limited to `user_id` when it is `Some`
 #[inline]
 #[allow(unused)]
-pub fn select_many(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<Vec<Dynos>> {
+pub fn select_by_checksum(
+    conn: &mut DynoDBPooledConnection,
+    checksum: &str,
+    user_id: Option<i64>,
+) -> DynoResult<Option<Dynos>> {
     use crate::schema::dynos;
-    dynos::table
-        .filter(dynos::dsl::user_id.eq(user_id))
+    let mut query = dynos::table
+        .filter(dynos::dsl::data_checksum.eq(checksum))
         .select(Dynos::as_select())
-        .get_results::<Dynos>(conn)
+        .order(dynos::dsl::id.asc())
+        .into_boxed();
+    if let Some(user_id) = user_id {
+        query = query.filter(dynos::dsl::user_id.eq(user_id));
+    }
+    query
+        .first(conn)
         .optional()
-        .map_err(DynoErr::database_error)?
-        .ok_or(DynoErr::database_error("Dynos record not exists in table"))
+        .map_err(DynoErr::database_error)
 }
 
+/// count of [`Dynos`] that reference the same stored data
 #[inline]
 #[allow(unused)]
-pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<Dynos>> {
+pub fn count_by_data_url(conn: &mut DynoDBPooledConnection, data_url: &str) -> DynoResult<i64> {
+    use crate::schema::dynos::dsl;
+    dsl::dynos
+        .filter(dsl::data_url.eq(data_url))
+        .count()
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// count and size of the stored data of a user, archived dynotest is counted separately
+#[allow(unused)]
+pub fn usage(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<StorageUsage> {
+    use crate::schema::dynos::dsl;
+    let sizes = dsl::dynos
+        .filter(dsl::user_id.eq(user_id))
+        .select((dsl::data_size, dsl::archived))
+        .load::<(i64, bool)>(conn)
+        .map_err(DynoErr::database_error)?;
+    Ok(sizes
+        .into_iter()
+        .fold(StorageUsage::default(), |mut usage, (size, archived)| {
+            if archived {
+                usage.archived_count += 1;
+                usage.archived_bytes += size;
+            } else {
+                usage.count += 1;
+                usage.bytes += size;
+            }
+            usage
+        }))
+}
+
+/// unverified and not archived [`Dynos`] that is created before `before`
+#[inline]
+#[allow(unused)]
+pub fn select_archivable(
+    conn: &mut DynoDBPooledConnection,
+    before: NaiveDateTime,
+) -> DynoResult<Vec<Dynos>> {
+    use crate::schema::dynos::dsl;
+    dsl::dynos
+        .filter(dsl::archived.eq(false))
+        .filter(dsl::verified.is_null().or(dsl::verified.eq(false)))
+        .filter(dsl::created_at.lt(before))
+        .select(Dynos::as_select())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// mark every [`Dynos`] that reference `data_url` as archived and point them into `new_data_url`
+#[inline]
+#[allow(unused)]
+pub fn archive(
+    conn: &mut DynoDBPooledConnection,
+    data_url: &str,
+    new_data_url: &str,
+) -> DynoResult<usize> {
+    use crate::schema::dynos::dsl;
+    diesel::update(dsl::dynos.filter(dsl::data_url.eq(data_url)))
+        .set((dsl::archived.eq(true), dsl::data_url.eq(new_data_url)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// `data_url` of every [`Dynos`] that has no size yet, created before the size is tracked
+#[inline]
+#[allow(unused)]
+pub fn select_unsized_data_urls(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<String>> {
+    use crate::schema::dynos::dsl;
+    dsl::dynos
+        .filter(dsl::data_size.eq(0))
+        .select(dsl::data_url)
+        .distinct()
+        .load::<String>(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// set the size of every [`Dynos`] that reference `data_url`, for record created before it is tracked
+#[inline]
+#[allow(unused)]
+pub fn update_data_size(
+    conn: &mut DynoDBPooledConnection,
+    data_url: &str,
+    data_size: i64,
+) -> DynoResult<usize> {
+    use crate::schema::dynos::dsl;
+    diesel::update(dsl::dynos.filter(dsl::data_url.eq(data_url)))
+        .set(dsl::data_size.eq(data_size))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn delete(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    use crate::schema::dynos::dsl;
+    diesel::delete(dsl::dynos.find(id))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn update_shared(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    user_id: i64,
+    shared: bool,
+) -> DynoResult<usize> {
+    use crate::schema::dynos::dsl;
+    diesel::update(dsl::dynos.filter(dsl::id.eq(id).and(dsl::user_id.eq(user_id))))
+        .set(dsl::shared.eq(shared))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn update_verified(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    verified: bool,
+) -> DynoResult<usize> {
+    use crate::schema::dynos::dsl;
+    diesel::update(dsl::dynos.find(id))
+        .set(dsl::verified.eq(verified))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_many(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<Vec<Dynos>> {
     use crate::schema::dynos;
     dynos::table
+        .filter(dynos::dsl::user_id.eq(user_id))
         .select(Dynos::as_select())
         .get_results::<Dynos>(conn)
         .optional()
@@ -103,19 +300,93 @@ pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<Dynos>> {
         .ok_or(DynoErr::database_error("Dynos record not exists in table"))
 }
 
+/// [`Dynos`] of `user_id`, or of every user when it is `None`, that has the `tag` when it is `Some`,
+/// at most `limit` when it is `Some`
 #[inline]
 #[allow(unused)]
-pub fn select_many_limit(
+pub fn select_listed(
     conn: &mut DynoDBPooledConnection,
-    user_id: i64,
-    limit: i64,
+    user_id: Option<i64>,
+    tag: Option<&str>,
+    limit: Option<i64>,
 ) -> DynoResult<Vec<Dynos>> {
-    use crate::schema::dynos;
+    use crate::schema::{dyno_tags, dynos};
+    let mut query = dynos::table.select(Dynos::as_select()).into_boxed();
+    if let Some(user_id) = user_id {
+        query = query.filter(dynos::dsl::user_id.eq(user_id));
+    }
+    if let Some(tag) = tag {
+        query = query.filter(
+            dynos::dsl::id.eq_any(
+                dyno_tags::table
+                    .filter(dyno_tags::dsl::tag.eq(tag))
+                    .select(dyno_tags::dsl::dyno_id),
+            ),
+        );
+    }
+    if let Some(limit) = limit {
+        query = query.limit(limit);
+    }
+    query.load(conn).map_err(DynoErr::database_error)
+}
 
+/// [`Dynos`] that is submitted to any assignment of the class, newest first, that has the `tag`
+/// when it is `Some`, at most `limit` when it is `Some`
+#[inline]
+#[allow(unused)]
+pub fn select_submitted_to_class(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    tag: Option<&str>,
+    limit: Option<i64>,
+) -> DynoResult<Vec<Dynos>> {
+    use crate::schema::{assignments, dyno_tags, dynos, submission_dynos, submissions};
+    let submitted = submission_dynos::table
+        .inner_join(submissions::table.inner_join(assignments::table))
+        .filter(assignments::dsl::class_id.eq(class_id))
+        .select(submission_dynos::dsl::dyno_id);
+    let mut query = dynos::table
+        .filter(dynos::dsl::id.eq_any(submitted))
+        .select(Dynos::as_select())
+        .order(dynos::dsl::id.desc())
+        .into_boxed();
+    if let Some(tag) = tag {
+        query = query.filter(
+            dynos::dsl::id.eq_any(
+                dyno_tags::table
+                    .filter(dyno_tags::dsl::tag.eq(tag))
+                    .select(dyno_tags::dsl::dyno_id),
+            ),
+        );
+    }
+    if let Some(limit) = limit {
+        query = query.limit(limit);
+    }
+    query.load(conn).map_err(DynoErr::database_error)
+}
+
+/// [`Dynos`] with the [`DynoInfo`] of every id in `ids`, missing id is skipped
+#[inline]
+#[allow(unused)]
+pub fn select_by_ids_with_info(
+    conn: &mut DynoDBPooledConnection,
+    ids: &[i64],
+) -> DynoResult<Vec<(Dynos, Option<DynoInfo>)>> {
+    use crate::schema::{dyno_info, dynos};
+    dynos::table
+        .left_join(dyno_info::table)
+        .filter(dynos::dsl::id.eq_any(ids))
+        .select((Dynos::as_select(), dyno_info::all_columns.nullable()))
+        .load::<(Dynos, Option<DynoInfo>)>(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<Dynos>> {
+    use crate::schema::dynos;
     dynos::table
-        .filter(dynos::dsl::user_id.eq(user_id))
         .select(Dynos::as_select())
-        .limit(limit)
         .get_results::<Dynos>(conn)
         .optional()
         .map_err(DynoErr::database_error)?
@@ -131,3 +402,37 @@ pub fn insert_many(conn: &mut DynoDBPooledConnection, new: Vec<NewDynos>) -> Dyn
         .execute(conn)
         .map_err(DynoErr::database_error)
 }
+
+#[inline]
+#[allow(unused)]
+/// # Returns.
+/// every [`Dynos`] that match the [`DynoFilter`] ordered by `id`, paired with its [`DynoInfo`] if any.
+pub fn select_filtered(
+    conn: &mut DynoDBPooledConnection,
+    filter: &DynoFilter,
+) -> DynoResult<Vec<(Dynos, Option<DynoInfo>)>> {
+    use crate::schema::{dyno_info, dynos};
+
+    let mut query = dynos::table
+        .left_join(dyno_info::table)
+        .select((Dynos::as_select(), dyno_info::all_columns.nullable()))
+        .order(dynos::dsl::id.asc())
+        .into_boxed();
+
+    if let Some(user_id) = filter.user_id {
+        query = query.filter(dynos::dsl::user_id.eq(user_id));
+    }
+    if let Some(from) = filter.from.and_then(|x| x.and_hms_opt(0, 0, 0)) {
+        query = query.filter(dynos::dsl::start.ge(from));
+    }
+    if let Some(to) = filter.to.and_then(|x| x.and_hms_opt(23, 59, 59)) {
+        query = query.filter(dynos::dsl::start.le(to));
+    }
+    if let Some(verified) = filter.verified {
+        query = query.filter(dynos::dsl::verified.eq(verified));
+    }
+
+    query
+        .load::<(Dynos, Option<DynoInfo>)>(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -0,0 +1,26 @@
+use crate::DynoDBPooledConnection;
+use diesel::RunQueryDsl;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use dyno_core::{DynoErr, DynoResult};
+
+/// every migration in `migrations/` at compile time, only compared with the applied migrations
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../migrations");
+
+/// simplest query to make sure the connection is usable
+#[inline]
+#[allow(unused)]
+pub fn ping(conn: &mut DynoDBPooledConnection) -> DynoResult<()> {
+    diesel::sql_query("SELECT 1")
+        .execute(conn)
+        .map(|_| ())
+        .map_err(DynoErr::database_error)
+}
+
+/// name of every migration that is not applied yet into the database
+#[inline]
+#[allow(unused)]
+pub fn pending_migrations(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<String>> {
+    conn.pending_migrations(MIGRATIONS)
+        .map(|pending| pending.iter().map(|x| x.name().to_string()).collect())
+        .map_err(DynoErr::database_error)
+}
@@ -61,6 +61,21 @@ pub fn select_many(
         .ok_or(DynoErr::database_error("Dynos record not exists in table"))
 }
 
+/// [`History`] of every user in `user_ids`, ex: member of a class
+#[inline]
+#[allow(unused)]
+pub fn select_by_users(
+    conn: &mut DynoDBPooledConnection,
+    user_ids: &[i64],
+) -> DynoResult<Vec<History>> {
+    dsl::histories
+        .select(History::as_select())
+        .filter(dsl::user_id.eq_any(user_ids))
+        .order(dsl::id.desc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
 #[inline]
 #[allow(unused)]
 pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<History>> {
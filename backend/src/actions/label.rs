@@ -0,0 +1,134 @@
+use crate::models::label::{DynoLabels, NewDynoMetadata, NewDynoTag};
+use crate::schema::{dyno_metadata, dyno_tags, dynos};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+/// [`DynoLabels`] of every dynotest in `dyno_ids`, in the same order
+#[allow(unused)]
+pub fn select_by_dynos(
+    conn: &mut DynoDBPooledConnection,
+    dyno_ids: &[i64],
+) -> DynoResult<Vec<DynoLabels>> {
+    let fields = dynos::table
+        .filter(dynos::dsl::id.eq_any(dyno_ids))
+        .select((dynos::dsl::id, dynos::dsl::title, dynos::dsl::notes))
+        .load::<(i64, Option<String>, Option<String>)>(conn)
+        .map_err(DynoErr::database_error)?;
+    let tags = dyno_tags::table
+        .filter(dyno_tags::dsl::dyno_id.eq_any(dyno_ids))
+        .select((dyno_tags::dsl::dyno_id, dyno_tags::dsl::tag))
+        .order(dyno_tags::dsl::tag.asc())
+        .load::<(i64, String)>(conn)
+        .map_err(DynoErr::database_error)?;
+    let metadata = dyno_metadata::table
+        .filter(dyno_metadata::dsl::dyno_id.eq_any(dyno_ids))
+        .select((
+            dyno_metadata::dsl::dyno_id,
+            dyno_metadata::dsl::key,
+            dyno_metadata::dsl::value,
+        ))
+        .load::<(i64, String, String)>(conn)
+        .map_err(DynoErr::database_error)?;
+
+    Ok(dyno_ids
+        .iter()
+        .filter_map(|&dyno_id| {
+            let (_, title, notes) = fields.iter().find(|(id, ..)| *id == dyno_id)?.clone();
+            Some(DynoLabels {
+                dyno_id,
+                title,
+                notes,
+                tags: tags
+                    .iter()
+                    .filter(|(id, _)| *id == dyno_id)
+                    .map(|(_, tag)| tag.clone())
+                    .collect(),
+                metadata: metadata
+                    .iter()
+                    .filter(|(id, ..)| *id == dyno_id)
+                    .map(|(_, key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// replace the title, notes, tags and metadata of the dynotest in one transaction
+#[allow(unused)]
+pub fn replace(
+    conn: &mut DynoDBPooledConnection,
+    dyno_id: i64,
+    DynoLabels {
+        title,
+        notes,
+        tags,
+        metadata,
+        ..
+    }: DynoLabels,
+) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        let updated = diesel::update(dynos::table.find(dyno_id))
+            .set((
+                dynos::dsl::title.eq(title),
+                dynos::dsl::notes.eq(notes),
+                dynos::dsl::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+        diesel::delete(dyno_tags::table.filter(dyno_tags::dsl::dyno_id.eq(dyno_id)))
+            .execute(conn)?;
+        diesel::delete(dyno_metadata::table.filter(dyno_metadata::dsl::dyno_id.eq(dyno_id)))
+            .execute(conn)?;
+        let tags = tags
+            .into_iter()
+            .map(|tag| NewDynoTag { dyno_id, tag })
+            .collect::<Vec<_>>();
+        diesel::insert_into(dyno_tags::table)
+            .values(tags)
+            .execute(conn)?;
+        let metadata = metadata
+            .into_iter()
+            .map(|(key, value)| NewDynoMetadata {
+                dyno_id,
+                key,
+                value,
+            })
+            .collect::<Vec<_>>();
+        diesel::insert_into(dyno_metadata::table)
+            .values(metadata)
+            .execute(conn)?;
+        Ok(updated)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+/// every distinct tag that is used in the dynotest of the user
+#[inline]
+#[allow(unused)]
+pub fn select_tags_by_user(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+) -> DynoResult<Vec<String>> {
+    dyno_tags::table
+        .inner_join(dynos::table)
+        .filter(dynos::dsl::user_id.eq(user_id))
+        .select(dyno_tags::dsl::tag)
+        .distinct()
+        .order(dyno_tags::dsl::tag.asc())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete every tag and metadata of the dynotest
+#[allow(unused)]
+pub fn delete_by_dyno(conn: &mut DynoDBPooledConnection, dyno_id: i64) -> DynoResult<usize> {
+    conn.transaction(|conn| {
+        let tags = diesel::delete(dyno_tags::table.filter(dyno_tags::dsl::dyno_id.eq(dyno_id)))
+            .execute(conn)?;
+        let metadata =
+            diesel::delete(dyno_metadata::table.filter(dyno_metadata::dsl::dyno_id.eq(dyno_id)))
+                .execute(conn)?;
+        Ok(tags + metadata)
+    })
+    .map_err(DynoErr::database_error)
+}
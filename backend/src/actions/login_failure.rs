@@ -0,0 +1,45 @@
+use crate::models::login_failure::{LoginFailure, NewLoginFailure};
+use crate::schema::login_failures::dsl;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::{DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewLoginFailure) -> DynoResult<usize> {
+    diesel::insert_into(dsl::login_failures)
+        .values(new)
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// latest failed login first, filtered by `nim` when it is `Some`
+#[inline]
+#[allow(unused)]
+pub fn select_many(
+    conn: &mut DynoDBPooledConnection,
+    nim: Option<&str>,
+    limit: i64,
+) -> DynoResult<Vec<LoginFailure>> {
+    let mut query = dsl::login_failures
+        .select(LoginFailure::as_select())
+        .order(dsl::id.desc())
+        .limit(limit)
+        .into_boxed();
+    if let Some(nim) = nim {
+        query = query.filter(dsl::nim.eq(nim));
+    }
+    query.load(conn).map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn delete_before(
+    conn: &mut DynoDBPooledConnection,
+    before: NaiveDateTime,
+) -> DynoResult<usize> {
+    diesel::delete(dsl::login_failures.filter(dsl::created_at.lt(before)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
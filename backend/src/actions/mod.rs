@@ -1,7 +1,46 @@
+pub mod audit_log;
 pub mod dyno;
 pub mod info;
 pub mod user;
 pub mod history;
+pub mod upload;
+pub mod share;
+pub mod reset;
+pub mod verification;
+pub mod login_failure;
+pub mod two_factor;
+pub mod api_key;
+pub mod permission;
+pub mod class;
+pub mod assignment;
+pub mod comment;
+pub mod label;
+pub mod search;
+pub mod health;
+
+/// run `f` in one `BEGIN IMMEDIATE` transaction, committed when it return `Ok` and rolled back
+/// otherwise. the write lock is taken before the first read, so a read then write sequence, ex:
+/// count the references then delete, can not interleave with other writer.
+pub fn transaction<T>(
+    conn: &mut crate::DynoDBPooledConnection,
+    f: impl FnOnce(&mut crate::DynoDBPooledConnection) -> dyno_core::DynoResult<T>,
+) -> dyno_core::DynoResult<T> {
+    use diesel::connection::{AnsiTransactionManager, TransactionManager};
+    use dyno_core::DynoErr;
+    AnsiTransactionManager::begin_transaction_sql(&mut **conn, "BEGIN IMMEDIATE")
+        .map_err(DynoErr::database_error)?;
+    match f(conn) {
+        Ok(value) => AnsiTransactionManager::commit_transaction(&mut **conn)
+            .map(|_| value)
+            .map_err(DynoErr::database_error),
+        Err(err) => {
+            if let Err(rollback) = AnsiTransactionManager::rollback_transaction(&mut **conn) {
+                dyno_core::log::error!("Failed to rollback transaction - {rollback}");
+            }
+            Err(err)
+        }
+    }
+}
 
 macro_rules! query_one {
     (FIND $table:ident WHERE ($filter:expr) as $types:ty [$conn:expr]) => {{
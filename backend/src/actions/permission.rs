@@ -0,0 +1,164 @@
+use crate::models::permission::{AccessRole, Permission, RolePermission, RolePermissions};
+use crate::schema::{role_permissions, users};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_access_role(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+) -> DynoResult<AccessRole> {
+    users::table
+        .find(user_id)
+        .select(users::dsl::access_role)
+        .first(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// `Admin` always has the permission, other role need it in `role_permissions`
+#[allow(unused)]
+pub fn has_permission(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    permission: Permission,
+) -> DynoResult<bool> {
+    let role = select_access_role(conn, user_id)?;
+    if role == AccessRole::Admin {
+        return Ok(true);
+    }
+    role_permissions::table
+        .find((role, permission))
+        .select(role_permissions::dsl::role)
+        .first::<AccessRole>(conn)
+        .optional()
+        .map_err(DynoErr::database_error)
+        .map(|x| x.is_some())
+}
+
+/// user with `user.manage` that is not `Admin` can not change an admin account, grant `Admin`
+/// or change the role of its own account, so the permission can not be escalated to `Admin`.
+/// `role` is the access role that is going to be set, `None` when only other data is changed.
+#[allow(unused)]
+pub fn check_account_change(
+    conn: &mut DynoDBPooledConnection,
+    actor_id: i64,
+    user_id: i64,
+    role: Option<AccessRole>,
+) -> DynoResult<()> {
+    if select_access_role(conn, actor_id)? == AccessRole::Admin {
+        return Ok(());
+    }
+    let current = select_access_role(conn, user_id)
+        .map_err(|_| DynoErr::not_found_error("User is not exists"))?;
+    let Some(role) = role else {
+        return match current {
+            AccessRole::Admin => Err(DynoErr::forbidden_error(
+                "Only admin can change an admin account",
+            )),
+            _ => Ok(()),
+        };
+    };
+    if role == current {
+        return Ok(());
+    }
+    if actor_id == user_id {
+        return Err(DynoErr::forbidden_error(
+            "Only admin can change the role of its own account",
+        ));
+    }
+    match (current, role) {
+        (AccessRole::Admin, _) | (_, AccessRole::Admin) => Err(DynoErr::forbidden_error(
+            "Only admin can grant or remove the admin role",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// permissions of every [`AccessRole`], `Admin` is listed with every permission
+#[allow(unused)]
+pub fn select_all(conn: &mut DynoDBPooledConnection) -> DynoResult<Vec<RolePermissions>> {
+    let granted = role_permissions::table
+        .select(RolePermission::as_select())
+        .load(conn)
+        .map_err(DynoErr::database_error)?;
+    Ok(AccessRole::ALL
+        .into_iter()
+        .map(|role| RolePermissions {
+            role,
+            permissions: match role {
+                AccessRole::Admin => Permission::ALL.to_vec(),
+                _ => granted
+                    .iter()
+                    .filter(|x| x.role == role)
+                    .map(|x| x.permission)
+                    .collect(),
+            },
+        })
+        .collect())
+}
+
+/// replace every permission of the `role` in one transaction
+#[allow(unused)]
+pub fn replace(
+    conn: &mut DynoDBPooledConnection,
+    role: AccessRole,
+    permissions: &[Permission],
+) -> DynoResult<usize> {
+    let news = permissions
+        .iter()
+        .map(|&permission| RolePermission { role, permission })
+        .collect::<Vec<_>>();
+    conn.transaction(|conn| {
+        diesel::delete(role_permissions::table.filter(role_permissions::dsl::role.eq(role)))
+            .execute(conn)?;
+        diesel::insert_into(role_permissions::table)
+            .values(&news)
+            .execute(conn)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+/// set the access role and keep the role in the token in sync
+#[inline]
+#[allow(unused)]
+pub fn update_access_role(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    role: AccessRole,
+) -> DynoResult<usize> {
+    use crate::models::role::ROLES;
+    diesel::update(users::table.find(user_id))
+        .set((
+            users::dsl::access_role.eq(role),
+            users::dsl::role.eq(ROLES(role.roles())),
+            users::dsl::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// access role follow the role when it is changed by the old admin form or approval,
+/// `Admin` is granted or removed, other access role is kept
+#[inline]
+#[allow(unused)]
+pub fn sync_access_role(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    role: dyno_core::role::Roles,
+) -> DynoResult<usize> {
+    let current = select_access_role(conn, user_id)?;
+    let synced = match AccessRole::from(role) {
+        AccessRole::Admin => AccessRole::Admin,
+        _ if current == AccessRole::Admin => AccessRole::Student,
+        _ => current,
+    };
+    if synced == current {
+        return Ok(0);
+    }
+    diesel::update(users::table.find(user_id))
+        .set(users::dsl::access_role.eq(synced))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -0,0 +1,57 @@
+use crate::actions::dyno::READABLE_DYNO;
+use crate::models::search::SearchResult;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Text};
+use dyno_core::{DynoErr, DynoResult};
+
+/// FTS5 query of the user input, every word is a quoted prefix term so an unfinished word
+/// still match and the syntax of FTS5 (`AND`, `NEAR`, `*`, ..) can not be injected
+pub fn fts_query(q: &str) -> Option<String> {
+    let terms = q
+        .split_whitespace()
+        .map(|x| x.replace('"', ""))
+        .filter(|x| !x.is_empty())
+        .map(|x| format!("\"{x}\"*"))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// ranked documents of the `search_index` that match the `query` built by [`fts_query`],
+/// a match in the `title` weigh more than in the `body`. only documents visible to `user_id` is
+/// matched before the `limit`: user is only itself unless `user_manage`, dynotest when it is
+/// readable, and dyno_info when any dynotest of it is readable.
+///
+/// NOTE: only implemented with the FTS5 table of sqlite, other backends need their own index
+#[inline]
+#[allow(unused)]
+pub fn search(
+    conn: &mut DynoDBPooledConnection,
+    query: &str,
+    user_id: i64,
+    read_all: bool,
+    user_manage: bool,
+    limit: i64,
+) -> DynoResult<Vec<SearchResult>> {
+    diesel::sql_query(format!(
+        "SELECT doc.kind AS kind, doc.ref_id AS id, search_index.title AS title, \
+            snippet(search_index, 1, '[', ']', '...', 8) AS snippet, \
+            -bm25(search_index, 10.0, 1.0) AS score \
+        FROM search_index JOIN search_documents doc ON doc.id = search_index.rowid \
+        WHERE search_index MATCH ?3 AND ( \
+            (doc.kind = 'user' AND (?4 OR doc.ref_id = ?1)) \
+            OR (doc.kind = 'dyno' AND EXISTS (SELECT 1 FROM dynos d \
+                WHERE d.id = doc.ref_id AND {READABLE_DYNO})) \
+            OR (doc.kind = 'info' AND EXISTS (SELECT 1 FROM dynos d \
+                WHERE d.info_id = doc.ref_id AND {READABLE_DYNO})) \
+        ) \
+        ORDER BY score DESC LIMIT ?5"
+    ))
+    .bind::<BigInt, _>(user_id)
+    .bind::<Bool, _>(read_all)
+    .bind::<Text, _>(query)
+    .bind::<Bool, _>(user_manage)
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+    .map_err(DynoErr::database_error)
+}
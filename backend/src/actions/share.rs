@@ -0,0 +1,82 @@
+use crate::models::share::{NewShareLink, ShareLink};
+use crate::schema::share_links::dsl;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<ShareLink> {
+    dsl::share_links
+        .find(id)
+        .select(ShareLink::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Share link is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_token_hash(
+    conn: &mut DynoDBPooledConnection,
+    token_hash: &str,
+) -> DynoResult<ShareLink> {
+    dsl::share_links
+        .filter(dsl::token_hash.eq(token_hash))
+        .select(ShareLink::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Share link is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_many(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    dyno_id: Option<i64>,
+) -> DynoResult<Vec<ShareLink>> {
+    let mut query = dsl::share_links
+        .filter(dsl::user_id.eq(user_id))
+        .select(ShareLink::as_select())
+        .order(dsl::id.desc())
+        .into_boxed();
+    if let Some(dyno_id) = dyno_id {
+        query = query.filter(dsl::dyno_id.eq(dyno_id));
+    }
+    query.load(conn).map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewShareLink) -> DynoResult<ShareLink> {
+    diesel::insert_into(dsl::share_links)
+        .values(new)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+        .and_then(|id| select_by_id(conn, id))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn revoke(conn: &mut DynoDBPooledConnection, id: i64, user_id: i64) -> DynoResult<ShareLink> {
+    diesel::update(dsl::share_links.filter(dsl::id.eq(id).and(dsl::user_id.eq(user_id))))
+        .set(dsl::revoked_at.eq(Utc::now().naive_utc()))
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Share link is not exists"))
+        .and_then(|id| select_by_id(conn, id))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn delete_by_dyno(conn: &mut DynoDBPooledConnection, dyno_id: i64) -> DynoResult<usize> {
+    diesel::delete(dsl::share_links.filter(dsl::dyno_id.eq(dyno_id)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -0,0 +1,128 @@
+use crate::models::two_factor::{LoginChallenge, NewLoginChallenge, NewRecoveryCode};
+use crate::schema::{login_challenges, recovery_codes};
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_challenge(
+    conn: &mut DynoDBPooledConnection,
+    token_hash: &str,
+) -> DynoResult<LoginChallenge> {
+    use login_challenges::dsl;
+    dsl::login_challenges
+        .filter(dsl::token_hash.eq(token_hash))
+        .select(LoginChallenge::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::unauthorized_error("Login challenge is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert_challenge(
+    conn: &mut DynoDBPooledConnection,
+    new: NewLoginChallenge,
+) -> DynoResult<i64> {
+    use login_challenges::dsl;
+    diesel::insert_into(dsl::login_challenges)
+        .values(new)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// mark the challenge as used, `false` when it is already used by another request
+#[inline]
+#[allow(unused)]
+pub fn mark_challenge_used(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<bool> {
+    use login_challenges::dsl;
+    diesel::update(dsl::login_challenges.filter(dsl::id.eq(id).and(dsl::used_at.is_null())))
+        .set(dsl::used_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map(|affected| affected > 0)
+        .map_err(DynoErr::database_error)
+}
+
+/// save `step` as the last accepted TOTP time step of the user, `false` when the same or a later
+/// step is already accepted, so a code can not be used again while it is still valid
+#[inline]
+#[allow(unused)]
+pub fn accept_totp_step(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    step: i64,
+) -> DynoResult<bool> {
+    use crate::schema::users::dsl;
+    let not_accepted = dsl::totp_last_step
+        .is_null()
+        .or(dsl::totp_last_step.lt(step));
+    diesel::update(dsl::users.find(user_id).filter(not_accepted))
+        .set(dsl::totp_last_step.eq(step))
+        .execute(conn)
+        .map(|affected| affected > 0)
+        .map_err(DynoErr::database_error)
+}
+
+/// replace every recovery code of the user
+#[inline]
+#[allow(unused)]
+pub fn replace_recovery_codes(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    news: Vec<NewRecoveryCode>,
+) -> DynoResult<usize> {
+    use recovery_codes::dsl;
+    conn.transaction(|conn| {
+        diesel::delete(dsl::recovery_codes.filter(dsl::user_id.eq(user_id))).execute(conn)?;
+        diesel::insert_into(dsl::recovery_codes)
+            .values(news)
+            .execute(conn)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn delete_recovery_codes(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<usize> {
+    use recovery_codes::dsl;
+    diesel::delete(dsl::recovery_codes.filter(dsl::user_id.eq(user_id)))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// mark the unused recovery code as used, `false` when it is not exists or already used
+#[inline]
+#[allow(unused)]
+pub fn use_recovery_code(
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+    code_hash: &str,
+) -> DynoResult<bool> {
+    use recovery_codes::dsl;
+    diesel::update(
+        dsl::recovery_codes.filter(
+            dsl::user_id
+                .eq(user_id)
+                .and(dsl::code_hash.eq(code_hash))
+                .and(dsl::used_at.is_null()),
+        ),
+    )
+    .set(dsl::used_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+    .map(|affected| affected > 0)
+    .map_err(DynoErr::database_error)
+}
+
+#[inline]
+#[allow(unused)]
+pub fn count_recovery_codes(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<i64> {
+    use recovery_codes::dsl;
+    dsl::recovery_codes
+        .filter(dsl::user_id.eq(user_id).and(dsl::used_at.is_null()))
+        .count()
+        .get_result(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -0,0 +1,89 @@
+use crate::models::upload::{NewUploadSession, UpdateUploadSession, UploadSession};
+use crate::schema::upload_sessions::dsl;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::{DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<UploadSession> {
+    dsl::upload_sessions
+        .find(id)
+        .select(UploadSession::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Upload session is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select(
+    conn: &mut DynoDBPooledConnection,
+    uuid: &str,
+    user_id: i64,
+) -> DynoResult<UploadSession> {
+    dsl::upload_sessions
+        .filter(dsl::uuid.eq(uuid).and(dsl::user_id.eq(user_id)))
+        .select(UploadSession::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Upload session is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(
+    conn: &mut DynoDBPooledConnection,
+    new: NewUploadSession,
+) -> DynoResult<UploadSession> {
+    diesel::insert_into(dsl::upload_sessions)
+        .values(new)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+        .and_then(|id| select_by_id(conn, id))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn update(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    updated: UpdateUploadSession,
+) -> DynoResult<UploadSession> {
+    diesel::update(dsl::upload_sessions.find(id))
+        .set(updated)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+        .and_then(|id| select_by_id(conn, id))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn select_expired(
+    conn: &mut DynoDBPooledConnection,
+    before: NaiveDateTime,
+) -> DynoResult<Vec<UploadSession>> {
+    dsl::upload_sessions
+        .filter(dsl::updated_at.lt(before))
+        .select(UploadSession::as_select())
+        .load(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// delete the session only if it is still not updated since `before`
+#[inline]
+#[allow(unused)]
+pub fn delete_expired(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    before: NaiveDateTime,
+) -> DynoResult<usize> {
+    diesel::delete(dsl::upload_sessions.filter(dsl::id.eq(id).and(dsl::updated_at.lt(before))))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
@@ -1,8 +1,15 @@
-use crate::models::user::{NewUser, UpdateUser, User};
+use crate::models::{
+    role::ROLES,
+    status::UserStatus,
+    user::{NewUser, UpdateUser, User},
+};
 use crate::schema::users::dsl;
 use crate::DynoDBPooledConnection;
 use diesel::prelude::*;
-use dyno_core::{DynoErr, DynoResult};
+use dyno_core::{
+    chrono::{NaiveDateTime, Utc},
+    DynoErr, DynoResult,
+};
 
 macro_rules! impl_generic_funcs {
     ($($by:ident: $types:ty),*) => {
@@ -42,6 +49,153 @@ macro_rules! impl_generic_funcs {
 }
 impl_generic_funcs!(uuid: &str, id: i64, nim: &str);
 
+/// delete the user, class membership, submission, comment and 2FA rows is deleted by the foreign
+/// keys. rows that do not cascade is kept: assignment created by the user is moved to the
+/// lecturer of its class, API key created by the user is moved to `admin_id`, and graded
+/// submission keep the grade without the grader. the user should not teach any class.
+#[allow(unused)]
+pub fn delete_with_references(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    admin_id: i64,
+) -> DynoResult<usize> {
+    use crate::schema::{api_keys, assignments, classes, submissions};
+    conn.transaction(|conn| {
+        let lecturers = assignments::table
+            .inner_join(classes::table)
+            .filter(assignments::dsl::created_by.eq(id))
+            .select((assignments::dsl::id, classes::dsl::lecturer_id))
+            .load::<(i64, i64)>(conn)?;
+        for (assignment_id, lecturer_id) in lecturers {
+            diesel::update(assignments::table.find(assignment_id))
+                .set(assignments::dsl::created_by.eq(lecturer_id))
+                .execute(conn)?;
+        }
+        diesel::update(api_keys::table.filter(api_keys::dsl::created_by.eq(id)))
+            .set(api_keys::dsl::created_by.eq(admin_id))
+            .execute(conn)?;
+        diesel::update(submissions::table.filter(submissions::dsl::graded_by.eq(id)))
+            .set(submissions::dsl::graded_by.eq(None::<i64>))
+            .execute(conn)?;
+        diesel::delete(dsl::users.find(id)).execute(conn)
+    })
+    .map_err(DynoErr::database_error)
+}
+
+#[allow(unused)]
+#[inline]
+pub fn find_by_email(conn: &mut DynoDBPooledConnection, email: &str) -> DynoResult<Option<User>> {
+    dsl::users
+        .filter(dsl::email.eq(email))
+        .select(User::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)
+}
+
+#[allow(unused)]
+#[inline]
+pub fn set_email_verified(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set(dsl::email_verified_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// account in the approval queue, pending registration and active account with role request.
+/// `status` select every account with the status instead, ex: list rejected account
+#[allow(unused)]
+pub fn select_approval_queue(
+    conn: &mut DynoDBPooledConnection,
+    status: Option<UserStatus>,
+) -> DynoResult<Vec<User>> {
+    let query = dsl::users
+        .select(User::as_select())
+        .order(dsl::id.asc())
+        .into_boxed();
+    let query = match status {
+        Some(status) => query.filter(dsl::status.eq(status)),
+        None => query.filter(
+            dsl::status.eq(UserStatus::Pending).or(dsl::status
+                .eq(UserStatus::Active)
+                .and(dsl::requested_role.is_not_null())),
+        ),
+    };
+    query.load(conn).map_err(DynoErr::database_error)
+}
+
+/// set the approval `status` and `role` of the account, pending role request is cleared
+#[allow(unused)]
+#[inline]
+pub fn update_approval(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    status: UserStatus,
+    role: ROLES,
+) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set((
+            dsl::status.eq(status),
+            dsl::role.eq(role),
+            dsl::requested_role.eq(None::<ROLES>),
+            dsl::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[allow(unused)]
+#[inline]
+pub fn update_requested_role(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    role: Option<ROLES>,
+) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set(dsl::requested_role.eq(role))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// save the TOTP secret of an enrollment that is not confirmed yet, TOTP stay disabled
+#[allow(unused)]
+#[inline]
+pub fn set_totp_secret(
+    conn: &mut DynoDBPooledConnection,
+    id: i64,
+    secret: &str,
+) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set((
+            dsl::totp_secret.eq(secret),
+            dsl::totp_enabled_at.eq(None::<NaiveDateTime>),
+            dsl::totp_last_step.eq(None::<i64>),
+        ))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[allow(unused)]
+#[inline]
+pub fn enable_totp(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set(dsl::totp_enabled_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
+#[allow(unused)]
+#[inline]
+pub fn disable_totp(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<usize> {
+    diesel::update(dsl::users.find(id))
+        .set((
+            dsl::totp_secret.eq(None::<String>),
+            dsl::totp_enabled_at.eq(None::<NaiveDateTime>),
+        ))
+        .execute(conn)
+        .map_err(DynoErr::database_error)
+}
+
 #[allow(unused)]
 #[inline]
 pub fn is_exists_by_id(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<bool> {
@@ -0,0 +1,53 @@
+use crate::models::verification::{EmailVerification, NewEmailVerification};
+use crate::schema::email_verifications::dsl;
+use crate::DynoDBPooledConnection;
+use diesel::prelude::*;
+use dyno_core::{chrono::Utc, DynoErr, DynoResult};
+
+#[inline]
+#[allow(unused)]
+pub fn select_by_token_hash(
+    conn: &mut DynoDBPooledConnection,
+    token_hash: &str,
+) -> DynoResult<EmailVerification> {
+    dsl::email_verifications
+        .filter(dsl::token_hash.eq(token_hash))
+        .select(EmailVerification::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DynoErr::database_error)?
+        .ok_or(DynoErr::not_found_error("Verification token is not exists"))
+}
+
+#[inline]
+#[allow(unused)]
+pub fn insert(conn: &mut DynoDBPooledConnection, new: NewEmailVerification) -> DynoResult<i64> {
+    diesel::insert_into(dsl::email_verifications)
+        .values(new)
+        .returning(dsl::id)
+        .get_result::<i64>(conn)
+        .map_err(DynoErr::database_error)
+}
+
+/// mark the verification as used, `false` when it is already used by another request
+#[inline]
+#[allow(unused)]
+pub fn mark_used(conn: &mut DynoDBPooledConnection, id: i64) -> DynoResult<bool> {
+    diesel::update(dsl::email_verifications.filter(dsl::id.eq(id).and(dsl::used_at.is_null())))
+        .set(dsl::used_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map(|affected| affected > 0)
+        .map_err(DynoErr::database_error)
+}
+
+/// mark every unused verification of the user as used, so only the latest token is valid
+#[inline]
+#[allow(unused)]
+pub fn invalidate_by_user(conn: &mut DynoDBPooledConnection, user_id: i64) -> DynoResult<usize> {
+    diesel::update(
+        dsl::email_verifications.filter(dsl::user_id.eq(user_id).and(dsl::used_at.is_null())),
+    )
+    .set(dsl::used_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+    .map_err(DynoErr::database_error)
+}
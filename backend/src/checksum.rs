@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use dyno_core::{DynoErr, DynoResult};
+use sha2::{Digest, Sha256};
+
+/// checksum of the stored dyno data (`data_checksum` of `dynos`), lowercase sha256 hex the same as
+/// [`dyno_core::crypto::checksum_from_bytes`] used by the desktop app. every upload, import and
+/// the storage audit compute it with this module, so the format can not drift between them.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Clone)]
+pub struct Checksum(Sha256);
+
+impl Checksum {
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.0.update(bytes);
+    }
+
+    pub fn finish(self) -> String {
+        hex::encode(self.0.finalize())
+    }
+}
+
+impl std::io::Write for Checksum {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// [`Checksum`] of the whole `bytes` in memory
+pub fn checksum_bytes(bytes: impl AsRef<[u8]>) -> String {
+    let mut checksum = Checksum::default();
+    checksum.update(bytes);
+    checksum.finish()
+}
+
+/// [`Checksum`] of a file on disk, read incrementally
+pub fn checksum_file(path: impl AsRef<Path>) -> DynoResult<String> {
+    let mut file = std::fs::File::open(path).map_err(DynoErr::internal_server_error)?;
+    let mut checksum = Checksum::default();
+    std::io::copy(&mut file, &mut checksum).map_err(DynoErr::internal_server_error)?;
+    Ok(checksum.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use dyno_core::crypto::checksum_from_bytes;
+
+    use super::*;
+
+    #[test]
+    fn same_as_checksum_from_bytes() {
+        let large = (0..=u8::MAX).cycle().take(100_000).collect::<Vec<u8>>();
+        for bytes in [&[][..], b"dynotests", &large] {
+            let expected = checksum_from_bytes(bytes);
+            assert_eq!(checksum_bytes(bytes), expected);
+
+            let mut streamed = Checksum::default();
+            for chunk in bytes.chunks(4096) {
+                streamed.update(chunk);
+            }
+            assert_eq!(streamed.finish(), expected);
+
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(bytes).unwrap();
+            assert_eq!(checksum_file(file.path()).unwrap(), expected);
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use dyno_core::log;
+use crate::models::permission::AccessRole;
+use dyno_core::{log, role::Roles};
 use std::path::PathBuf;
 use std::process::exit;
 
@@ -34,6 +35,18 @@ pub fn get_env_optional<S: AsRef<str>>(env: S) -> Option<String> {
 pub struct ServerConfig {
     pub secret: Option<Secrets>,
     pub jwt: Jwt,
+    pub upload: Upload,
+    pub storage: Storage,
+    pub jobs: Jobs,
+    pub quotas: Quotas,
+    pub mail: Mail,
+    pub registration: Registration,
+    pub rate_limit: RateLimit,
+    pub two_factor: TwoFactor,
+    /// bearer token required by `GET /metrics` when `DYNO_METRICS_TOKEN` is set
+    pub metrics_token: Option<String>,
+    /// `/api/health/ready` fail when the free space of the local storage or upload folder is below this
+    pub health_min_free_mb: u64,
 
     pub app_root_path: PathBuf,
     pub app_public_path: PathBuf,
@@ -56,9 +69,25 @@ impl ServerConfig {
             .parse()
             .expect("`DYNO_PORT` ENV should be numerical value");
 
+        let mail = Mail::init();
         Self {
             host,
             port,
+            upload: Upload::init(&app_public_path),
+            storage: Storage::init(&app_public_path),
+            jobs: Jobs::init(),
+            quotas: Quotas::init(),
+            registration: Registration::init(mail.is_enabled()),
+            mail,
+            rate_limit: RateLimit::init(),
+            two_factor: TwoFactor::init(),
+            metrics_token: get_env_optional("DYNO_METRICS_TOKEN").filter(|x| !x.is_empty()),
+            health_min_free_mb: get_env_optional("DYNO_HEALTH_MIN_FREE_MB")
+                .map(|x| {
+                    x.parse()
+                        .expect("`DYNO_HEALTH_MIN_FREE_MB` ENV should be numerical value")
+                })
+                .unwrap_or(256),
             app_root_path,
             app_public_path,
             database_url,
@@ -125,3 +154,449 @@ impl Jwt {
         }
     }
 }
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Upload {
+    /// maximum size in bytes of a single multipart field
+    pub max_field_size: u64,
+    /// maximum size in bytes of the whole multipart request
+    pub max_request_size: u64,
+    /// folder where uploaded data is staged before moved into the [`Storage`],
+    /// should be in the same filesystem as `Storage::local_path` so the rename is atomic.
+    pub tmp_path: PathBuf,
+    /// resumable upload session without any chunk for this many hours is removed by the cleanup job
+    pub session_expiry_hours: u64,
+}
+
+impl Upload {
+    fn init(app_public_path: &std::path::Path) -> Self {
+        let max_field_size = get_env_optional("DYNO_UPLOAD_MAX_FIELD_SIZE")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_UPLOAD_MAX_FIELD_SIZE` ENV should be numerical value")
+            })
+            .unwrap_or(32 * 1024 * 1024);
+        let max_request_size = get_env_optional("DYNO_UPLOAD_MAX_REQUEST_SIZE")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_UPLOAD_MAX_REQUEST_SIZE` ENV should be numerical value")
+            })
+            .unwrap_or(max_field_size + 1024 * 1024);
+        let tmp_path = get_env_optional("DYNO_UPLOAD_TMP_PATH")
+            .map(PathBuf::from)
+            .unwrap_or(app_public_path.join("dyno").join(".tmp"));
+        let session_expiry_hours = get_env_optional("DYNO_UPLOAD_SESSION_EXPIRY_HOURS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_UPLOAD_SESSION_EXPIRY_HOURS` ENV should be numerical value")
+            })
+            .unwrap_or(24);
+        Self {
+            max_field_size,
+            max_request_size,
+            tmp_path,
+            session_expiry_hours,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Storage {
+    /// root folder of the local storage, used when `s3` is `None`
+    pub local_path: PathBuf,
+    /// use S3 compatible storage when `DYNO_STORAGE=s3`
+    pub s3: Option<S3>,
+}
+
+impl Storage {
+    fn init(app_public_path: &std::path::Path) -> Self {
+        let local_path = get_env_optional("DYNO_STORAGE_LOCAL_PATH")
+            .map(PathBuf::from)
+            .unwrap_or(app_public_path.join("dyno"));
+        let s3 = get_env_optional("DYNO_STORAGE")
+            .is_some_and(|x| x.eq_ignore_ascii_case("s3"))
+            .then(S3::init);
+        Self { local_path, s3 }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct S3 {
+    pub bucket: String,
+    pub region: String,
+    /// custom endpoint for S3 compatible server, ex: `http://127.0.0.1:9000` for MinIO
+    pub endpoint: Option<String>,
+    pub access_key: String,
+    pub secret_key: String,
+    /// address bucket as `{endpoint}/{bucket}` instead of `{bucket}.{endpoint}`, needed by MinIO
+    pub path_style: bool,
+}
+
+impl S3 {
+    fn init() -> Self {
+        let bucket = get_env("DYNO_STORAGE_S3_BUCKET");
+        let region = get_env_optional("DYNO_STORAGE_S3_REGION").unwrap_or("us-east-1".to_owned());
+        let endpoint = get_env_optional("DYNO_STORAGE_S3_ENDPOINT");
+        let access_key = get_env("DYNO_STORAGE_S3_ACCESS_KEY");
+        let secret_key = get_env("DYNO_STORAGE_S3_SECRET_KEY");
+        let path_style = get_env_optional("DYNO_STORAGE_S3_PATH_STYLE")
+            .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+            .unwrap_or(endpoint.is_some());
+        Self {
+            bucket,
+            region,
+            endpoint,
+            access_key,
+            secret_key,
+            path_style,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Jobs {
+    /// hours between scheduled storage audit, `None` when `DYNO_AUDIT_INTERVAL_HOURS=0`
+    pub audit_interval_hours: Option<u64>,
+    /// scheduled audit also remove orphan files
+    pub audit_repair: bool,
+    /// hours between retention job, `None` when `DYNO_RETENTION_INTERVAL_HOURS=0`
+    pub retention_interval_hours: Option<u64>,
+    /// unverified dynotest older than this is archived, `None` disable the retention job
+    pub archive_unverified_months: Option<u32>,
+    /// failed login older than this many days is deleted by the retention job,
+    /// `None` when `DYNO_LOGIN_FAILURE_RETENTION_DAYS=0`
+    pub login_failure_retention_days: Option<u32>,
+    /// hours between expired upload session cleanup, `None` when `DYNO_UPLOAD_CLEANUP_INTERVAL_HOURS=0`
+    pub upload_cleanup_interval_hours: Option<u64>,
+}
+
+impl Jobs {
+    fn init() -> Self {
+        let audit_interval_hours = get_env_optional("DYNO_AUDIT_INTERVAL_HOURS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_AUDIT_INTERVAL_HOURS` ENV should be numerical value")
+            })
+            .unwrap_or(24);
+        let audit_repair = get_env_optional("DYNO_AUDIT_REPAIR")
+            .is_some_and(|x| x == "1" || x.eq_ignore_ascii_case("true"));
+        let retention_interval_hours = get_env_optional("DYNO_RETENTION_INTERVAL_HOURS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_RETENTION_INTERVAL_HOURS` ENV should be numerical value")
+            })
+            .unwrap_or(24);
+        let archive_unverified_months =
+            get_env_optional("DYNO_RETENTION_ARCHIVE_UNVERIFIED_MONTHS").map(|x| {
+                x.parse().expect(
+                    "`DYNO_RETENTION_ARCHIVE_UNVERIFIED_MONTHS` ENV should be numerical value",
+                )
+            });
+        let login_failure_retention_days = get_env_optional("DYNO_LOGIN_FAILURE_RETENTION_DAYS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_LOGIN_FAILURE_RETENTION_DAYS` ENV should be numerical value")
+            })
+            .unwrap_or(90);
+        let upload_cleanup_interval_hours = get_env_optional("DYNO_UPLOAD_CLEANUP_INTERVAL_HOURS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_UPLOAD_CLEANUP_INTERVAL_HOURS` ENV should be numerical value")
+            })
+            .unwrap_or(1);
+        Self {
+            audit_interval_hours: (audit_interval_hours > 0).then_some(audit_interval_hours),
+            audit_repair,
+            retention_interval_hours: (retention_interval_hours > 0)
+                .then_some(retention_interval_hours),
+            archive_unverified_months,
+            login_failure_retention_days: (login_failure_retention_days > 0)
+                .then_some(login_failure_retention_days),
+            upload_cleanup_interval_hours: (upload_cleanup_interval_hours > 0)
+                .then_some(upload_cleanup_interval_hours),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Quotas {
+    pub student: Quota,
+    pub lecturer: Quota,
+    pub technician: Quota,
+    pub admin: Quota,
+}
+
+impl Quotas {
+    fn init() -> Self {
+        // `DYNO_QUOTA_USER_*` is the default of every non admin access role
+        let user = Quota::init("USER", &Quota::default());
+        Self {
+            student: Quota::init("STUDENT", &user),
+            lecturer: Quota::init("LECTURER", &user),
+            technician: Quota::init("TECHNICIAN", &user),
+            admin: Quota::init("ADMIN", &Quota::default()),
+        }
+    }
+
+    pub fn for_role(&self, role: AccessRole) -> &Quota {
+        match role {
+            AccessRole::Student => &self.student,
+            AccessRole::Lecturer => &self.lecturer,
+            AccessRole::Technician => &self.technician,
+            AccessRole::Admin => &self.admin,
+        }
+    }
+}
+
+/// limits of not archived dynotest per user, `None` is unlimited
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Quota {
+    pub max_count: Option<i64>,
+    pub max_bytes: Option<i64>,
+}
+
+impl Quota {
+    /// read `DYNO_QUOTA_{role}_COUNT` and `DYNO_QUOTA_{role}_BYTES`, the limit that is not set is
+    /// taken from `default`
+    fn init(role: &str, default: &Quota) -> Self {
+        let parse = |env: String| {
+            get_env_optional(&env).map(|x| {
+                x.parse::<i64>()
+                    .unwrap_or_else(|_| panic!("`{env}` ENV should be numerical value"))
+            })
+        };
+        Self {
+            max_count: parse(format!("DYNO_QUOTA_{role}_COUNT")).or(default.max_count),
+            max_bytes: parse(format!("DYNO_QUOTA_{role}_BYTES")).or(default.max_bytes),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Mail {
+    /// `None` when `DYNO_SMTP_HOST` is not set
+    pub smtp: Option<Smtp>,
+    /// only log the recipient and subject when SMTP is not set, `DYNO_MAIL_LOG` for development
+    pub log: bool,
+    pub from: String,
+    /// url of the frontend used in the link inside the mail, never taken from the request host.
+    /// mail is disabled when it is not set
+    pub public_url: Option<String>,
+    pub reset_token_minutes: i64,
+}
+
+impl Mail {
+    fn init() -> Self {
+        let from = get_env_optional("DYNO_MAIL_FROM")
+            .unwrap_or("Dynotests <noreply@localhost>".to_owned());
+        let public_url = get_env_optional("DYNO_PUBLIC_URL")
+            .map(|x| x.trim_end_matches('/').to_owned())
+            .filter(|x| !x.is_empty());
+        let reset_token_minutes = get_env_optional("DYNO_RESET_TOKEN_MINUTES")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_RESET_TOKEN_MINUTES` ENV should be numerical value")
+            })
+            .unwrap_or(30);
+        Self {
+            smtp: get_env_optional("DYNO_SMTP_HOST").map(Smtp::init),
+            log: get_env_optional("DYNO_MAIL_LOG")
+                .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            from,
+            public_url,
+            reset_token_minutes,
+        }
+    }
+
+    /// mail can be sent: SMTP or `DYNO_MAIL_LOG` is set, and `DYNO_PUBLIC_URL` for the link
+    pub fn is_enabled(&self) -> bool {
+        (self.smtp.is_some() || self.log) && self.public_url.is_some()
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Smtp {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// use STARTTLS, disable it for local stand-in server like MailHog
+    pub tls: bool,
+}
+
+impl Smtp {
+    fn init(host: String) -> Self {
+        let port = get_env_optional("DYNO_SMTP_PORT")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_SMTP_PORT` ENV should be numerical value")
+            })
+            .unwrap_or(587);
+        let tls = get_env_optional("DYNO_SMTP_TLS")
+            .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+            .unwrap_or(true);
+        Self {
+            host,
+            port,
+            username: get_env_optional("DYNO_SMTP_USERNAME"),
+            password: get_env_optional("DYNO_SMTP_PASSWORD"),
+            tls,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct Registration {
+    /// self registered user need to open the link sent into the email before login,
+    /// default to [`Mail::is_enabled`]
+    pub verify_email: bool,
+    pub verify_token_hours: i64,
+    /// allowed domain of the email, ex: `student.polije.ac.id`, empty allow every domain
+    pub email_domains: Vec<String>,
+}
+
+impl Registration {
+    fn init(mail_enabled: bool) -> Self {
+        let verify_email = get_env_optional("DYNO_REGISTER_VERIFY_EMAIL")
+            .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+            .unwrap_or(mail_enabled);
+        let verify_token_hours = get_env_optional("DYNO_REGISTER_VERIFY_TOKEN_HOURS")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_REGISTER_VERIFY_TOKEN_HOURS` ENV should be numerical value")
+            })
+            .unwrap_or(48);
+        let email_domains = get_env_optional("DYNO_REGISTER_EMAIL_DOMAINS")
+            .map(|x| {
+                x.split(',')
+                    .map(|domain| domain.trim().trim_start_matches('@').to_lowercase())
+                    .filter(|domain| !domain.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            verify_email,
+            verify_token_hours,
+            email_domains,
+        }
+    }
+
+    pub fn is_allowed_email(&self, email: &str) -> bool {
+        if self.email_domains.is_empty() {
+            return true;
+        }
+        email.rsplit_once('@').is_some_and(|(_, domain)| {
+            let domain = domain.to_lowercase();
+            self.email_domains.iter().any(|allowed| *allowed == domain)
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct RateLimit {
+    /// failed attempts before the exponential backoff is applied
+    pub free_attempts: u32,
+    pub backoff_secs: u64,
+    pub max_backoff_secs: u64,
+    /// failed attempts before the nim or ip is locked for `lockout_minutes`
+    pub lockout_attempts: u32,
+    pub lockout_minutes: u64,
+    /// maximum request per ip in `window_secs` of the rate limited endpoints
+    pub requests: u32,
+    pub window_secs: u64,
+    /// use `X-Forwarded-For` as client ip, only enable it behind trusted reverse proxy
+    pub trust_proxy: bool,
+}
+
+impl RateLimit {
+    fn init() -> Self {
+        fn parse<T: std::str::FromStr>(env: &str, default: T) -> T {
+            get_env_optional(env)
+                .map(|x| {
+                    x.parse()
+                        .unwrap_or_else(|_| panic!("`{env}` ENV should be numerical value"))
+                })
+                .unwrap_or(default)
+        }
+        let trust_proxy = get_env_optional("DYNO_RATE_LIMIT_TRUST_PROXY")
+            .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        Self {
+            free_attempts: parse("DYNO_LOGIN_FREE_ATTEMPTS", 3),
+            backoff_secs: parse("DYNO_LOGIN_BACKOFF_SECS", 2),
+            max_backoff_secs: parse("DYNO_LOGIN_MAX_BACKOFF_SECS", 300),
+            lockout_attempts: parse("DYNO_LOGIN_LOCKOUT_ATTEMPTS", 10),
+            lockout_minutes: parse("DYNO_LOGIN_LOCKOUT_MINUTES", 15),
+            requests: parse("DYNO_RATE_LIMIT_REQUESTS", 30),
+            window_secs: parse("DYNO_RATE_LIMIT_WINDOW_SECS", 60),
+            trust_proxy,
+        }
+    }
+
+    #[inline]
+    pub fn lockout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.lockout_minutes * 60)
+    }
+
+    /// blocked duration after `failures` failed attempts, `None` when it is still allowed
+    pub fn backoff(&self, failures: u32) -> Option<std::time::Duration> {
+        if failures >= self.lockout_attempts {
+            return Some(self.lockout());
+        }
+        let exponent = failures.checked_sub(self.free_attempts)?.checked_sub(1)?;
+        let secs = self
+            .backoff_secs
+            .saturating_mul(1u64.checked_shl(exponent).unwrap_or(u64::MAX))
+            .min(self.max_backoff_secs);
+        Some(std::time::Duration::from_secs(secs))
+    }
+}
+
+#[derive(Debug, Default, Clone, dyno_core::serde::Deserialize, dyno_core::serde::Serialize)]
+#[serde(crate = "dyno_core::serde")]
+pub struct TwoFactor {
+    /// admin without TOTP should enroll it in the second login step before the token is issued
+    pub required_admin: bool,
+    /// issuer shown in the authenticator app
+    pub issuer: String,
+    /// validity of the second login step challenge
+    pub challenge_minutes: i64,
+    pub recovery_codes: usize,
+}
+
+impl TwoFactor {
+    fn init() -> Self {
+        let required_admin = get_env_optional("DYNO_2FA_REQUIRED_ADMIN")
+            .map(|x| x == "1" || x.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let issuer = get_env_optional("DYNO_2FA_ISSUER").unwrap_or("Dynotests".to_owned());
+        let challenge_minutes = get_env_optional("DYNO_2FA_CHALLENGE_MINUTES")
+            .map(|x| {
+                x.parse()
+                    .expect("`DYNO_2FA_CHALLENGE_MINUTES` ENV should be numerical value")
+            })
+            .unwrap_or(5);
+        Self {
+            required_admin,
+            issuer,
+            challenge_minutes,
+            recovery_codes: 10,
+        }
+    }
+
+    #[inline]
+    pub fn is_required(&self, role: &Roles) -> bool {
+        self.required_admin && role.is_admin()
+    }
+}
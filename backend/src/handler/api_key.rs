@@ -0,0 +1,157 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use dyno_core::{
+    chrono::{Duration, Utc},
+    ApiResponse, DynoErr, DynoResult,
+};
+
+use crate::{
+    actions::{self, api_key as api_key_actions, audit_log as audit_log_actions},
+    middlewares::{perm, Auditor, RequirePermission},
+    models::api_key::{ApiKey, ApiKeyCreated, ApiScopes, CreateApiKey, NewApiKey},
+    token,
+};
+
+/// characters of the key that is kept in plain text to recognize the key
+const KEY_PREFIX_LEN: usize = 12;
+
+/// # Api Key Endpoint `get_api_keys`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/api-keys`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `station.operate`
+/// RESPONSE(JSON)      => [Vec<'crate::models::api_key::ApiKeyResponse'>]
+/// -----------------------------------------------------------------
+#[get("/admin/api-keys")]
+pub async fn get_api_keys(
+    _: RequirePermission<perm::StationOperate>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| api_key_actions::select_all(&mut conn))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|keys| {
+        HttpResponse::Ok().json(ApiResponse::success(
+            keys.into_iter()
+                .map(ApiKey::into_response)
+                .collect::<Vec<_>>(),
+        ))
+    })
+}
+
+/// # Api Key Endpoint `create_api_key`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/api-keys`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `station.operate`
+/// BODY(JSON)          => ['crate::models::api_key::CreateApiKey']
+/// RESPONSE(JSON)      => ['crate::models::api_key::ApiKeyCreated']
+///
+/// the key is only shown in this response, the station send it in the `X-Api-Key` header.
+/// -----------------------------------------------------------------
+#[post("/admin/api-keys")]
+pub async fn create_api_key(
+    RequirePermission(session, ..): RequirePermission<perm::StationOperate>,
+    web::Json(create): web::Json<CreateApiKey>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let (name, station) = (create.name.trim(), create.station.trim());
+    if name.is_empty() || station.is_empty() {
+        return Err(DynoErr::bad_request_error(
+            "Api key name and station should not be empty",
+        ));
+    }
+    if create.scopes.is_empty() {
+        return Err(DynoErr::bad_request_error(
+            "Api key should have at least one scope",
+        ));
+    }
+    if create.expires_days.is_some_and(|days| days <= 0) {
+        return Err(DynoErr::bad_request_error(
+            "Api key expiration should be greater than zero days",
+        ));
+    }
+
+    let mut scopes = create.scopes;
+    scopes.sort_by_key(|x| x.as_str());
+    scopes.dedup();
+    let key = format!("dyno_{}", token::generate_token());
+    let new = NewApiKey {
+        name: name.to_owned(),
+        station: station.to_owned(),
+        key_prefix: key[..KEY_PREFIX_LEN].to_owned(),
+        key_hash: token::hash_token(&key),
+        scopes: ApiScopes(scopes).to_string(),
+        created_by: session.id,
+        expires_at: create
+            .expires_days
+            .map(|days| Utc::now().naive_utc() + Duration::days(days)),
+    };
+    let dbpool = data.db.clone();
+    let api_key = web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let id = api_key_actions::insert(conn, new)?;
+            let api_key = api_key_actions::select_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "api_key.create")
+                    .target("api_key", id)
+                    .details(&api_key.clone().into_response()),
+            )?;
+            Ok(api_key)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(ApiKeyCreated {
+        key,
+        api_key: api_key.into_response(),
+    })))
+}
+
+/// # Api Key Endpoint `revoke_api_key`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/api-keys/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `station.operate`
+/// RESPONSE(JSON)      => ['crate::models::api_key::ApiKeyResponse']
+/// -----------------------------------------------------------------
+#[delete("/admin/api-keys/{id}")]
+pub async fn revoke_api_key(
+    id: web::Path<i64>,
+    RequirePermission(session, ..): RequirePermission<perm::StationOperate>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            if !api_key_actions::revoke(conn, id)? {
+                return Err(DynoErr::not_found_error(
+                    "Api key is not exists or already revoked",
+                ));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "api_key.revoke")
+                    .target("api_key", id),
+            )?;
+            api_key_actions::select_by_id(conn, id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|api_key| HttpResponse::Ok().json(ApiResponse::success(api_key.into_response())))
+}
@@ -0,0 +1,202 @@
+use actix_web::{get, post, web, HttpResponse};
+use dyno_core::{role::Roles, serde, ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, permission as permission_actions,
+        user as user_actions,
+    },
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission},
+    models::{permission::AccessRole, role::ROLES, status::UserStatus, user::User},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryApproval {
+    pub status: Option<UserStatus>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ApproveAccount {
+    /// granted role, the requested role is only granted when it is passed here. default to
+    /// [`Roles::User`] for pending or rejected account and the current role for active account
+    pub role: Option<Roles>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RoleRequest {
+    pub role: Roles,
+}
+
+/// # Approval Endpoint `get_approvals`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/approvals?status={pending|active|rejected}`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => [Vec<'crate::models::user::ApprovalResponse'>]
+///
+/// without `status` the queue is pending account and active account with role request.
+/// -----------------------------------------------------------------
+#[get("/admin/approvals")]
+pub async fn get_approvals(
+    web::Query(QueryApproval { status }): web::Query<QueryApproval>,
+    _: RequirePermission<perm::UserManage>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| user_actions::select_approval_queue(&mut conn, status))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|users| {
+        HttpResponse::Ok().json(ApiResponse::success(
+            users
+                .into_iter()
+                .map(User::into_approval_response)
+                .collect::<Vec<_>>(),
+        ))
+    })
+}
+
+/// # Approval Endpoint `approve_account`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/approvals/{id}/approve`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// BODY(JSON)          => ['ApproveAccount']
+/// RESPONSE(JSON)      => ['crate::models::user::ApprovalResponse']
+///
+/// activate the account and grant the role, also used to accept role request of active account.
+/// role requested at registration is never granted implicitly, see [`ApproveAccount`].
+/// -----------------------------------------------------------------
+#[post("/admin/approvals/{id}/approve")]
+pub async fn approve_account(
+    id: web::Path<i64>,
+    web::Json(ApproveAccount { role }): web::Json<ApproveAccount>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let user = user_actions::find_by_id(conn, id)?;
+            let role = match (role, user.status) {
+                (Some(role), _) => ROLES(role),
+                (None, UserStatus::Active) => user.role,
+                (None, _) => ROLES(Roles::User),
+            };
+            permission_actions::check_account_change(
+                conn,
+                session.id,
+                id,
+                Some(AccessRole::from(role.into_inner())),
+            )?;
+            user_actions::update_approval(conn, id, UserStatus::Active, role)?;
+            permission_actions::sync_access_role(conn, id, role.into_inner())?;
+            let after = user_actions::find_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "user.approve")
+                    .target("user", id)
+                    .diff(Some(&user), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|user| HttpResponse::Ok().json(ApiResponse::success(user.into_approval_response())))
+}
+
+/// # Approval Endpoint `reject_account`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/approvals/{id}/reject`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => ['crate::models::user::ApprovalResponse']
+///
+/// pending account is rejected and can not login, active account only has the role request removed.
+/// -----------------------------------------------------------------
+#[post("/admin/approvals/{id}/reject")]
+pub async fn reject_account(
+    id: web::Path<i64>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let user = user_actions::find_by_id(conn, id)?;
+            match user.status {
+                UserStatus::Active => user_actions::update_requested_role(conn, id, None)?,
+                _ => user_actions::update_approval(conn, id, UserStatus::Rejected, user.role)?,
+            };
+            let after = user_actions::find_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "user.reject")
+                    .target("user", id)
+                    .diff(Some(&user), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|user| HttpResponse::Ok().json(ApiResponse::success(user.into_approval_response())))
+}
+
+/// # Approval Endpoint `request_role`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/me/role`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['RoleRequest']
+/// RESPONSE(JSON)      => ['crate::models::user::ApprovalResponse']
+///
+/// the role is only granted after it is approved by admin.
+/// -----------------------------------------------------------------
+#[post("/me/role")]
+pub async fn request_role(
+    web::Json(RoleRequest { role }): web::Json<RoleRequest>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let requested = (role != session.role).then_some(ROLES(role));
+            user_actions::update_requested_role(conn, session.id, requested)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "user.request_role")
+                    .target("user", session.id)
+                    .details(&requested),
+            )?;
+            user_actions::find_by_id(conn, session.id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|user| HttpResponse::Ok().json(ApiResponse::success(user.into_approval_response())))
+}
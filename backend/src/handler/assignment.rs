@@ -0,0 +1,559 @@
+use actix_web::{delete, get, patch, post, web, HttpResponse};
+use dyno_core::{serde, ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{
+        self, assignment as assignment_actions, audit_log as audit_log_actions,
+        class as class_actions, dyno as dyno_actions,
+    },
+    handler::dyno::FileType,
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission},
+    models::{
+        assignment::{
+            Assignment, GradeSubmission, NewAssignment, NewSubmission, SubmitAssignment,
+            UpdateAssignment,
+        },
+        class::ClassMemberResponse,
+    },
+    DynoDBPooledConnection,
+};
+
+/// who is accessing the assignment of a class
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ClassAccess {
+    /// lecturer of the class or user with `user.manage` permission
+    Manager,
+    Member,
+}
+
+fn class_access(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    user_id: i64,
+    is_manager: bool,
+) -> DynoResult<ClassAccess> {
+    let class = class_actions::select_by_id(conn, class_id)?;
+    if is_manager || class.lecturer_id == user_id {
+        Ok(ClassAccess::Manager)
+    } else if class_actions::is_member(conn, class_id, user_id)? {
+        Ok(ClassAccess::Member)
+    } else {
+        Err(DynoErr::forbidden_error(
+            "Assignment is only accessible by the lecturer and members of the class",
+        ))
+    }
+}
+
+fn manage_access(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    user_id: i64,
+    is_manager: bool,
+) -> DynoResult<()> {
+    match class_access(conn, class_id, user_id, is_manager)? {
+        ClassAccess::Manager => Ok(()),
+        ClassAccess::Member => Err(DynoErr::forbidden_error(
+            "Assignment can only be managed by the lecturer of the class",
+        )),
+    }
+}
+
+/// check the submitted dynotests against the requirement of the assignment
+fn check_requirement(
+    conn: &mut DynoDBPooledConnection,
+    assignment: &Assignment,
+    user_id: i64,
+    dyno_ids: &[i64],
+) -> DynoResult<()> {
+    if (dyno_ids.len() as i32) < assignment.min_runs {
+        return Err(DynoErr::bad_request_error(format!(
+            "Assignment require at least {} dynotest runs",
+            assignment.min_runs
+        )));
+    }
+    let dynos = dyno_actions::select_by_ids_with_info(conn, dyno_ids)?;
+    if dynos.len() != dyno_ids.len() {
+        return Err(DynoErr::bad_request_error(
+            "Some of the submitted dynotests is not exists",
+        ));
+    }
+    for (dyno, info) in dynos {
+        if dyno.user_id != user_id {
+            return Err(DynoErr::forbidden_error(format!(
+                "Dynotest {} is not owned by the user",
+                dyno.id
+            )));
+        }
+        let cc = info.as_ref().and_then(|x| x.cc);
+        if assignment.engine_cc.is_some_and(|x| cc != Some(x)) {
+            return Err(DynoErr::bad_request_error(format!(
+                "Dynotest {} is not a {}cc engine",
+                dyno.id,
+                assignment.engine_cc.unwrap_or_default()
+            )));
+        }
+        let stroke = info.as_ref().and_then(|x| x.stroke);
+        if assignment.engine_stroke.is_some_and(|x| stroke != Some(x)) {
+            return Err(DynoErr::bad_request_error(format!(
+                "Dynotest {} is not a {}-stroke engine",
+                dyno.id,
+                assignment.engine_stroke.unwrap_or_default()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// # Assignment Endpoint `get_assignments`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/assignments`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer, member of the class or `user.manage`
+/// RESPONSE(JSON)      => [Vec<'crate::models::assignment::Assignment'>]
+/// -----------------------------------------------------------------
+#[get("/classes/{id}/assignments")]
+pub async fn get_assignments(
+    id: web::Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let class_id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        class_access(&mut conn, class_id, session.id, is_manager)?;
+        assignment_actions::select_by_class(&mut conn, class_id)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|assignments| HttpResponse::Ok().json(ApiResponse::success(assignments)))
+}
+
+/// # Assignment Endpoint `create_assignment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/assignments`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// BODY(JSON)          => ['crate::models::assignment::NewAssignment']
+/// RESPONSE(JSON)      => ['crate::models::assignment::Assignment']
+/// -----------------------------------------------------------------
+#[post("/classes/{id}/assignments")]
+pub async fn create_assignment(
+    id: web::Path<i64>,
+    web::Json(mut new): web::Json<NewAssignment>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if new.title.trim().is_empty() {
+        return Err(DynoErr::bad_request_error(
+            "Assignment title should not be empty",
+        ));
+    }
+    if new.min_runs < 1 {
+        return Err(DynoErr::bad_request_error(
+            "Assignment minimum runs should be at least 1",
+        ));
+    }
+    new.class_id = id.into_inner();
+    new.created_by = session.id;
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            manage_access(conn, new.class_id, session.id, is_manager)?;
+            let id = assignment_actions::insert(conn, new)?;
+            let assignment = assignment_actions::select_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "assignment.create")
+                    .target("assignment", id)
+                    .diff(None::<&Assignment>, Some(&assignment)),
+            )?;
+            Ok(assignment)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|assignment| HttpResponse::Ok().json(ApiResponse::success(assignment)))
+}
+
+/// # Assignment Endpoint `update_assignment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/assignments/{id}`
+/// GUARD               => `PATCH`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// BODY(JSON)          => ['crate::models::assignment::UpdateAssignment']
+/// RESPONSE(JSON)      => ['crate::models::assignment::Assignment']
+/// -----------------------------------------------------------------
+#[patch("/assignments/{id}")]
+pub async fn update_assignment(
+    id: web::Path<i64>,
+    web::Json(updated): web::Json<UpdateAssignment>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if updated.min_runs.is_some_and(|x| x < 1) {
+        return Err(DynoErr::bad_request_error(
+            "Assignment minimum runs should be at least 1",
+        ));
+    }
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let assignment = assignment_actions::select_by_id(conn, id)?;
+            manage_access(conn, assignment.class_id, session.id, is_manager)?;
+            assignment_actions::update(conn, id, updated)?;
+            let after = assignment_actions::select_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "assignment.update")
+                    .target("assignment", id)
+                    .diff(Some(&assignment), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|assignment| HttpResponse::Ok().json(ApiResponse::success(assignment)))
+}
+
+/// # Assignment Endpoint `delete_assignment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/assignments/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// RESPONSE(JSON)      => `id` of deleted assignment
+///
+/// every submission of the assignment is deleted, the submitted dynotests is kept.
+/// -----------------------------------------------------------------
+#[delete("/assignments/{id}")]
+pub async fn delete_assignment(
+    id: web::Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let assignment = assignment_actions::select_by_id(conn, id)?;
+            manage_access(conn, assignment.class_id, session.id, is_manager)?;
+            assignment_actions::delete(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "assignment.delete")
+                    .target("assignment", id)
+                    .diff(Some(&assignment), None::<&Assignment>),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(id)))
+}
+
+/// # Assignment Endpoint `get_submissions`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/assignments/{id}/submissions`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer, member of the class or `user.manage`
+/// RESPONSE(JSON)      => [Vec<'crate::models::assignment::SubmissionResponse'>]
+///
+/// every submission for the lecturer, member of the class only get their own submission.
+/// -----------------------------------------------------------------
+#[get("/assignments/{id}/submissions")]
+pub async fn get_submissions(
+    id: web::Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let assignment = assignment_actions::select_by_id(&mut conn, id)?;
+        let user_id = match class_access(&mut conn, assignment.class_id, session.id, is_manager)? {
+            ClassAccess::Manager => None,
+            ClassAccess::Member => Some(session.id),
+        };
+        assignment_actions::select_submissions(&mut conn, id, user_id)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|submissions| HttpResponse::Ok().json(ApiResponse::success(submissions)))
+}
+
+/// # Assignment Endpoint `submit_assignment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/assignments/{id}/submissions`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] member of the class
+/// BODY(JSON)          => ['crate::models::assignment::SubmitAssignment']
+/// RESPONSE(JSON)      => ['crate::models::assignment::SubmissionResponse']
+///
+/// submitting again before the deadline replace the previous submission,
+/// graded submission can not be replaced.
+/// -----------------------------------------------------------------
+#[post("/assignments/{id}/submissions")]
+pub async fn submit_assignment(
+    id: web::Path<i64>,
+    web::Json(SubmitAssignment {
+        mut dyno_ids,
+        analysis,
+    }): web::Json<SubmitAssignment>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if analysis.trim().is_empty() {
+        return Err(DynoErr::bad_request_error(
+            "Submission analysis should not be empty",
+        ));
+    }
+    dyno_ids.sort_unstable();
+    dyno_ids.dedup();
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let assignment = assignment_actions::select_by_id(conn, id)?;
+            if !class_actions::is_member(conn, assignment.class_id, session.id)? {
+                return Err(DynoErr::forbidden_error(
+                    "Assignment can only be submitted by members of the class",
+                ));
+            }
+            if assignment.is_closed() {
+                return Err(DynoErr::bad_request_error(
+                    "Assignment deadline has already passed",
+                ));
+            }
+            let before = assignment_actions::select_submission(conn, id, session.id)?;
+            if before.as_ref().is_some_and(|x| x.grade.is_some()) {
+                return Err(DynoErr::bad_request_error(
+                    "Submission is already graded and can not be replaced",
+                ));
+            }
+            check_requirement(conn, &assignment, session.id, &dyno_ids)?;
+
+            let new = NewSubmission {
+                assignment_id: id,
+                user_id: session.id,
+                analysis,
+            };
+            assignment_actions::submit(conn, new, dyno_ids)?;
+            let submission = assignment_actions::select_submissions(conn, id, Some(session.id))?
+                .pop()
+                .ok_or(DynoErr::not_found_error("Submission is not exists"))?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "submission.submit")
+                    .target("submission", submission.submission.id)
+                    .diff(before.as_ref(), Some(&submission.submission)),
+            )?;
+            Ok(submission)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|submission| HttpResponse::Ok().json(ApiResponse::success(submission)))
+}
+
+/// # Assignment Endpoint `grade_submission`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/submissions/{id}/grade`
+/// GUARD               => `PATCH`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// BODY(JSON)          => ['crate::models::assignment::GradeSubmission']
+/// RESPONSE(JSON)      => ['crate::models::assignment::Submission']
+/// -----------------------------------------------------------------
+#[patch("/submissions/{id}/grade")]
+pub async fn grade_submission(
+    id: web::Path<i64>,
+    web::Json(mut graded): web::Json<GradeSubmission>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if !(0.0..=100.0).contains(&graded.grade) {
+        return Err(DynoErr::bad_request_error(
+            "Grade should be in range of 0 - 100",
+        ));
+    }
+    graded.comment = graded
+        .comment
+        .map(|x| x.trim().to_owned())
+        .filter(|x| !x.is_empty());
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let submission = assignment_actions::select_submission_by_id(conn, id)?;
+            let assignment = assignment_actions::select_by_id(conn, submission.assignment_id)?;
+            manage_access(conn, assignment.class_id, session.id, is_manager)?;
+            assignment_actions::grade(conn, id, session.id, graded)?;
+            let after = assignment_actions::select_submission_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "submission.grade")
+                    .target("submission", id)
+                    .diff(Some(&submission), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|submission| HttpResponse::Ok().json(ApiResponse::success(submission)))
+}
+
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct GradeExportQueries {
+    /// only [`FileType::Csv`] and [`FileType::Excel`]
+    pub tp: FileType,
+}
+
+/// one row per member of the class with the grade of every assignment and the average
+fn grade_rows(
+    assignments: &[Assignment],
+    members: &[ClassMemberResponse],
+    grades: &[(i64, i64, Option<f32>)],
+) -> Vec<Vec<String>> {
+    let mut header = vec!["NIM".to_owned(), "Name".to_owned(), "Group".to_owned()];
+    header.extend(assignments.iter().map(|x| x.title.clone()));
+    header.push("Average".to_owned());
+
+    let mut rows = vec![header];
+    for member in members {
+        let mut row = vec![
+            member.nim.clone(),
+            member.name.clone(),
+            member.group_name.clone().unwrap_or_default(),
+        ];
+        let mut total = 0.0;
+        for assignment in assignments {
+            let grade = grades
+                .iter()
+                .find(|(a, u, _)| *a == assignment.id && *u == member.user_id)
+                .and_then(|(.., g)| *g);
+            total += grade.unwrap_or_default();
+            row.push(grade.map(|x| x.to_string()).unwrap_or_default());
+        }
+        let average = match assignments.len() {
+            0 => String::new(),
+            n => format!("{:.2}", total / n as f32),
+        };
+        row.push(average);
+        rows.push(row);
+    }
+    rows
+}
+
+/// prefix `'` to the text cell that a spreadsheet would evaluate as formula, NIM, name, group and
+/// assignment title are entered by users. numbers (grades and average) are kept as is.
+fn csv_cell(cell: String) -> String {
+    match cell.chars().next() {
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') if cell.parse::<f64>().is_err() => {
+            format!("'{cell}")
+        }
+        _ => cell,
+    }
+}
+
+fn grade_csv(rows: Vec<Vec<String>>) -> DynoResult<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer
+            .write_record(row.into_iter().map(csv_cell))
+            .map_err(DynoErr::serialize_error)?;
+    }
+    writer.into_inner().map_err(DynoErr::serialize_error)
+}
+
+fn grade_excel(rows: Vec<Vec<String>>) -> DynoResult<Vec<u8>> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Grades").map_err(DynoErr::serialize_error)?;
+    for (r, row) in rows.into_iter().enumerate() {
+        for (c, cell) in row.into_iter().enumerate() {
+            let written = match cell.parse::<f64>() {
+                Ok(number) if r > 0 && c > 2 => sheet.write_number(r as _, c as _, number),
+                _ => sheet.write_string(r as _, c as _, cell),
+            };
+            written.map_err(DynoErr::serialize_error)?;
+        }
+    }
+    workbook.save_to_buffer().map_err(DynoErr::serialize_error)
+}
+
+/// # Assignment Endpoint `export_grades`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/grades{query}` [query = `GradeExportQueries`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// BODY(FILE)          => `.csv` or `.xlsx` of the class gradebook
+/// -----------------------------------------------------------------
+#[get("/classes/{id}/grades")]
+pub async fn export_grades(
+    id: web::Path<i64>,
+    web::Query(GradeExportQueries { tp }): web::Query<GradeExportQueries>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if !matches!(tp, FileType::Csv | FileType::Excel) {
+        return Err(DynoErr::bad_request_error(
+            "Grades can only be exported as `csv` or `excel`",
+        ));
+    }
+    let class_id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    let (class, bytes) = web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        manage_access(&mut conn, class_id, session.id, is_manager)?;
+        let class = class_actions::select_by_id(&mut conn, class_id)?;
+        let assignments = assignment_actions::select_by_class(&mut conn, class_id)?;
+        let members = class_actions::select_members(&mut conn, class_id)?;
+        let grades = assignment_actions::select_grades(&mut conn, class_id)?;
+        let rows = grade_rows(&assignments, &members, &grades);
+        let bytes = match tp {
+            FileType::Excel => grade_excel(rows),
+            _ => grade_csv(rows),
+        }?;
+        Ok::<_, DynoErr>((class, bytes))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    Ok(tp.response(
+        format!("grades-{}-{}", class.id, class.semester.replace('/', "-")),
+        bytes,
+    ))
+}
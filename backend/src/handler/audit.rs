@@ -0,0 +1,62 @@
+use actix_web::{get, post, web, HttpResponse};
+use dyno_core::{serde, ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    jobs::audit,
+    middlewares::{perm, Auditor, RequirePermission},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct QueryAudit {
+    /// remove orphan files after the audit
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// # Admin Endpoint `start_audit`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/audit?repair={bool}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `station.operate`
+/// RESPONSE(JSON)      => `202 Accepted`, the report is available in `GET /api/admin/audit`
+///
+/// -----------------------------------------------------------------
+#[post("/admin/audit")]
+pub async fn start_audit(
+    web::Query(QueryAudit { repair }): web::Query<QueryAudit>,
+    RequirePermission(session, ..): RequirePermission<perm::StationOperate>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.get_ref().clone();
+    audit::try_start(&state)?;
+    let entry = auditor
+        .entry(Some(session.id), "storage.audit")
+        .details(&QueryAudit { repair });
+    super::audit_log::record(&data, entry).await?;
+    actix_web::rt::task::spawn_blocking(move || audit::run_started(&state, repair));
+    Ok(HttpResponse::Accepted().json(ApiResponse::success("Storage audit is started")))
+}
+
+/// # Admin Endpoint `get_audit`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/audit`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `station.operate`
+/// RESPONSE(JSON)      => ['crate::jobs::audit::AuditStatus']
+///
+/// -----------------------------------------------------------------
+#[get("/admin/audit")]
+pub async fn get_audit(
+    _: RequirePermission<perm::StationOperate>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let status = data
+        .audit
+        .lock()
+        .map_err(|_| DynoErr::internal_server_error("Audit status lock is poisoned"))?
+        .clone();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(status)))
+}
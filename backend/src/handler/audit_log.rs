@@ -0,0 +1,50 @@
+use actix_web::{get, web, HttpResponse};
+use dyno_core::{ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::audit_log as audit_log_actions,
+    middlewares::RequireAdmin,
+    models::audit_log::{AuditLogFilter, NewAuditLog},
+};
+
+/// write the entry outside of the `web::block` of the handler, ex: after the login is succeed
+pub(crate) async fn record(data: &crate::ServerState, entry: NewAuditLog) -> DynoResult<i64> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| audit_log_actions::insert(&mut conn, entry))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+}
+
+/// # Audit Log Endpoint `get_audit_log`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/audit-log{query}` [query = `AuditLogFilter`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequireAdmin`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::audit_log::AuditLogResponse'>]
+///
+/// every administrative and data-changing action, newest first, 50 per `page` by default.
+/// only admin access role can read it, the entries contain the ip and changes of every user.
+/// not to be confused with the storage audit in `/api/admin/audit`.
+/// -----------------------------------------------------------------
+#[get("/admin/audit-log")]
+pub async fn get_audit_log(
+    web::Query(filter): web::Query<AuditLogFilter>,
+    _: RequireAdmin,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| audit_log_actions::select_filtered(&mut conn, &filter))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|logs| HttpResponse::Ok().json(ApiResponse::success(logs)))
+}
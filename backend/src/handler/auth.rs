@@ -1,10 +1,33 @@
 use crate::actions;
-use crate::middlewares::JwtUserMiddleware;
-use crate::{actions::user as user_actions, models::user::NewUser};
+use crate::mailer::Mail;
+use crate::middlewares::{
+    client_ip, too_many_requests, ApiKeyMiddleware, Auditor, JwtUserMiddleware, RateLimit,
+};
+use crate::models::{
+    api_key::ApiScope,
+    login_failure::NewLoginFailure,
+    reset::{ForgotPassword, NewPasswordReset, ResetPassword},
+    status::UserStatus,
+    two_factor::{NewLoginChallenge, TwoFactorChallenge},
+    user::{UpdateUser, User},
+    verification::{NewEmailVerification, ResendVerification, VerifyEmail},
+};
+use crate::token;
+use crate::{
+    actions::{
+        audit_log as audit_log_actions, login_failure as login_failure_actions,
+        reset as reset_actions, two_factor as two_factor_actions, user as user_actions,
+        verification as verification_actions,
+    },
+    models::user::NewUser,
+};
+use std::sync::OnceLock;
+
 use actix_web::cookie::{self, Cookie};
-use actix_web::http::header;
+use actix_web::http::{header, StatusCode};
 use actix_web::{get, post, HttpRequest};
 use actix_web::{web, HttpResponse};
+use dyno_core::chrono::{Duration, Utc};
 use dyno_core::crypto::TokenDetails;
 use dyno_core::DynoResult;
 use dyno_core::{
@@ -15,67 +38,293 @@ use dyno_core::{
 
 const RESET_AGE_DUR: cookie::time::Duration = cookie::time::Duration::new(-1, 0);
 
-#[post("/auth/register")]
+/// hash verified against the password of unknown nim in [`login_user`]
+fn dummy_hash() -> DynoResult<&'static str> {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    if let Some(hash) = DUMMY_HASH.get() {
+        return Ok(hash);
+    }
+    let hash = dyno_core::crypto::hash_password(token::generate_token())?;
+    Ok(DUMMY_HASH.get_or_init(|| hash))
+}
+
+/// `503 Service Unavailable` of the endpoint that send mail while mail is disabled
+fn mail_unavailable() -> HttpResponse {
+    super::error_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Mail is not configured on this server, please contact the admin",
+    )
+}
+
+/// mail of the verification link `/verify?token=` to the user, previous link is invalidated.
+/// the link is always built from `DYNO_PUBLIC_URL`, never from the request host.
+fn verification_mail(
+    state: &crate::ServerState,
+    public_url: &str,
+    conn: &mut crate::DynoDBPooledConnection,
+    user: &User,
+) -> DynoResult<Mail> {
+    let Some(email) = user.email.clone() else {
+        return Err(DynoErr::bad_request_error("User does not have an email"));
+    };
+    verification_actions::invalidate_by_user(conn, user.id)?;
+    let token = token::generate_token();
+    let hours = state.cfg.registration.verify_token_hours;
+    verification_actions::insert(
+        conn,
+        NewEmailVerification {
+            user_id: user.id,
+            token_hash: token::hash_token(&token),
+            expires_at: Utc::now().naive_utc() + Duration::hours(hours),
+        },
+    )?;
+    Ok(Mail {
+        to: email,
+        subject: "Dynotests email verification".to_owned(),
+        body: format!(
+            "Hi {},\n\nopen the link below to verify your email, \
+            the link is valid for {hours} hours.\n\n\
+            {public_url}/verify?token={token}\n\n\
+            Ignore this email if you did not register.",
+            user.name
+        ),
+    })
+}
+
+/// # Auth Endpoint `register_user`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/register`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['dyno_core::model::users::UserRegistration']
+/// RESPONSE(JSON)      => message
+///
+/// email should be in `DYNO_REGISTER_EMAIL_DOMAINS` when it is set, the account can only
+/// login after the link sent into the email is opened when `DYNO_REGISTER_VERIFY_EMAIL` is enabled.
+/// account is created as pending [`dyno_core::role::Roles::User`] that need admin approval,
+/// other requested role is only granted by admin from the approval queue.
+/// `503 Service Unavailable` when the email should be verified but mail is disabled.
+/// -----------------------------------------------------------------
+#[post("/auth/register", wrap = "RateLimit::new(\"register\")")]
 pub async fn register_user(
     web::Json(user_registration): web::Json<UserRegistration>,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
 ) -> DynoResult<HttpResponse> {
+    let email = user_registration.email.trim().to_lowercase();
+    dyno_core::validate_email(&email)?;
+    if !data.cfg.registration.is_allowed_email(&email) {
+        return Err(DynoErr::bad_request_error(
+            "Email domain is not allowed for registration",
+        ));
+    }
+    let verify = data.cfg.registration.verify_email;
+    if verify && data.mail.is_none() {
+        return Ok(mail_unavailable());
+    }
+    let public_url = data.mail.as_ref().map(|x| x.public_url.clone());
+    let state = data.clone();
     let ret_block = web::block(move || {
-        data.db
+        let mut conn = state
+            .db
             .get()
-            .map_err(|_| DynoErr::database_error("Failed to get database connection"))
-            .and_then(|mut conn| {
-                if matches!(
-                    user_actions::is_exists_by_nim(&mut conn, &user_registration.nim),
-                    Ok(true)
-                ) {
-                    return Err(DynoErr::bad_request_error("User is already registered!"));
-                }
-                let newuser = NewUser::from_registration(user_registration)?;
-                user_actions::insert_new(&mut conn, newuser)
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let nim = user_registration.nim.clone();
+        // hashed before the transaction, so the write lock is not held while hashing
+        let newuser = NewUser::from_registration(UserRegistration {
+            email: email.clone(),
+            ..user_registration
+        })?
+        .pending();
+        actions::transaction(&mut conn, |conn| {
+            if matches!(user_actions::is_exists_by_nim(conn, &nim), Ok(true)) {
+                return Err(DynoErr::bad_request_error("User is already registered!"));
+            }
+            if user_actions::find_by_email(conn, &email)?.is_some() {
+                return Err(DynoErr::bad_request_error("Email is already registered!"));
+            }
+            user_actions::insert_new(
+                conn,
+                if verify {
+                    newuser.unverified()
+                } else {
+                    newuser
+                },
+            )?;
+            let user = user_actions::find_by_nim(conn, &nim)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(user.id), "user.register")
+                    .target("user", user.id)
+                    .diff(None::<&User>, Some(&user)),
+            )?;
+            let Some(public_url) = public_url.filter(|_| verify) else {
+                return Ok((
+                    "Registration success, login after the account is approved by admin",
+                    None,
+                ));
+            };
+            verification_mail(&state, &public_url, conn, &user).map(|mail| {
+                (
+                    "Registration success, open the link sent into your email to verify it, \
+                    then login after the account is approved by admin",
+                    Some(mail),
+                )
             })
+        })
     })
     .await
     .map_err(DynoErr::internal_server_error)?;
     match ret_block {
-        Ok(ok) => Ok(HttpResponse::Ok().json(ApiResponse::success(ok))),
+        Ok((ok, mail)) => {
+            if let (Some(mail), Some(sender)) = (mail, &data.mail) {
+                sender.send_detached(mail);
+            }
+            Ok(HttpResponse::Ok().json(ApiResponse::success(ok.to_owned())))
+        }
         Err(err) => Err(err),
     }
 }
 
-#[post("/auth/login")]
+/// # Auth Endpoint `login_user`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/login`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => optional [`crate::middlewares::ApiKeyMiddleware`] of the station
+/// BODY(JSON)          => ['dyno_core::model::users::UserLogin']
+/// RESPONSE(JSON)      => ['dyno_core::crypto::TokenDetails']
+///
+/// the user is set as the active user of the dyno when the api key has `post_active` scope.
+/// user with TOTP enabled, or admin when `DYNO_2FA_REQUIRED_ADMIN` is set, get `202 Accepted` with
+/// ['crate::models::two_factor::TwoFactorChallenge'] instead, and finish it in `/api/auth/2fa/login`.
+/// -----------------------------------------------------------------
+#[post("/auth/login", wrap = "RateLimit::new(\"login\")")]
 pub async fn login_user(
     web::Json(UserLogin { nim, password }): web::Json<UserLogin>,
+    station: Option<ApiKeyMiddleware>,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
     req: HttpRequest,
 ) -> DynoResult<HttpResponse> {
-    dyno_core::log::debug!("login endpoint post with nim: {nim}, pswd: {password}");
+    dyno_core::log::debug!("login endpoint post with nim: {nim}");
 
-    let is_in_desktop = req
-        .headers()
-        .get(header::USER_AGENT)
-        .is_some_and(|x| x.to_str().is_ok_and(|x| x.contains("Dyno/Desktop")));
+    let is_in_desktop = from_station(station);
 
-    let nim_borrowed = nim.clone();
-    let db = data.db.clone();
+    let failure = failed_attempt(&req, &data.cfg.rate_limit, &nim);
+    let nim_key = format!("nim:{nim}");
+    let state = data.clone();
+    let failure_auditor = auditor.clone();
     let user = web::block(move || {
-        match db
+        let mut conn = state
+            .db
             .get()
-            .map_err(|_| DynoErr::database_error("Failed to get database connection"))
-        {
-            Ok(mut conn) => match user_actions::find_by_nim(&mut conn, &nim_borrowed) {
-                Ok(user) if verify_hash_password(&user.password, password) => Ok(user),
-                Ok(_) => Err(DynoErr::unauthorized_error(
-                    "Auth Failed! User Password is not the same",
-                )),
-                Err(err) => Err(err),
-            },
-            Err(err) => Err(err),
-        }
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let reason = if let Some(retry_after) = state.limiter.blocked(&nim_key) {
+            Err(retry_after)
+        } else {
+            match user_actions::find_by_nim(&mut conn, &nim) {
+                Ok(user) if verify_hash_password(&user.password, password) => {
+                    state.limiter.success(&nim_key);
+                    return Ok(Ok(user));
+                }
+                Ok(_) => Ok("wrong_password"),
+                Err(_) => {
+                    // same hashing cost as the wrong password, so unknown nim is not faster
+                    verify_hash_password(dummy_hash()?, password);
+                    Ok("unknown_nim")
+                }
+            }
+        };
+        let blocked = match reason {
+            Ok(_) => state.limiter.failure(&state.cfg.rate_limit, &nim_key),
+            Err(retry_after) => Some(retry_after),
+        };
+        let failure = NewLoginFailure {
+            reason: reason.unwrap_or("blocked").to_owned(),
+            ..failure
+        };
+        dyno_core::log::warn!(
+            "[LOGIN] failed for nim: {} from {} - {}",
+            failure.nim,
+            failure.ip,
+            failure.reason
+        );
+        record_failure(&mut conn, &failure_auditor, failure)?;
+        Ok(Err(blocked))
     })
     .await
     .map_err(DynoErr::internal_server_error)??;
 
+    // unknown nim and wrong password is the same error, so registered nim can not be enumerated
+    let user = match user {
+        Ok(user) => user,
+        Err(Some(retry_after)) => return Ok(too_many_requests(retry_after)),
+        Err(None) => {
+            return Err(DynoErr::unauthorized_error(
+                "Auth Failed! NIM or password is wrong",
+            ))
+        }
+    };
+
+    if data.cfg.registration.verify_email && !user.is_verified() {
+        return Err(DynoErr::forbidden_error(
+            "Email is not verified, open the link sent into your email",
+        ));
+    }
+    match user.status {
+        UserStatus::Active => {}
+        UserStatus::Pending => {
+            return Err(DynoErr::forbidden_error(
+                "Account is waiting for admin approval",
+            ))
+        }
+        UserStatus::Rejected => {
+            return Err(DynoErr::forbidden_error("Account registration is rejected"))
+        }
+    }
+
+    let required = data.cfg.two_factor.is_required(&user.role.into_inner());
+    if user.is_two_factor_enabled() || required {
+        let challenge = token::generate_token();
+        let expires_at =
+            Utc::now().naive_utc() + Duration::minutes(data.cfg.two_factor.challenge_minutes);
+        let new = NewLoginChallenge {
+            user_id: user.id,
+            token_hash: token::hash_token(&challenge),
+            expires_at,
+        };
+        let dbpool = data.db.clone();
+        web::block(move || {
+            dbpool
+                .get()
+                .map_err(DynoErr::database_error)
+                .and_then(|mut conn| two_factor_actions::insert_challenge(&mut conn, new))
+        })
+        .await
+        .map_err(DynoErr::internal_server_error)??;
+        return Ok(
+            HttpResponse::Accepted().json(ApiResponse::success(TwoFactorChallenge {
+                challenge,
+                enroll: !user.is_two_factor_enabled(),
+                expires_at,
+            })),
+        );
+    }
+
+    let entry = auditor
+        .entry(Some(user.id), "auth.login")
+        .target("user", user.id)
+        .details(&"password");
+    super::audit_log::record(&data, entry).await?;
+    login_response(&data, &user, is_in_desktop)
+}
+
+/// issue the access token of the `user` as cookies and json body, last step of the login
+pub(super) fn login_response(
+    data: &crate::ServerState,
+    user: &User,
+    is_in_desktop: bool,
+) -> DynoResult<HttpResponse> {
     let user_session = UserSession {
         id: user.id,
         role: user.role.into_inner(),
@@ -116,11 +365,50 @@ pub async fn login_user(
     )
 }
 
+/// audit row of a failed login from the request, `reason` is filled by the caller
+/// insert the failed login into `login_failures` and `auth.login_failed` audit entry without actor
+pub(super) fn record_failure(
+    conn: &mut crate::DynoDBPooledConnection,
+    auditor: &Auditor,
+    failure: NewLoginFailure,
+) -> DynoResult<()> {
+    actions::transaction(conn, |conn| {
+        audit_log_actions::insert(
+            conn,
+            auditor.entry(None, "auth.login_failed").details(&failure),
+        )?;
+        login_failure_actions::insert(conn, failure).map(|_| ())
+    })
+}
+
+pub(super) fn failed_attempt(
+    req: &HttpRequest,
+    cfg: &crate::config::RateLimit,
+    nim: &str,
+) -> NewLoginFailure {
+    NewLoginFailure {
+        nim: nim.to_owned(),
+        ip: client_ip(req, cfg),
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|x| x.to_str().ok())
+            .map(ToOwned::to_owned),
+        reason: String::new(),
+    }
+}
+
+/// login from the station with `post_active` api key, the user is set as the active user of the dyno
+#[inline]
+pub(super) fn from_station(station: Option<ApiKeyMiddleware>) -> bool {
+    station.is_some_and(|x| x.has(ApiScope::PostActive))
+}
+
 #[get("/auth/logout")]
 pub async fn logout_user(
     JwtUserMiddleware(_d): JwtUserMiddleware,
+    station: Option<ApiKeyMiddleware>,
     data: web::Data<crate::ServerState>,
-    req: HttpRequest,
 ) -> DynoResult<HttpResponse> {
     let access_cookie = Cookie::build("access_token", "")
         .path("/")
@@ -134,22 +422,18 @@ pub async fn logout_user(
         .finish();
 
     if let Some(active) = data.get_active() {
-        if let Some(head) = req.headers().get(header::USER_AGENT) {
-            if head.to_str().is_ok_and(|x| x.contains("Dyno/Desktop")) {
-                if let Some(hist) = active.to_history() {
-                    let db = data.db.clone();
-                    web::block(move || {
-                        db.get()
-                            .map_err(|_| {
-                                DynoErr::database_error("Failed to get database connection")
-                            })
-                            .and_then(|mut conn| actions::history::insert(&mut conn, hist))
-                    })
-                    .await
-                    .map_err(DynoErr::internal_server_error)??;
-                }
-                data.set_active(None);
+        if from_station(station) {
+            if let Some(hist) = active.to_history() {
+                let db = data.db.clone();
+                web::block(move || {
+                    db.get()
+                        .map_err(|_| DynoErr::database_error("Failed to get database connection"))
+                        .and_then(|mut conn| actions::history::insert(&mut conn, hist))
+                })
+                .await
+                .map_err(DynoErr::internal_server_error)??;
             }
+            data.set_active(None);
         }
     }
     Ok(HttpResponse::Ok()
@@ -177,3 +461,220 @@ pub async fn me(
         Err(err) => Err(err),
     }
 }
+
+/// # Auth Endpoint `forgot_password`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/forgot`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['crate::models::reset::ForgotPassword']
+/// RESPONSE(JSON)      => message
+///
+/// email the reset link `/reset?token=` to the user, response is always the same
+/// whether the email is registered or not, so it can not be used to find registered email.
+/// `503 Service Unavailable` when mail is disabled.
+/// -----------------------------------------------------------------
+#[post("/auth/forgot", wrap = "RateLimit::new(\"forgot\")")]
+pub async fn forgot_password(
+    web::Json(ForgotPassword { email }): web::Json<ForgotPassword>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let Some(sender) = data.mail.clone() else {
+        return Ok(mail_unavailable());
+    };
+    let public_url = sender.public_url.clone();
+    let state = data.clone();
+    let email = email.trim().to_lowercase();
+    let mail = web::block(move || {
+        let mut conn = state
+            .db
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let Some(user) = user_actions::find_by_email(&mut conn, &email)? else {
+            dyno_core::log::info!("Password reset requested for unknown email");
+            return Ok(None);
+        };
+        reset_actions::invalidate_by_user(&mut conn, user.id)?;
+        let token = token::generate_token();
+        let minutes = state.cfg.mail.reset_token_minutes;
+        reset_actions::insert(
+            &mut conn,
+            NewPasswordReset {
+                user_id: user.id,
+                token_hash: token::hash_token(&token),
+                expires_at: Utc::now().naive_utc() + Duration::minutes(minutes),
+            },
+        )?;
+        Ok(Some(Mail {
+            to: email,
+            subject: "Dynotests password reset".to_owned(),
+            body: format!(
+                "Hi {},\n\nopen the link below to reset your password, \
+                the link is valid for {minutes} minutes and can only be used once.\n\n\
+                {public_url}/reset?token={token}\n\n\
+                Ignore this email if you did not request a password reset.",
+                user.name
+            ),
+        }))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+    if let Some(mail) = mail {
+        sender.send_detached(mail);
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "If the email is registered, a password reset link has been sent".to_owned(),
+    )))
+}
+
+/// # Auth Endpoint `reset_password`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/reset`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['crate::models::reset::ResetPassword']
+/// RESPONSE(JSON)      => message
+///
+/// token is single use and expired after `DYNO_RESET_TOKEN_MINUTES`.
+/// -----------------------------------------------------------------
+#[post("/auth/reset")]
+pub async fn reset_password(
+    web::Json(ResetPassword { token, password }): web::Json<ResetPassword>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    dyno_core::validate_password(&password)?;
+    let db = data.db.clone();
+    web::block(move || {
+        let mut conn = db
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let password = dyno_core::crypto::hash_password(password)?;
+        actions::transaction(&mut conn, |conn| {
+            let reset = reset_actions::select_by_token_hash(conn, &token::hash_token(&token))
+                .ok()
+                .filter(|reset| reset.is_valid())
+                .ok_or(DynoErr::bad_request_error(
+                    "Reset token is invalid, expired or already used",
+                ))?;
+            if !reset_actions::mark_used(conn, reset.id)? {
+                return Err(DynoErr::bad_request_error(
+                    "Reset token is invalid, expired or already used",
+                ));
+            }
+            user_actions::update_by_id(
+                conn,
+                reset.user_id,
+                UpdateUser {
+                    password: Some(password),
+                    updated_at: Some(Utc::now().naive_utc()),
+                    ..Default::default()
+                },
+            )?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(reset.user_id), "user.password_reset")
+                    .target("user", reset.user_id),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "Password is changed, login with the new password".to_owned(),
+    )))
+}
+
+/// # Auth Endpoint `verify_email`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/verify`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['crate::models::verification::VerifyEmail']
+/// RESPONSE(JSON)      => message
+///
+/// -----------------------------------------------------------------
+#[post("/auth/verify")]
+pub async fn verify_email(
+    web::Json(VerifyEmail { token }): web::Json<VerifyEmail>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let db = data.db.clone();
+    web::block(move || {
+        let mut conn = db
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        actions::transaction(&mut conn, |conn| {
+            let verification =
+                verification_actions::select_by_token_hash(conn, &token::hash_token(&token))
+                    .ok()
+                    .filter(|verification| verification.is_valid())
+                    .ok_or(DynoErr::bad_request_error(
+                        "Verification link is invalid, expired or already used",
+                    ))?;
+            if !verification_actions::mark_used(conn, verification.id)? {
+                return Err(DynoErr::bad_request_error(
+                    "Verification link is invalid, expired or already used",
+                ));
+            }
+            user_actions::set_email_verified(conn, verification.user_id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(verification.user_id), "user.verify_email")
+                    .target("user", verification.user_id),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "Email is verified, login with the registered account".to_owned(),
+    )))
+}
+
+/// # Auth Endpoint `resend_verification`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/verify/resend`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['crate::models::verification::ResendVerification']
+/// RESPONSE(JSON)      => message
+///
+/// response is always the same whether the email is registered, verified or not.
+/// `503 Service Unavailable` when mail is disabled.
+/// -----------------------------------------------------------------
+#[post("/auth/verify/resend", wrap = "RateLimit::new(\"resend\")")]
+pub async fn resend_verification(
+    web::Json(ResendVerification { email }): web::Json<ResendVerification>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let Some(sender) = data.mail.clone() else {
+        return Ok(mail_unavailable());
+    };
+    let public_url = sender.public_url.clone();
+    let state = data.clone();
+    let email = email.trim().to_lowercase();
+    let mail = web::block(move || {
+        let mut conn = state
+            .db
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        match user_actions::find_by_email(&mut conn, &email)? {
+            Some(user) if !user.is_verified() => {
+                verification_mail(&state, &public_url, &mut conn, &user).map(Some)
+            }
+            _ => Ok(None),
+        }
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+    if let Some(mail) = mail {
+        sender.send_detached(mail);
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "If the email is waiting for verification, a new link has been sent".to_owned(),
+    )))
+}
@@ -0,0 +1,333 @@
+use actix_web::{delete, get, patch, post, web, HttpResponse};
+use dyno_core::{model::UserSession, ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, class as class_actions,
+        permission as permission_actions, user as user_actions,
+    },
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission},
+    models::{
+        class::{Class, EnrollMembers, NewClass, UpdateClass},
+        permission::AccessRole,
+    },
+    DynoDBPooledConnection,
+};
+
+/// only the lecturer of the class or user with `user.manage` permission can remove the members
+fn manageable(
+    conn: &mut DynoDBPooledConnection,
+    class_id: i64,
+    session: &UserSession,
+    is_manager: bool,
+) -> DynoResult<Class> {
+    let class = class_actions::select_by_id(conn, class_id)?;
+    if is_manager || class.lecturer_id == session.id {
+        Ok(class)
+    } else {
+        Err(DynoErr::forbidden_error(
+            "Class can only be managed by its lecturer or user with `user.manage` permission",
+        ))
+    }
+}
+
+/// lecturer of the class should has the `lecturer` access role
+fn check_lecturer(conn: &mut DynoDBPooledConnection, lecturer_id: i64) -> DynoResult<()> {
+    match permission_actions::select_access_role(conn, lecturer_id) {
+        Ok(AccessRole::Lecturer) => Ok(()),
+        Ok(role) => Err(DynoErr::bad_request_error(format!(
+            "User with id {lecturer_id} has the `{}` access role, lecturer should be `lecturer`",
+            role.as_str()
+        ))),
+        Err(_) => Err(DynoErr::bad_request_error("Lecturer is not exists")),
+    }
+}
+
+/// # Class Endpoint `get_classes`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::class::Class'>]
+///
+/// every class for user with `user.manage` permission, otherwise the class that is taught by
+/// or has the user enrolled.
+/// -----------------------------------------------------------------
+#[get("/classes")]
+pub async fn get_classes(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        if is_manager {
+            class_actions::select_all(&mut conn)
+        } else {
+            class_actions::select_by_user(&mut conn, session.id)
+        }
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|classes| HttpResponse::Ok().json(ApiResponse::success(classes)))
+}
+
+/// # Class Endpoint `create_class`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// BODY(JSON)          => ['crate::models::class::NewClass']
+/// RESPONSE(JSON)      => ['crate::models::class::Class']
+/// -----------------------------------------------------------------
+#[post("/classes")]
+pub async fn create_class(
+    web::Json(new): web::Json<NewClass>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if new.name.trim().is_empty() || new.course.trim().is_empty() || new.semester.trim().is_empty()
+    {
+        return Err(DynoErr::bad_request_error(
+            "Class name, course and semester should not be empty",
+        ));
+    }
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            check_lecturer(conn, new.lecturer_id)?;
+            let id = class_actions::insert(conn, new)?;
+            let class = class_actions::select_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "class.create")
+                    .target("class", id)
+                    .diff(None::<&Class>, Some(&class)),
+            )?;
+            Ok(class)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|class| HttpResponse::Ok().json(ApiResponse::success(class)))
+}
+
+/// # Class Endpoint `update_class`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}`
+/// GUARD               => `PATCH`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// BODY(JSON)          => ['crate::models::class::UpdateClass']
+/// RESPONSE(JSON)      => ['crate::models::class::Class']
+/// -----------------------------------------------------------------
+#[patch("/classes/{id}")]
+pub async fn update_class(
+    id: web::Path<i64>,
+    web::Json(updated): web::Json<UpdateClass>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            if let Some(lecturer_id) = updated.lecturer_id {
+                check_lecturer(conn, lecturer_id)?;
+            }
+            let before = class_actions::select_by_id(conn, id)
+                .map_err(|_| DynoErr::not_found_error("Class is not exists"))?;
+            if class_actions::update(conn, id, updated)? == 0 {
+                return Err(DynoErr::not_found_error("Class is not exists"));
+            }
+            let after = class_actions::select_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "class.update")
+                    .target("class", id)
+                    .diff(Some(&before), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|class| HttpResponse::Ok().json(ApiResponse::success(class)))
+}
+
+/// # Class Endpoint `delete_class`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => `id` of deleted class
+///
+/// only the class and the enrollment is deleted, dynotest of the members is kept.
+/// -----------------------------------------------------------------
+#[delete("/classes/{id}")]
+pub async fn delete_class(
+    id: web::Path<i64>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let before = class_actions::select_by_id(conn, id)
+                .map_err(|_| DynoErr::not_found_error("Class is not exists"))?;
+            class_actions::delete(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "class.delete")
+                    .target("class", id)
+                    .diff(Some(&before), None::<&Class>),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(id)))
+}
+
+/// # Class Endpoint `get_class_members`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/members`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::class::ClassMemberResponse'>]
+///
+/// visible for the lecturer, the members of the class and user with `user.manage` permission.
+/// -----------------------------------------------------------------
+#[get("/classes/{id}/members")]
+pub async fn get_class_members(
+    id: web::Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let class = class_actions::select_by_id(&mut conn, id)?;
+        let members = class_actions::select_members(&mut conn, id)?;
+        if is_manager
+            || class.lecturer_id == session.id
+            || members.iter().any(|x| x.user_id == session.id)
+        {
+            Ok(members)
+        } else {
+            Err(DynoErr::forbidden_error(
+                "Class members is only visible for the lecturer and members of the class",
+            ))
+        }
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|members| HttpResponse::Ok().json(ApiResponse::success(members)))
+}
+
+/// # Class Endpoint `enroll_class_members`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/members`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// BODY(JSON)          => ['crate::models::class::EnrollMembers']
+/// RESPONSE(JSON)      => [Vec<'crate::models::class::ClassMemberResponse'>]
+///
+/// enrolled user is moved into the `group_name`. not allowed for the lecturer, so a lecturer can
+/// not enroll any user to read its dynotest, the lecturer only read what is submitted to the
+/// assignments of the class.
+/// -----------------------------------------------------------------
+#[post("/classes/{id}/members")]
+pub async fn enroll_class_members(
+    id: web::Path<i64>,
+    web::Json(mut enroll): web::Json<EnrollMembers>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    enroll.group_name = enroll
+        .group_name
+        .map(|x| x.trim().to_owned())
+        .filter(|x| !x.is_empty());
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            class_actions::select_by_id(conn, id)
+                .map_err(|_| DynoErr::not_found_error("Class is not exists"))?;
+            for &user_id in &enroll.user_ids {
+                if !user_actions::is_exists_by_id(conn, user_id)? {
+                    return Err(DynoErr::bad_request_error(format!(
+                        "User with id {user_id} is not exists"
+                    )));
+                }
+            }
+            let entry = auditor
+                .entry(Some(session.id), "class.enroll")
+                .target("class", id)
+                .details(&enroll);
+            class_actions::enroll(conn, id, enroll)?;
+            audit_log_actions::insert(conn, entry)?;
+            class_actions::select_members(conn, id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|members| HttpResponse::Ok().json(ApiResponse::success(members)))
+}
+
+/// # Class Endpoint `remove_class_member`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/classes/{id}/members/{user_id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] lecturer of the class or `user.manage`
+/// RESPONSE(JSON)      => `user_id` of removed member
+/// -----------------------------------------------------------------
+#[delete("/classes/{id}/members/{user_id}")]
+pub async fn remove_class_member(
+    path: web::Path<(i64, i64)>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let (id, user_id) = path.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            manageable(conn, id, &session, is_manager)?;
+            if class_actions::remove_member(conn, id, user_id)? == 0 {
+                return Err(DynoErr::not_found_error(
+                    "User is not enrolled in the class",
+                ));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "class.remove_member")
+                    .target("class", id)
+                    .details(&user_id),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(user_id)))
+}
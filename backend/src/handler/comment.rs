@@ -0,0 +1,322 @@
+use actix_web::{
+    delete, get, post,
+    web::{self, Path},
+    HttpResponse,
+};
+use dyno_core::{ApiResponse, BufferData, CompresedSaver, DynoErr, DynoResult};
+
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, comment as comment_actions, dyno as dyno_actions,
+    },
+    handler::{dyno::readable, share::DynoPeaks},
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission},
+    models::comment::{
+        Annotation, AnnotationAnchor, CommentRequest, DynoSeries, NewAnnotation, NewComment,
+        SeriesRange,
+    },
+    storage::Storage,
+};
+
+const MAX_COMMENT_LENGTH: usize = 4096;
+const MAX_LABEL_LENGTH: usize = 128;
+
+/// # Comment Endpoint `get_series`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/series`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::comment::DynoSeries']
+///
+/// data of the dynotest with the comments, the chart annotations and the range of the anchor,
+/// readable by the same user as [`crate::handler::dyno::get_file`].
+/// -----------------------------------------------------------------
+#[get("/dyno/{id}/series")]
+pub async fn get_series(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    let read_all = read_all.is_some();
+
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let dyno = dyno_actions::select_by_id(&mut conn, id)
+            .and_then(|dyno| readable(&mut conn, dyno, &session, read_all))?;
+        let data = storage
+            .get(&dyno.data_url)
+            .and_then(BufferData::decompress)?;
+        let range = SeriesRange {
+            max_rpm: DynoPeaks::from_buffer(&data)?.max_rpm,
+            duration: (dyno.stop - dyno.start).num_milliseconds() as f64 / 1000.0,
+        };
+        Ok(DynoSeries {
+            data,
+            range,
+            comments: comment_actions::select_comments(&mut conn, id)?,
+            annotations: comment_actions::select_annotations(&mut conn, id)?,
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|series| HttpResponse::Ok().json(ApiResponse::success(series)))
+}
+
+/// # Comment Endpoint `get_comments`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/comments`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::comment::CommentResponse'>]
+/// -----------------------------------------------------------------
+#[get("/dyno/{id}/comments")]
+pub async fn get_comments(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let read_all = read_all.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        dyno_actions::select_by_id(&mut conn, id)
+            .and_then(|dyno| readable(&mut conn, dyno, &session, read_all))?;
+        comment_actions::select_comments(&mut conn, id)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|comments| HttpResponse::Ok().json(ApiResponse::success(comments)))
+}
+
+/// # Comment Endpoint `add_comment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/comments`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['crate::models::comment::CommentRequest']
+/// RESPONSE(JSON)      => [Vec<'crate::models::comment::CommentResponse'>]
+///
+/// every user that can read the dynotest can comment or reply, responded with the whole thread.
+/// -----------------------------------------------------------------
+#[post("/dyno/{id}/comments")]
+pub async fn add_comment(
+    id: Path<i64>,
+    web::Json(CommentRequest { body, parent_id }): web::Json<CommentRequest>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let body = body.trim().to_owned();
+    if body.is_empty() || body.len() > MAX_COMMENT_LENGTH {
+        return Err(DynoErr::bad_request_error(format!(
+            "Comment should not be empty or longer than {MAX_COMMENT_LENGTH} characters"
+        )));
+    }
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let read_all = read_all.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            dyno_actions::select_by_id(conn, id)
+                .and_then(|dyno| readable(conn, dyno, &session, read_all))?;
+            if let Some(parent_id) = parent_id {
+                let (dyno_id, _) = comment_actions::select_comment_owner(conn, parent_id)?;
+                if dyno_id != id {
+                    return Err(DynoErr::bad_request_error(
+                        "Replied comment is not in the same dynotest",
+                    ));
+                }
+            }
+            let entry = auditor
+                .entry(Some(session.id), "comment.create")
+                .details(&body);
+            let new = NewComment {
+                dyno_id: id,
+                user_id: session.id,
+                parent_id,
+                body,
+            };
+            let comment_id = comment_actions::insert_comment(conn, new)?;
+            audit_log_actions::insert(conn, entry.target("comment", comment_id))?;
+            comment_actions::select_comments(conn, id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|comments| HttpResponse::Ok().json(ApiResponse::success(comments)))
+}
+
+/// # Comment Endpoint `delete_comment`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/comments/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => `id` of deleted comment
+///
+/// only the author or user with `user.manage` permission can delete, every reply is deleted too.
+/// -----------------------------------------------------------------
+#[delete("/comments/{id}")]
+pub async fn delete_comment(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let (dyno_id, user_id) = comment_actions::select_comment_owner(conn, id)?;
+            if user_id != session.id && !is_manager {
+                return Err(DynoErr::forbidden_error(
+                    "Comment can only be deleted by its author or user with `user.manage` permission",
+                ));
+            }
+            comment_actions::delete_comment(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "comment.delete")
+                    .target("comment", id)
+                    .details(&dyno_id),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(id)))
+}
+
+/// # Comment Endpoint `add_annotation`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/annotations`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['crate::models::comment::NewAnnotation']
+/// RESPONSE(JSON)      => ['crate::models::comment::Annotation']
+///
+/// `position` is the rpm or the seconds from the start of the dynotest depend on the `anchor`,
+/// it should be in the range of [`crate::models::comment::SeriesRange`] of the dynotest.
+/// -----------------------------------------------------------------
+#[post("/dyno/{id}/annotations")]
+pub async fn add_annotation(
+    id: Path<i64>,
+    web::Json(mut new): web::Json<NewAnnotation>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    new.label = new.label.trim().to_owned();
+    if new.label.is_empty() || new.label.len() > MAX_LABEL_LENGTH {
+        return Err(DynoErr::bad_request_error(format!(
+            "Annotation label should not be empty or longer than {MAX_LABEL_LENGTH} characters"
+        )));
+    }
+    if !new.position.is_finite() || new.position < 0.0 {
+        return Err(DynoErr::bad_request_error(
+            "Annotation position should be a positive number",
+        ));
+    }
+    new.dyno_id = id.into_inner();
+    new.user_id = session.id;
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    let read_all = read_all.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let dyno = dyno_actions::select_by_id(&mut conn, new.dyno_id)
+            .and_then(|dyno| readable(&mut conn, dyno, &session, read_all))?;
+        match new.anchor {
+            AnnotationAnchor::Time => {
+                let duration = (dyno.stop - dyno.start).num_milliseconds() as f64 / 1000.0;
+                if new.position > duration {
+                    return Err(DynoErr::bad_request_error(format!(
+                        "Annotation time should be in range of 0 - {duration} seconds"
+                    )));
+                }
+            }
+            AnnotationAnchor::Rpm => {
+                let data = storage
+                    .get(&dyno.data_url)
+                    .and_then(BufferData::decompress)?;
+                let max_rpm = DynoPeaks::from_buffer(&data)?.max_rpm.unwrap_or_default();
+                if new.position > max_rpm {
+                    return Err(DynoErr::bad_request_error(format!(
+                        "Annotation rpm should be in range of 0 - {max_rpm} rpm"
+                    )));
+                }
+            }
+        }
+        actions::transaction(&mut conn, |conn| {
+            let id = comment_actions::insert_annotation(conn, new)?;
+            let annotation = comment_actions::select_annotation_by_id(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "annotation.create")
+                    .target("annotation", id)
+                    .diff(None::<&Annotation>, Some(&annotation)),
+            )?;
+            Ok(annotation)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|annotation| HttpResponse::Ok().json(ApiResponse::success(annotation)))
+}
+
+/// # Comment Endpoint `delete_annotation`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/annotations/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => `id` of deleted annotation
+///
+/// only the author or user with `user.manage` permission can delete.
+/// -----------------------------------------------------------------
+#[delete("/annotations/{id}")]
+pub async fn delete_annotation(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let annotation = comment_actions::select_annotation_by_id(conn, id)?;
+            if annotation.user_id != session.id && !is_manager {
+                return Err(DynoErr::forbidden_error(
+                    "Annotation can only be deleted by its author or user with `user.manage` permission",
+                ));
+            }
+            comment_actions::delete_annotation(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "annotation.delete")
+                    .target("annotation", id)
+                    .diff(Some(&annotation), None::<&Annotation>),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(id)))
+}
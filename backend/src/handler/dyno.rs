@@ -1,69 +1,76 @@
-use std::path::Path as StdPath;
+use std::{io::Write, path::PathBuf};
 
 use actix_web::{
-    get,
+    delete, get,
     http::header,
     post,
     web::{self, Path},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 
 use actix_multipart::Multipart;
 use dyno_core::{
-    crypto::{checksum_from_bytes, compare_checksums},
+    chrono::{Duration, NaiveDate, NaiveDateTime},
+    crypto::compare_checksums,
     dynotests::DynoTestDataInfo,
     users::OneOrMany,
-    ApiResponse, BufferData, CompresedSaver, CsvSaver, DynoErr, DynoResult, ExcelSaver,
+    ApiResponse, BufferData, CompresedSaver, CsvSaver, DynoConfig, DynoErr, DynoResult, ExcelSaver,
+    UserSession,
 };
-use futures::TryStreamExt;
+use futures::{channel::mpsc, SinkExt, TryStreamExt};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
 
 use crate::{
-    actions::dyno as dyno_actions,
+    actions,
+    actions::assignment as assignment_actions,
+    actions::audit_log as audit_log_actions,
+    actions::class as class_actions,
+    actions::comment as comment_actions,
+    actions::dyno::{self as dyno_actions, DynoFilter},
     actions::info as info_actions,
+    actions::label as label_actions,
+    actions::permission as permission_actions,
+    actions::share as share_actions,
+    checksum,
+    config::Quotas,
     handler::DynoUrlsQueries,
-    middlewares::JwtUserMiddleware,
+    import::{ImportSpec, ImportedBuffer, Sheet, SheetFormat},
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission, UploaderMiddleware},
     models::{
         dyno::{Dynos, NewDynos},
+        info::DynoInfo,
         uuid::UUID,
     },
+    storage::{self, DynoStorage, Storage},
+    upload::{self, StagedFile, UploadBudget, UploadResult},
+    DynoDBPooledConnection,
 };
 
-#[inline]
-fn save_dyno(
-    path: impl AsRef<StdPath>,
-    data: impl AsRef<[u8]>,
-    id: i64,
-    uuid: impl std::fmt::Display + Copy,
-) -> DynoResult<()> {
-    let path = path.as_ref();
-    dyno_core::log::debug!("Save dyno in {}, with id:{id} uuid:{uuid}", path.display());
-    if !path.exists() {
-        if let Err(err) = std::fs::create_dir_all(path) {
-            return Err(DynoErr::internal_server_error(err));
-        }
-    }
-    std::fs::write(path.join(format!("{id}-{uuid}.dyno")), data)
-        .map_err(DynoErr::internal_server_error)
-}
-
 /// # Dynotest Endpoint `add_dyno`
 /// -----------------------------------------------------------------
 /// URL                 => `/api/dyno`
 /// GUARD               => `POST`
-/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
-/// BODY(JSON)          => 'dyno_core::model::dynotests::DynoTestDataInfo '
+/// HEADER/COOKIES      => [`crate::middlewares::UploaderMiddleware`]
+/// BODY(MULTIPART)     => `info` 'dyno_core::model::dynotests::DynoTestDataInfo' + `data`
 ///
+/// `data` part is streamed into `cfg.upload.tmp_path` and limited by `cfg.upload`,
+/// request that exceed the limits is responded with `413 Payload Too Large`.
+/// data with the same checksum as existing dynotest is handled by `duplicate` [`OnDuplicate`].
 /// -----------------------------------------------------------------
 #[post("/dyno")]
 pub async fn add_dyno(
+    req: HttpRequest,
+    web::Query(QueryDuplicate { duplicate }): web::Query<QueryDuplicate>,
     mut payload: Multipart,
-    JwtUserMiddleware(session): JwtUserMiddleware,
+    UploaderMiddleware(session): UploaderMiddleware,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
-) -> DynoResult<HttpResponse> {
+) -> UploadResult<HttpResponse> {
     let dbpool = data.db.clone();
     let cfg = &data.cfg;
-    let mut info_stream = web::BytesMut::with_capacity(core::mem::size_of::<DynoTestDataInfo>());
-    let mut data_stream = web::BytesMut::new();
+    let mut budget = UploadBudget::from_request(&req, &cfg.upload)?;
+    let mut info_stream = None;
+    let mut data_stream = None;
 
     while let Some(mut field) = payload
         .try_next()
@@ -75,71 +82,351 @@ pub async fn add_dyno(
             continue;
         };
         if field_name == "data" {
-            while let Some(chunk) = field
-                .try_next()
-                .await
-                .map_err(DynoErr::internal_server_error)?
-            {
-                data_stream.extend_from_slice(&chunk)
-            }
+            data_stream = Some(
+                upload::stream_field_to_file(&mut field, &mut budget, &cfg.upload.tmp_path).await?,
+            );
         } else if field_name == "info" {
-            while let Some(chunk) = field
-                .try_next()
-                .await
-                .map_err(DynoErr::internal_server_error)?
-            {
-                info_stream.extend_from_slice(&chunk)
-            }
+            info_stream = Some(upload::read_field(&mut field, &mut budget).await?);
         }
     }
-    let id = session.id;
-    let uuid = session.uuid;
-    let public_path = cfg.app_public_path.clone();
+    let (Some(info_stream), Some(data_stream)) = (info_stream, data_stream) else {
+        return Err(DynoErr::bad_request_error(
+            "Multipart POST 'info' and 'data' part is required",
+        )
+        .into());
+    };
+
+    let storage = data.storage.clone();
+    let quotas = data.cfg.quotas.clone();
     let blk_result = web::block(move || {
-        let mut conn = dbpool
-            .get()
-            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
         let dyno_config = DynoTestDataInfo::decompress(&info_stream).map_err(|err| {
             DynoErr::bad_request_error(format!("Multipart POST 'info' part is invalid - {err}",))
         })?;
 
-        let checksum = checksum_from_bytes(&data_stream);
-        if !compare_checksums(checksum.as_bytes(), dyno_config.checksum_hex.as_bytes()) {
+        if !compare_checksums(
+            data_stream.checksum.as_bytes(),
+            dyno_config.checksum_hex.as_bytes(),
+        ) {
             return Err(DynoErr::expectation_failed_error(
                 "Failed receive data, checksum of 'data' part stream is not the same",
             ));
         }
 
-        let info_id = info_actions::insert(&mut conn, dyno_config.config.clone().into()).ok();
-
-        let last_dyno_id = dyno_actions::get_last_id(&mut conn)?;
-        let user_path = format!("dyno/{uuid}");
-        let dyno_uuid = UUID::new();
-        save_dyno(
-            public_path.join(&user_path),
-            data_stream,
-            last_dyno_id + 1,
-            dyno_uuid,
-        )
-        .and_then(|_| {
-            dyno_actions::insert(
-                &mut conn,
-                NewDynos {
-                    user_id: id,
-                    info_id,
-                    uuid: dyno_uuid,
-                    data_url: format!("/{user_path}/{}-{dyno_uuid}.dyno", last_dyno_id + 1),
-                    data_checksum: checksum,
+        let mut conn = dbpool
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let actor_id = session.id;
+        actions::transaction(&mut conn, |conn| {
+            let id = store_dyno(
+                conn,
+                storage.as_ref(),
+                &quotas,
+                session,
+                StoredDyno {
+                    checksum: data_stream.checksum.clone(),
+                    data: DynoData::Staged(data_stream),
+                    config: dyno_config.config,
                     start: dyno_config.start,
                     stop: dyno_config.stop,
+                    imported: false,
+                    on_duplicate: duplicate,
                 },
-            )
+            )?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(actor_id), "dyno.create")
+                    .target("dyno", id),
+            )?;
+            Ok(id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?;
+
+    blk_result
+        .map(|id| HttpResponse::Ok().json(ApiResponse::success(id)))
+        .map_err(From::from)
+}
+
+pub(super) enum DynoData<'a> {
+    Bytes(&'a [u8]),
+    Staged(StagedFile),
+    /// file that is already complete on disk, ex: finished resumable upload
+    File(PathBuf),
+}
+
+impl DynoData<'_> {
+    fn save(self, storage: &dyn Storage, key: &str) -> DynoResult<()> {
+        dyno_core::log::debug!("Save dyno in {} storage as {key}", storage.name());
+        match self {
+            DynoData::Staged(staged) => storage.put_file(key, &staged.into_temp_path()),
+            DynoData::File(from) => storage.put_file(key, &from),
+            DynoData::Bytes(bytes) => storage.put_bytes(key, bytes),
+        }
+    }
+
+    /// size in bytes of the compressed data
+    fn size(&self) -> DynoResult<i64> {
+        match self {
+            DynoData::Bytes(bytes) => Ok(bytes.len() as i64),
+            DynoData::Staged(staged) => Ok(staged.size as i64),
+            DynoData::File(path) => std::fs::metadata(path)
+                .map(|m| m.len() as i64)
+                .map_err(DynoErr::internal_server_error),
+        }
+    }
+
+    /// remove the data that is not saved because it is a duplicate
+    fn discard(self) {
+        if let DynoData::File(path) = self {
+            if let Err(err) = std::fs::remove_file(&path) {
+                dyno_core::log::error!("Failed to remove {} - {err}", path.display());
+            }
+        }
+    }
+}
+
+/// what to do when uploaded data has the same checksum as already stored dynotest
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+pub enum OnDuplicate {
+    /// return `id` of the existing dynotest of the same user, so re-upload is idempotent
+    #[default]
+    Return,
+    /// always create new dynotest, that share the stored data with the existing one
+    Link,
+}
+
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryDuplicate {
+    #[serde(default)]
+    pub duplicate: OnDuplicate,
+}
+
+pub(super) struct StoredDyno<'a> {
+    pub data: DynoData<'a>,
+    pub checksum: String,
+    pub config: DynoConfig,
+    pub start: NaiveDateTime,
+    pub stop: NaiveDateTime,
+    pub imported: bool,
+    pub on_duplicate: OnDuplicate,
+}
+
+/// save the compressed data into [`Storage`] and insert its [`DynoInfo`] and [`Dynos`] record,
+/// data that is already stored with the same checksum is not saved again.
+/// new dynotest that exceed the quota of the user access role is rejected with `403 Forbidden`.
+///
+/// should be called inside [`actions::transaction`], so the checksum lookup and the insert can
+/// not interleave with [`delete_dyno`] removing the stored data that is looked up.
+pub(super) fn store_dyno(
+    conn: &mut DynoDBPooledConnection,
+    storage: &dyn Storage,
+    quotas: &Quotas,
+    session: UserSession,
+    dyno: StoredDyno<'_>,
+) -> DynoResult<i64> {
+    if dyno.on_duplicate == OnDuplicate::Return {
+        if let Some(existing) =
+            dyno_actions::select_by_checksum(conn, &dyno.checksum, Some(session.id))?
+        {
+            dyno_core::log::info!("Upload is duplicate of dyno {}, skip", existing.id);
+            dyno.data.discard();
+            return Ok(existing.id);
+        }
+    }
+
+    let data_size = dyno.data.size()?;
+    let quota = quotas.for_role(permission_actions::select_access_role(conn, session.id)?);
+    let usage = dyno_actions::usage(conn, session.id)?;
+    if quota.max_count.is_some_and(|max| usage.count + 1 > max)
+        || quota
+            .max_bytes
+            .is_some_and(|max| usage.bytes + data_size > max)
+    {
+        dyno.data.discard();
+        return Err(DynoErr::forbidden_error("Storage quota exceeded"));
+    }
+
+    let info_id = info_actions::insert(conn, dyno.config.into()).ok();
+    let dyno_uuid = UUID::new();
+    let (data_url, data_size) = match dyno_actions::select_by_checksum(conn, &dyno.checksum, None)?
+    {
+        // content addressed, the new record reference the same stored data
+        Some(existing) => {
+            dyno.data.discard();
+            (existing.data_url, existing.data_size)
+        }
+        None => {
+            let last_dyno_id = dyno_actions::get_last_id(conn)?;
+            let data_url = storage::dyno_key(session.uuid, last_dyno_id + 1, &dyno_uuid);
+            dyno.data.save(storage, &data_url)?;
+            (data_url, data_size)
+        }
+    };
+    let saved = data_url.clone();
+    let inserted = dyno_actions::insert(
+        conn,
+        NewDynos {
+            user_id: session.id,
+            info_id,
+            uuid: dyno_uuid,
+            data_url,
+            data_checksum: dyno.checksum,
+            start: dyno.start,
+            stop: dyno.stop,
+            imported: dyno.imported,
+            data_size,
+        },
+    );
+    if inserted.is_err() && dyno_actions::count_by_data_url(conn, &saved).unwrap_or(1) == 0 {
+        if let Err(err) = storage.delete(&saved) {
+            dyno_core::log::error!("Failed to remove not inserted {saved} - {err}");
+        }
+    }
+    inserted
+}
+
+/// read multipart POST of spreadsheet import, `file` part is required, `spec` part is optional.
+async fn read_import_payload(
+    req: &HttpRequest,
+    mut payload: Multipart,
+    cfg: &crate::config::Upload,
+) -> UploadResult<(Sheet, Option<ImportSpec>)> {
+    let mut budget = UploadBudget::from_request(req, cfg)?;
+    let mut file = None;
+    let mut spec = None;
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(DynoErr::internal_server_error)?
+    {
+        let content_disposition = field.content_disposition().clone();
+        match content_disposition.get_name() {
+            Some("file") => {
+                let format = content_disposition
+                    .get_filename()
+                    .and_then(SheetFormat::from_file_name)
+                    .ok_or(DynoErr::bad_request_error(
+                        "Multipart POST 'file' part should be a .csv or .xlsx file",
+                    ))?;
+                file = Some((format, upload::read_field(&mut field, &mut budget).await?));
+            }
+            Some("spec") => {
+                let bytes = upload::read_field(&mut field, &mut budget).await?;
+                spec = Some(
+                    dyno_core::serde_json::from_slice::<ImportSpec>(&bytes).map_err(|err| {
+                        DynoErr::bad_request_error(format!(
+                            "Multipart POST 'spec' part is invalid - {err}"
+                        ))
+                    })?,
+                );
+            }
+            _ => continue,
+        }
+    }
+    let Some((format, bytes)) = file else {
+        return Err(DynoErr::bad_request_error("Multipart POST 'file' part is required").into());
+    };
+    let sheet = web::block(move || Sheet::parse(&bytes, format))
+        .await
+        .map_err(DynoErr::internal_server_error)??;
+    Ok((sheet, spec))
+}
+
+/// # Dynotest Endpoint `preview_import`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/import/preview`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(MULTIPART)     => `file` (.csv/.xlsx)
+///
+/// -----------------------------------------------------------------
+#[post("/dyno/import/preview")]
+pub async fn preview_import(
+    req: HttpRequest,
+    payload: Multipart,
+    JwtUserMiddleware(_session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> UploadResult<HttpResponse> {
+    let (sheet, _) = read_import_payload(&req, payload, &data.cfg.upload).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(sheet.preview())))
+}
+
+/// # Dynotest Endpoint `import_dyno`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/import`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(MULTIPART)     => `file` (.csv/.xlsx) + `spec` ['crate::import::ImportSpec']
+///
+/// -----------------------------------------------------------------
+#[post("/dyno/import")]
+pub async fn import_dyno(
+    req: HttpRequest,
+    payload: Multipart,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> UploadResult<HttpResponse> {
+    let (sheet, spec) = read_import_payload(&req, payload, &data.cfg.upload).await?;
+    let Some(ImportSpec {
+        mapping,
+        config,
+        start,
+    }) = spec
+    else {
+        return Err(DynoErr::bad_request_error("Multipart POST 'spec' part is required").into());
+    };
+
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    let quotas = data.cfg.quotas.clone();
+    let blk_result = web::block(move || {
+        let ImportedBuffer {
+            buffer,
+            duration_ms,
+        } = sheet.into_buffer(&mapping)?;
+        let compressed = buffer.compress()?;
+        let checksum = checksum::checksum_bytes(&compressed);
+
+        let mut conn = dbpool
+            .get()
+            .map_err(|_| DynoErr::database_error("Failed to get database connection"))?;
+        let actor_id = session.id;
+        actions::transaction(&mut conn, |conn| {
+            let id = store_dyno(
+                conn,
+                storage.as_ref(),
+                &quotas,
+                session,
+                StoredDyno {
+                    data: DynoData::Bytes(&compressed),
+                    checksum,
+                    config,
+                    start,
+                    stop: start + Duration::milliseconds(duration_ms),
+                    imported: true,
+                    on_duplicate: OnDuplicate::Return,
+                },
+            )?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(actor_id), "dyno.import")
+                    .target("dyno", id),
+            )?;
+            Ok(id)
         })
     })
     .await
     .map_err(DynoErr::internal_server_error)?;
 
-    blk_result.map(|id| HttpResponse::Ok().json(ApiResponse::success(id)))
+    blk_result
+        .map(|id| HttpResponse::Ok().json(ApiResponse::success(id)))
+        .map_err(From::from)
 }
 
 /// # Dynotest Endpoint `get_dyno`
@@ -149,6 +436,10 @@ pub async fn add_dyno(
 /// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
 /// BODY(JSON)          => ['crate::dyno_core::model::dynotests::DynoTestDataInfo']
 ///
+/// `class_id` list the dynotest submitted to the assignments of the class, only for the lecturer
+/// of the class or user with `dyno.read_all` permission, newest first.
+/// every list is limited to `max` (default 5) unless `all` is set.
+/// `tag` only list the dynotest that has the tag, see [`crate::handler::label`].
 /// -----------------------------------------------------------------
 #[get("/dyno")]
 pub async fn get_dyno(
@@ -157,17 +448,20 @@ pub async fn get_dyno(
         max,
         all,
         admin,
+        class_id,
+        tag,
     }): web::Query<DynoUrlsQueries>,
     JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
     data: web::Data<crate::ServerState>,
 ) -> DynoResult<HttpResponse> {
     let dbpool = data.db.clone();
 
-    let is_admin = session.role.is_admin();
+    let is_admin = read_all.is_some();
     let admin_query = admin.is_some_and(|x| x);
     if admin_query && !is_admin {
         return Err(DynoErr::unauthorized_error(
-            "NotAuthorized! Admin Access required!",
+            "NotAuthorized! Permission `dyno.read_all` required!",
         ));
     }
 
@@ -176,30 +470,39 @@ pub async fn get_dyno(
         dbpool
             .get()
             .map_err(DynoErr::database_error)
-            .and_then(|mut conn| match id {
-                Some(id) => dyno_actions::select_by_id(&mut conn, id)
+            .and_then(|mut conn| match (id, class_id) {
+                (Some(id), _) => dyno_actions::select_by_id(&mut conn, id)
+                    .and_then(|x| readable(&mut conn, x, &session, is_admin))
                     .map(|x| OneOrMany::One(Dynos::into_response(x))),
-                None => {
-                    if all.is_some_and(|x| x) {
-                        if admin_query && is_admin {
-                            dyno_actions::select_all(&mut conn)
-                        } else {
-                            dyno_actions::select_many(&mut conn, user_id)
+                (_, class_id) => {
+                    let tag = tag
+                        .map(|x| x.trim().to_lowercase())
+                        .filter(|x| !x.is_empty());
+                    let all = all.is_some_and(|x| x);
+                    let limit = (!all).then(|| max.unwrap_or(5).max(0));
+                    let dynos = match class_id {
+                        Some(class_id) => {
+                            class_actions::check_class_access(
+                                &mut conn, class_id, user_id, is_admin,
+                            )?;
+                            dyno_actions::select_submitted_to_class(
+                                &mut conn,
+                                class_id,
+                                tag.as_deref(),
+                                limit,
+                            )?
+                        }
+                        None => {
+                            let owner = (!(all && admin_query && is_admin)).then_some(user_id);
+                            dyno_actions::select_listed(&mut conn, owner, tag.as_deref(), limit)?
                         }
-                        .map(|x| {
-                            OneOrMany::Many(
-                                x.into_iter().map(Dynos::into_response).collect::<Vec<_>>(),
-                            )
-                        })
-                    } else {
-                        dyno_actions::select_many_limit(&mut conn, user_id, max.unwrap_or(5)).map(
-                            |x| {
-                                OneOrMany::Many(
-                                    x.into_iter().map(Dynos::into_response).collect::<Vec<_>>(),
-                                )
-                            },
-                        )
-                    }
+                    };
+                    Ok(OneOrMany::Many(
+                        dynos
+                            .into_iter()
+                            .map(Dynos::into_response)
+                            .collect::<Vec<_>>(),
+                    ))
                 }
             })
     })
@@ -228,6 +531,62 @@ pub enum FileType {
     Excel,
 }
 
+impl FileType {
+    /// convert the compressed `.dyno` bytes stored on disk into this [`FileType`]
+    pub fn convert(self, bytes: Vec<u8>) -> DynoResult<Vec<u8>> {
+        match self {
+            FileType::Bin => Ok(bytes),
+            FileType::Csv => BufferData::decompress(bytes).and_then(|x| x.save_csv_into_bytes()),
+            FileType::Excel => {
+                BufferData::decompress(bytes).and_then(|x| x.save_excel_into_bytes())
+            }
+            FileType::Json => BufferData::decompress(bytes).and_then(|x| {
+                dyno_core::serde_json::to_vec(&ApiResponse::success(x))
+                    .map_err(DynoErr::serialize_error)
+            }),
+        }
+    }
+
+    /// extension appended to the stored file name, [`FileType::Bin`] keep the name as is
+    pub const fn extension(self) -> Option<&'static str> {
+        match self {
+            FileType::Json => Some("json"),
+            FileType::Bin => None,
+            FileType::Csv => Some("csv"),
+            FileType::Excel => Some("xlsx"),
+        }
+    }
+
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            FileType::Json => "application/json",
+            FileType::Bin => "application/octet-stream",
+            FileType::Csv => "text/csv",
+            FileType::Excel => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    /// json is shown inline, other [`FileType`] is downloaded as attachment named `file`
+    pub fn response(self, file: impl std::fmt::Display, data: Vec<u8>) -> HttpResponse {
+        match self {
+            FileType::Json => HttpResponse::Ok()
+                .content_type(self.content_type())
+                .body(data),
+            _ => HttpResponse::Ok()
+                .append_header(header::ContentDisposition::attachment(self.file_name(file)))
+                .content_type(self.content_type())
+                .body(data),
+        }
+    }
+
+    pub fn file_name(self, file: impl std::fmt::Display) -> String {
+        match self.extension() {
+            Some(ext) => format!("{file}.{ext}"),
+            None => file.to_string(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, serde::Deserialize, serde::Serialize, PartialEq)]
 #[serde(crate = "serde")]
 pub struct QueryFile {
@@ -235,61 +594,445 @@ pub struct QueryFile {
     pub tp: FileType,
 }
 
-#[get("/dyno/{user_uuid}/{file}")]
+/// only return the [`Dynos`] when it is readable by the `session`, see [`dyno_actions::READABLE_DYNO`]
+pub(crate) fn readable(
+    conn: &mut DynoDBPooledConnection,
+    dyno: Dynos,
+    session: &UserSession,
+    read_all: bool,
+) -> DynoResult<Dynos> {
+    if dyno_actions::is_readable(conn, dyno.id, session.id, read_all)? {
+        Ok(dyno)
+    } else {
+        Err(DynoErr::forbidden_error(
+            "Dynotest is only accessible by its owner, lecturer or user with `dyno.read_all` permission",
+        ))
+    }
+}
+
+/// # Dynotest Endpoint `get_file`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/file{query}` [query = `QueryFile`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(BINARY)        => data of the dynotest converted into `tp`
+///
+/// only the owner, lecturer of the owner, user with `dyno.read_all` permission, or any user when
+/// the dynotest is `shared` can download it.
+/// -----------------------------------------------------------------
+#[get("/dyno/{id}/file")]
 pub async fn get_file(
     web::Query(QueryFile { tp }): web::Query<QueryFile>,
-    path: Path<(String, String)>,
-    JwtUserMiddleware(_session): JwtUserMiddleware,
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
     data: web::Data<crate::ServerState>,
 ) -> DynoResult<HttpResponse> {
-    let (user_uuid, file) = path.into_inner();
-    let dyno_path = data
-        .cfg
-        .app_public_path
-        .join("dyno")
-        .join(user_uuid)
-        .join(&file);
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    let read_all = read_all.is_some();
 
     web::block(move || {
-        std::fs::read(dyno_path)
-            .map_err(DynoErr::internal_server_error)
-            .and_then(|bytes| match tp {
-                FileType::Bin => Ok(bytes),
-                FileType::Csv => {
-                    BufferData::decompress(bytes).and_then(|x| x.save_csv_into_bytes())
-                }
-                FileType::Excel => {
-                    BufferData::decompress(bytes).and_then(|x| x.save_excel_into_bytes())
-                }
-                FileType::Json => BufferData::decompress(bytes).and_then(|x| {
-                    dyno_core::serde_json::to_vec(&ApiResponse::success(x))
-                        .map_err(DynoErr::serialize_error)
-                }),
-            })
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let dyno = dyno_actions::select_by_id(&mut conn, id)
+            .and_then(|dyno| readable(&mut conn, dyno, &session, read_all))?;
+        let file = format!("{}-{}.dyno", dyno.id, dyno.uuid);
+        storage
+            .get(&dyno.data_url)
+            .and_then(|bytes| tp.convert(bytes))
+            .map(|data| (file, data))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|(file, data)| tp.response(file, data))
+}
+
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryShared {
+    pub shared: bool,
+}
+
+/// # Dynotest Endpoint `share_dyno`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/share?shared={bool}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => `shared` value that is saved
+///
+/// only the owner can share or unshare the dynotest.
+/// -----------------------------------------------------------------
+#[post("/dyno/{id}/share")]
+pub async fn share_dyno(
+    web::Query(QueryShared { shared }): web::Query<QueryShared>,
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            if dyno_actions::update_shared(conn, id, session.id, shared)? == 0 {
+                return Err(DynoErr::forbidden_error(
+                    "Dynotest is not exists or not owned by the user",
+                ));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "dyno.share")
+                    .target("dyno", id)
+                    .details(&shared),
+            )
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(shared)))
+}
+
+/// # Dynotest Endpoint `delete_dyno`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => `id` of deleted dynotest
+///
+/// only the owner or user with `user.manage` permission can delete, the stored data is removed
+/// after the commit when no other dynotest reference it anymore. dynotest of a graded submission is refused with
+/// `409 Conflict`, it is removed from the not graded submission.
+/// -----------------------------------------------------------------
+#[delete("/dyno/{id}")]
+pub async fn delete_dyno(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    manager: Option<RequirePermission<perm::UserManage>>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    let is_manager = manager.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let deleted = actions::transaction(&mut conn, |conn| {
+            let dyno = dyno_actions::select_by_id(conn, id)?;
+            if dyno.user_id != session.id && !is_manager {
+                return Err(DynoErr::forbidden_error(
+                    "Dynotest can only be deleted by its owner or user with `user.manage` permission",
+                ));
+            }
+            if assignment_actions::is_dyno_graded(conn, id)? {
+                return Ok(Err(
+                    "Dynotest is submitted into a graded submission and can not be deleted",
+                ));
+            }
+            assignment_actions::delete_submission_dynos_by_dyno(conn, id)?;
+            share_actions::delete_by_dyno(conn, id)?;
+            comment_actions::delete_by_dyno(conn, id)?;
+            label_actions::delete_by_dyno(conn, id)?;
+            dyno_actions::delete(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "dyno.delete")
+                    .target("dyno", id)
+                    .diff(Some(&dyno), None::<&Dynos>),
+            )?;
+            Ok(Ok(dyno))
+        })?;
+        let dyno = match deleted {
+            Ok(dyno) => dyno,
+            Err(reason) => return Ok(Err(reason)),
+        };
+        // re-checked after the commit, new upload of the same checksum either committed its
+        // reference before the delete, or store its data under a new key after it
+        if dyno_actions::count_by_data_url(&mut conn, &dyno.data_url)? == 0 {
+            if let Err(err) = storage.delete(&dyno.data_url) {
+                dyno_core::log::error!("Failed to remove {} - {err}", dyno.data_url);
+            }
+        }
+        Ok(Ok(id))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|deleted| match deleted {
+        Ok(id) => HttpResponse::Ok().json(ApiResponse::success(id)),
+        Err(reason) => super::conflict(reason),
+    })
+}
+
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryVerified {
+    pub verified: bool,
+}
+
+/// # Dynotest Endpoint `verify_dyno`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/verify?verified={bool}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `dyno.verify`
+/// RESPONSE(JSON)      => `verified` value that is saved
+///
+/// verified dynotest is not archived by the retention job.
+/// -----------------------------------------------------------------
+#[post("/dyno/{id}/verify")]
+pub async fn verify_dyno(
+    web::Query(QueryVerified { verified }): web::Query<QueryVerified>,
+    id: Path<i64>,
+    RequirePermission(session, ..): RequirePermission<perm::DynoVerify>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let before = dyno_actions::select_by_id(conn, id)
+                .map_err(|_| DynoErr::not_found_error("Dynotest is not exists"))?;
+            if dyno_actions::update_verified(conn, id, verified)? == 0 {
+                return Err(DynoErr::not_found_error("Dynotest is not exists"));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "dyno.verify")
+                    .target("dyno", id)
+                    .diff(Some(&before.verified), Some(&Some(verified))),
+            )
+        })
     })
     .await
     .map_err(DynoErr::internal_server_error)?
-    .map(|data| match tp {
-        FileType::Bin => HttpResponse::Ok()
-            .append_header(header::ContentDisposition::attachment(file))
-            .content_type("application/octet-stream")
-            .body(data),
-        FileType::Csv => HttpResponse::Ok()
-            .append_header(header::ContentDisposition::attachment(format!(
-                "{}.csv",
-                file
-            )))
-            .content_type("text/csv")
-            .body(data),
-        FileType::Excel => HttpResponse::Ok()
-            .append_header(header::ContentDisposition::attachment(format!(
-                "{}.xlsx",
-                file
-            )))
-            .content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
-            .body(data),
-        FileType::Json => HttpResponse::Ok()
-            .content_type("application/json")
-            .body(data),
+    .map(|_| HttpResponse::Ok().json(ApiResponse::success(verified)))
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct DynoExportQueries {
+    pub user_id: Option<i64>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub verified: Option<bool>,
+    #[serde(default)]
+    pub tp: FileType,
+}
+
+/// size of every chunk that sended to the client while streaming the archive
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// [`std::io::Write`] that forward every [`EXPORT_CHUNK_SIZE`] bytes into a bounded channel,
+/// so the archive is never fully buffered in memory.
+struct ChunkWriter {
+    tx: mpsc::Sender<Result<web::Bytes, std::io::Error>>,
+    buffer: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn new(tx: mpsc::Sender<Result<web::Bytes, std::io::Error>>) -> Self {
+        Self {
+            tx,
+            buffer: Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        }
+    }
+
+    fn send(&mut self, chunk: Result<web::Bytes, std::io::Error>) -> std::io::Result<()> {
+        futures::executor::block_on(self.tx.send(chunk))
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::BrokenPipe, err))
+    }
+}
+
+impl std::io::Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= EXPORT_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = web::Bytes::from(std::mem::replace(
+            &mut self.buffer,
+            Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        ));
+        self.send(Ok(chunk))
+    }
+}
+
+const MANIFEST_HEADERS: [&str; 23] = [
+    "id",
+    "uuid",
+    "user_id",
+    "file",
+    "data_checksum",
+    "verified",
+    "start",
+    "stop",
+    "created_at",
+    "info_id",
+    "motor_type",
+    "name",
+    "cc",
+    "cylinder",
+    "stroke",
+    "diameter_roller",
+    "diameter_roller_beban",
+    "diameter_gear_encoder",
+    "diameter_gear_beban",
+    "jarak_gear",
+    "berat_beban",
+    "gaya_beban",
+    "keliling_roller",
+];
+
+fn manifest_record(dyno: &Dynos, info: Option<&DynoInfo>, file: &str) -> Vec<String> {
+    fn opt<T: ToString>(value: Option<T>) -> String {
+        value.map(|x| x.to_string()).unwrap_or_default()
+    }
+    vec![
+        dyno.id.to_string(),
+        dyno.uuid.to_string(),
+        dyno.user_id.to_string(),
+        file.to_owned(),
+        dyno.data_checksum.clone(),
+        dyno.verified.is_some_and(|x| x).to_string(),
+        dyno.start.to_string(),
+        dyno.stop.to_string(),
+        dyno.created_at.to_string(),
+        opt(dyno.info_id),
+        opt(info.map(|x| x.motor_type)),
+        opt(info.and_then(|x| x.name.clone())),
+        opt(info.and_then(|x| x.cc)),
+        opt(info.and_then(|x| x.cylinder)),
+        opt(info.and_then(|x| x.stroke)),
+        opt(info.and_then(|x| x.diameter_roller)),
+        opt(info.and_then(|x| x.diameter_roller_beban)),
+        opt(info.and_then(|x| x.diameter_gear_encoder)),
+        opt(info.and_then(|x| x.diameter_gear_beban)),
+        opt(info.and_then(|x| x.jarak_gear)),
+        opt(info.and_then(|x| x.berat_beban)),
+        opt(info.and_then(|x| x.gaya_beban)),
+        opt(info.and_then(|x| x.keliling_roller)),
+    ]
+}
+
+fn write_export_archive(
+    writer: ChunkWriter,
+    dynos: Vec<(Dynos, Option<DynoInfo>)>,
+    storage: DynoStorage,
+    tp: FileType,
+) -> DynoResult<()> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new_stream(writer);
+    let mut manifest = csv::Writer::from_writer(Vec::new());
+    manifest
+        .write_record(MANIFEST_HEADERS)
+        .map_err(DynoErr::serialize_error)?;
+
+    for (dyno, info) in dynos {
+        let file = match storage
+            .get(&dyno.data_url)
+            .and_then(|bytes| tp.convert(bytes))
+        {
+            Ok(converted) => {
+                let name = tp.file_name(format!("{}-{}.dyno", dyno.id, dyno.uuid));
+                zip.start_file(format!("dynos/{name}"), options)
+                    .map_err(DynoErr::internal_server_error)?;
+                zip.write_all(&converted)
+                    .map_err(DynoErr::internal_server_error)?;
+                name
+            }
+            Err(err) => {
+                dyno_core::log::error!(
+                    "Failed to export dyno {} from {} - {err}",
+                    dyno.id,
+                    dyno.data_url
+                );
+                String::new()
+            }
+        };
+        manifest
+            .write_record(manifest_record(&dyno, info.as_ref(), &file))
+            .map_err(DynoErr::serialize_error)?;
+    }
+
+    let manifest = manifest.into_inner().map_err(DynoErr::serialize_error)?;
+    zip.start_file("manifest.csv", options)
+        .map_err(DynoErr::internal_server_error)?;
+    zip.write_all(&manifest)
+        .map_err(DynoErr::internal_server_error)?;
+    zip.finish()
+        .map_err(DynoErr::internal_server_error)?
+        .into_inner()
+        .flush()
+        .map_err(DynoErr::internal_server_error)
+}
+
+/// # Dynotest Endpoint `export_dyno`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/export{query}` [query = `DynoExportQueries`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `dyno.read_all`
+/// BODY(ZIP)           => one file per dynotest in `tp` format + `manifest.csv`
+///
+/// -----------------------------------------------------------------
+#[get("/dyno/export")]
+pub async fn export_dyno(
+    web::Query(DynoExportQueries {
+        user_id,
+        from,
+        to,
+        verified,
+        tp,
+    }): web::Query<DynoExportQueries>,
+    _: RequirePermission<perm::DynoReadAll>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    let filter = DynoFilter {
+        user_id,
+        from,
+        to,
+        verified,
+    };
+    let dynos = web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| dyno_actions::select_filtered(&mut conn, &filter))
     })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    let storage = data.storage.clone();
+    let (tx, rx) = mpsc::channel(4);
+    actix_web::rt::task::spawn_blocking(move || {
+        let mut error_tx = tx.clone();
+        if let Err(err) = write_export_archive(ChunkWriter::new(tx), dynos, storage, tp) {
+            dyno_core::log::error!("Failed to write export archive - {err}");
+            let err = std::io::Error::new(std::io::ErrorKind::Other, err.to_string());
+            let _ = futures::executor::block_on(error_tx.send(Err(err)));
+        }
+    });
+
+    let file_name = format!(
+        "dynotests-{}.zip",
+        dyno_core::chrono::Local::now().format("%Y%m%d%H%M%S")
+    );
+    Ok(HttpResponse::Ok()
+        .append_header(header::ContentDisposition::attachment(file_name))
+        .content_type("application/zip")
+        .streaming(rx))
 }
@@ -0,0 +1,200 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use actix_web::{get, web, HttpResponse};
+use dyno_core::{DynoErr, DynoResult};
+
+use crate::{
+    actions::health as health_actions,
+    handler::ws::HEARTBEAT_INTERVAL,
+    models::health::{HealthCheck, HealthReport, HealthStatus},
+    storage::DynoStorage,
+    token,
+};
+
+/// the broadcast loop is considered dead after this many missed [`HEARTBEAT_INTERVAL`]
+const MISSED_HEARTBEATS: u32 = 5;
+/// probe should fail fast instead of waiting for the default pool timeout
+const DB_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// run the `check` and measure its latency, `Ok` is the detail of the passed check
+fn run_check(check: impl FnOnce() -> DynoResult<Option<String>>) -> HealthCheck {
+    let start = Instant::now();
+    let result = check();
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(detail) => HealthCheck {
+            status: HealthStatus::Ok,
+            latency_ms,
+            detail,
+        },
+        Err(err) => HealthCheck {
+            status: HealthStatus::Fail,
+            latency_ms,
+            detail: Some(err.to_string()),
+        },
+    }
+}
+
+fn check_database(state: &crate::ServerState) -> DynoResult<Option<String>> {
+    let mut conn = state
+        .db
+        .get_timeout(DB_TIMEOUT)
+        .map_err(DynoErr::database_error)?;
+    health_actions::ping(&mut conn).map(|_| None)
+}
+
+fn check_migrations(state: &crate::ServerState) -> DynoResult<Option<String>> {
+    let mut conn = state
+        .db
+        .get_timeout(DB_TIMEOUT)
+        .map_err(DynoErr::database_error)?;
+    match health_actions::pending_migrations(&mut conn)?.as_slice() {
+        [] => Ok(None),
+        pending => Err(DynoErr::database_error(format!(
+            "{} migrations is not applied: {}",
+            pending.len(),
+            pending.join(", ")
+        ))),
+    }
+}
+
+/// put, read back and delete a hidden probe object through the [`crate::storage::Storage`],
+/// so the check is the same for the local folder and S3
+fn check_storage(storage: &DynoStorage) -> DynoResult<Option<String>> {
+    let key = format!(".health/{}", token::generate_token());
+    let probe = b"health";
+    storage.put_bytes(&key, probe)?;
+    let read = storage.get(&key);
+    storage.delete(&key)?;
+    if read? != probe {
+        return Err(DynoErr::internal_server_error(format!(
+            "Probe object `{key}` is read back with different content"
+        )));
+    }
+    Ok(Some(storage.name().to_owned()))
+}
+
+/// create and write a temporary file, it is removed when dropped. the folder is created when it
+/// is missing, the same as the upload does.
+fn check_writable(path: &Path) -> DynoResult<Option<String>> {
+    std::fs::create_dir_all(path)
+        .and_then(|_| tempfile::NamedTempFile::new_in(path))
+        .and_then(|mut file| file.write_all(b"health").and_then(|_| file.flush()))
+        .map(|_| None)
+        .map_err(|err| {
+            DynoErr::internal_server_error(format!("{} is not writable - {err}", path.display()))
+        })
+}
+
+/// every path should have at least `min_free_mb` free, the detail is the free space of each path
+fn check_disk(paths: &[&Path], min_free_mb: u64) -> DynoResult<Option<String>> {
+    let mut details = Vec::with_capacity(paths.len());
+    for path in paths {
+        let free_mb = fs2::available_space(path).map_err(|err| {
+            DynoErr::internal_server_error(format!(
+                "Failed to get free space of {} - {err}",
+                path.display()
+            ))
+        })? / (1024 * 1024);
+        if free_mb < min_free_mb {
+            return Err(DynoErr::internal_server_error(format!(
+                "{} has {free_mb} MB free, less than the minimum {min_free_mb} MB",
+                path.display()
+            )));
+        }
+        details.push(format!("{} has {free_mb} MB free", path.display()));
+    }
+    Ok(Some(details.join(", ")))
+}
+
+fn check_websocket(state: &crate::ServerState) -> DynoResult<Option<String>> {
+    match state.ws_heartbeat.elapsed() {
+        None => Err(DynoErr::internal_server_error(
+            "Websocket broadcaster is not started",
+        )),
+        Some(elapsed) if elapsed > HEARTBEAT_INTERVAL * MISSED_HEARTBEATS => {
+            Err(DynoErr::internal_server_error(format!(
+                "Websocket broadcaster is not responding for {} seconds",
+                elapsed.as_secs()
+            )))
+        }
+        Some(_) => Ok(Some(format!(
+            "{} clients connected",
+            state.metrics.ws_clients()
+        ))),
+    }
+}
+
+fn report_response(report: HealthReport) -> HttpResponse {
+    match report.status {
+        HealthStatus::Ok => HttpResponse::Ok().json(report),
+        HealthStatus::Fail => HttpResponse::ServiceUnavailable().json(report),
+    }
+}
+
+/// # Health Endpoint `health_live`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/health/live`
+/// GUARD               => `GET`
+/// RESPONSE(JSON)      => ['crate::models::health::HealthReport'] without `checks`
+///
+/// the process is running and responding, nothing else is checked so the container is only
+/// restarted when it is really stuck. not wrapped in `ApiResponse` so probes read it directly.
+/// -----------------------------------------------------------------
+#[get("/health/live")]
+pub async fn health_live(data: web::Data<crate::ServerState>) -> HttpResponse {
+    report_response(HealthReport::new(
+        data.started_at.elapsed().as_secs(),
+        BTreeMap::new(),
+    ))
+}
+
+/// # Health Endpoint `health_ready`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/health/ready`
+/// GUARD               => `GET`
+/// RESPONSE(JSON)      => ['crate::models::health::HealthReport'], `503` when any check is failed
+///
+/// checks `database` (simple query), `migrations` (every embedded migration is applied),
+/// `storage` (probe object through the configured storage), `upload` (`cfg.upload.tmp_path` is
+/// writable), `disk` (free space of the local storage and upload folder is at least
+/// `DYNO_HEALTH_MIN_FREE_MB`) and `websocket` (the broadcast loop is running).
+/// -----------------------------------------------------------------
+#[get("/health/ready")]
+pub async fn health_ready(data: web::Data<crate::ServerState>) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    let report = web::block(move || {
+        let tmp_path = state.cfg.upload.tmp_path.as_path();
+        // S3 has no local free space, only the upload folder is on the disk
+        let disk_paths = match state.cfg.storage.s3 {
+            Some(_) => vec![tmp_path],
+            None => vec![state.cfg.storage.local_path.as_path(), tmp_path],
+        };
+        let mut checks = BTreeMap::new();
+        checks.insert("database", run_check(|| check_database(&state)));
+        checks.insert("migrations", run_check(|| check_migrations(&state)));
+        // before `disk`, the probe create the local storage folder when it is missing
+        checks.insert("storage", run_check(|| check_storage(&state.storage)));
+        checks.insert("upload", run_check(|| check_writable(tmp_path)));
+        checks.insert(
+            "disk",
+            run_check(|| check_disk(&disk_paths, state.cfg.health_min_free_mb)),
+        );
+        checks.insert("websocket", run_check(|| check_websocket(&state)));
+        HealthReport::new(state.started_at.elapsed().as_secs(), checks)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?;
+
+    for (name, check) in &report.checks {
+        if let (HealthStatus::Fail, Some(detail)) = (check.status, &check.detail) {
+            dyno_core::log::warn!("Readiness check `{name}` is failed - {detail}");
+        }
+    }
+    Ok(report_response(report))
+}
@@ -2,7 +2,11 @@ use actix_web::{get, web, HttpResponse, Responder};
 use dyno_core::{users::OneOrMany, ApiResponse, DynoErr};
 
 use crate::{
-    actions::history as history_actions, handler::DynoUrlsQueries, middlewares::JwtUserMiddleware,
+    actions::{
+        assignment as assignment_actions, class as class_actions, history as history_actions,
+    },
+    handler::DynoUrlsQueries,
+    middlewares::{perm, JwtUserMiddleware, RequirePermission},
     models::history::History,
 };
 
@@ -13,15 +17,18 @@ pub async fn history(
         all,
         max,
         admin,
+        class_id,
+        tag: _,
     }): web::Query<DynoUrlsQueries>,
     JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
-    let is_admin = session.role.is_admin();
+    let is_admin = read_all.is_some();
     let admin_query = admin.is_some_and(|x| x);
     if admin_query && !is_admin {
         return Err(DynoErr::unauthorized_error(
-            "NotAuthorized! Admin Access required!",
+            "NotAuthorized! Permission `dyno.read_all` required!",
         ));
     }
     let user_id = session.id;
@@ -29,10 +36,21 @@ pub async fn history(
         data.db
             .get()
             .map_err(DynoErr::database_error)
-            .and_then(|mut conn| match id {
-                Some(id) => history_actions::select(&mut conn, id)
+            .and_then(|mut conn| match (id, class_id) {
+                (Some(id), _) => history_actions::select(&mut conn, id)
                     .map(|x| OneOrMany::One(History::into_response(x))),
-                None => {
+                (None, Some(class_id)) => {
+                    class_actions::check_class_access(&mut conn, class_id, user_id, is_admin)?;
+                    let user_ids = assignment_actions::select_submitter_ids(&mut conn, class_id)?;
+                    history_actions::select_by_users(&mut conn, &user_ids).map(|x| {
+                        OneOrMany::Many(
+                            x.into_iter()
+                                .map(History::into_response)
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                }
+                (None, None) => {
                     if all.is_some_and(|x| x) {
                         if admin_query && is_admin {
                             history_actions::select_all(&mut conn)
@@ -1,7 +1,11 @@
 use actix_web::{get, web, HttpResponse, Responder};
 use dyno_core::{users::OneOrMany, ApiResponse, DynoErr};
 
-use crate::{actions, handler::DynoUrlsQueries, middlewares::JwtUserMiddleware};
+use crate::{
+    actions,
+    handler::DynoUrlsQueries,
+    middlewares::{perm, JwtUserMiddleware, RequirePermission},
+};
 
 #[get("/info")]
 pub async fn get_info(
@@ -10,15 +14,18 @@ pub async fn get_info(
         all,
         max: _,
         admin,
+        class_id: _,
+        tag: _,
     }): web::Query<DynoUrlsQueries>,
     JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
-    let is_admin = session.role.is_admin();
+    let is_admin = read_all.is_some();
     let admin_query = admin.is_some_and(|x| x);
     if admin.is_some_and(|x| x) && !is_admin {
         return Err(DynoErr::unauthorized_error(
-            "NotAuthorized! Admin Access required!",
+            "NotAuthorized! Permission `dyno.read_all` required!",
         ));
     }
     let ret = web::block(move || {
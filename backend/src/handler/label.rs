@@ -0,0 +1,218 @@
+use actix_web::{
+    get, put,
+    web::{self, Path},
+    HttpResponse,
+};
+use dyno_core::{ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{self, audit_log as audit_log_actions, dyno as dyno_actions, label as label_actions},
+    handler::dyno::readable,
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequirePermission},
+    models::{dyno::Dynos, label::DynoLabels},
+};
+
+const MAX_TITLE_LENGTH: usize = 128;
+const MAX_NOTES_LENGTH: usize = 4096;
+const MAX_TAG_LENGTH: usize = 32;
+const MAX_TAGS: usize = 16;
+const MAX_KEY_LENGTH: usize = 64;
+const MAX_VALUE_LENGTH: usize = 1024;
+const MAX_METADATA: usize = 32;
+
+/// trim every field, drop the empty one and check the length of it
+fn sanitize(labels: DynoLabels) -> DynoResult<DynoLabels> {
+    fn non_empty(x: Option<String>) -> Option<String> {
+        x.map(|x| x.trim().to_owned()).filter(|x| !x.is_empty())
+    }
+
+    let title = non_empty(labels.title);
+    if title.as_ref().is_some_and(|x| x.len() > MAX_TITLE_LENGTH) {
+        return Err(DynoErr::bad_request_error(format!(
+            "Title should not be longer than {MAX_TITLE_LENGTH} characters"
+        )));
+    }
+    let notes = non_empty(labels.notes);
+    if notes.as_ref().is_some_and(|x| x.len() > MAX_NOTES_LENGTH) {
+        return Err(DynoErr::bad_request_error(format!(
+            "Notes should not be longer than {MAX_NOTES_LENGTH} characters"
+        )));
+    }
+
+    let mut tags = Vec::<String>::new();
+    for tag in labels.tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tags.contains(&tag) {
+            continue;
+        }
+        if tag.len() > MAX_TAG_LENGTH {
+            return Err(DynoErr::bad_request_error(format!(
+                "Tag should not be longer than {MAX_TAG_LENGTH} characters"
+            )));
+        }
+        tags.push(tag);
+    }
+    if tags.len() > MAX_TAGS {
+        return Err(DynoErr::bad_request_error(format!(
+            "Dynotest can only have {MAX_TAGS} tags"
+        )));
+    }
+
+    let mut metadata = std::collections::BTreeMap::new();
+    for (key, value) in labels.metadata {
+        let key = key.trim().to_owned();
+        if key.is_empty() || key.len() > MAX_KEY_LENGTH || value.len() > MAX_VALUE_LENGTH {
+            return Err(DynoErr::bad_request_error(format!(
+                "Metadata key should not be empty or longer than {MAX_KEY_LENGTH} characters, \
+                 and the value not longer than {MAX_VALUE_LENGTH} characters"
+            )));
+        }
+        metadata.insert(key, value.trim().to_owned());
+    }
+    if metadata.len() > MAX_METADATA {
+        return Err(DynoErr::bad_request_error(format!(
+            "Dynotest can only have {MAX_METADATA} metadata"
+        )));
+    }
+
+    Ok(DynoLabels {
+        dyno_id: labels.dyno_id,
+        title,
+        notes,
+        tags,
+        metadata,
+    })
+}
+
+/// # Label Endpoint `get_labels`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/labels`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::label::DynoLabels'>]
+///
+/// title, notes, tags and metadata of every dynotest owned by the user.
+/// -----------------------------------------------------------------
+#[get("/dyno/labels")]
+pub async fn get_labels(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let ids = dyno_actions::select_many(&mut conn, session.id)?
+            .into_iter()
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+        label_actions::select_by_dynos(&mut conn, &ids)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|labels| HttpResponse::Ok().json(ApiResponse::success(labels)))
+}
+
+/// # Label Endpoint `get_tags`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/tags`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<String>]
+///
+/// every distinct tag used in the dynotest of the user, for the tag filter.
+/// -----------------------------------------------------------------
+#[get("/dyno/tags")]
+pub async fn get_tags(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        label_actions::select_tags_by_user(&mut conn, session.id)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|tags| HttpResponse::Ok().json(ApiResponse::success(tags)))
+}
+
+/// # Label Endpoint `get_dyno_labels`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/labels`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::label::DynoLabels']
+/// -----------------------------------------------------------------
+#[get("/dyno/{id}/labels")]
+pub async fn get_dyno_labels(
+    id: Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    let read_all = read_all.is_some();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        dyno_actions::select_by_id(&mut conn, id)
+            .and_then(|dyno| readable(&mut conn, dyno, &session, read_all))?;
+        label_actions::select_by_dynos(&mut conn, &[id])?
+            .pop()
+            .ok_or(DynoErr::not_found_error("Dynotest is not exists"))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|labels| HttpResponse::Ok().json(ApiResponse::success(labels)))
+}
+
+/// # Label Endpoint `update_dyno_labels`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/labels`
+/// GUARD               => `PUT`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['crate::models::label::DynoLabels']
+/// RESPONSE(JSON)      => ['crate::models::label::DynoLabels']
+///
+/// replace the title, notes, tags and metadata of the dynotest, only by the owner.
+/// tags are lowercased and deduplicated.
+/// -----------------------------------------------------------------
+#[put("/dyno/{id}/labels")]
+pub async fn update_dyno_labels(
+    id: Path<i64>,
+    web::Json(labels): web::Json<DynoLabels>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let labels = sanitize(labels)?;
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let Dynos { user_id, .. } = dyno_actions::select_by_id(conn, id)?;
+            if user_id != session.id {
+                return Err(DynoErr::forbidden_error(
+                    "Dynotest labels can only be edited by its owner",
+                ));
+            }
+            let before = label_actions::select_by_dynos(conn, &[id])?.pop();
+            label_actions::replace(conn, id, labels)?;
+            let after = label_actions::select_by_dynos(conn, &[id])?
+                .pop()
+                .ok_or(DynoErr::not_found_error("Dynotest is not exists"))?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "dyno.labels")
+                    .target("dyno", id)
+                    .diff(before.as_ref(), Some(&after)),
+            )?;
+            Ok(after)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|labels| HttpResponse::Ok().json(ApiResponse::success(labels)))
+}
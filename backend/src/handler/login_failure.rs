@@ -0,0 +1,45 @@
+use actix_web::{get, web, HttpResponse};
+use dyno_core::{serde, ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::login_failure as login_failure_actions,
+    middlewares::{perm, RequirePermission},
+};
+
+const DEFAULT_MAX: i64 = 100;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryLoginFailure {
+    pub nim: Option<String>,
+    pub max: Option<i64>,
+}
+
+/// # Login Failure Endpoint `get_login_failures`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/login-failures?nim={nim}&max={max}`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => [Vec<'crate::models::login_failure::LoginFailure'>]
+///
+/// audit trail of failed login, latest first, `max` default to 100.
+/// -----------------------------------------------------------------
+#[get("/admin/login-failures")]
+pub async fn get_login_failures(
+    web::Query(QueryLoginFailure { nim, max }): web::Query<QueryLoginFailure>,
+    _: RequirePermission<perm::UserManage>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    let max = max.unwrap_or(DEFAULT_MAX).clamp(1, 1000);
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| login_failure_actions::select_many(&mut conn, nim.as_deref(), max))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|failures| HttpResponse::Ok().json(ApiResponse::success(failures)))
+}
@@ -0,0 +1,85 @@
+use actix_web::{get, http::header, web, HttpRequest, HttpResponse};
+use dyno_core::{chrono::Utc, crypto::compare_checksums, DynoErr, DynoResult};
+
+use crate::metrics::gauge;
+
+/// `Authorization: Bearer {DYNO_METRICS_TOKEN}` when the token is configured
+fn authorized(req: &HttpRequest, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.strip_prefix("Bearer "))
+        .is_some_and(|x| compare_checksums(x.as_bytes(), token.as_bytes()))
+}
+
+/// # Metrics Endpoint `get_metrics`
+/// -----------------------------------------------------------------
+/// URL                 => `/metrics`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => `Authorization: Bearer {DYNO_METRICS_TOKEN}` when it is set
+/// RESPONSE(TEXT)      => prometheus text format `0.0.4`
+///
+/// HTTP requests and latency per route, database pool, websocket clients and frames,
+/// upload bytes and the active session of the station.
+/// -----------------------------------------------------------------
+#[get("/metrics")]
+pub async fn get_metrics(
+    req: HttpRequest,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    if !authorized(&req, data.cfg.metrics_token.as_deref()) {
+        return Err(DynoErr::unauthorized_error(
+            "Metrics token is invalid, please provide `Authorization: Bearer` header",
+        ));
+    }
+
+    let mut out = String::new();
+    data.metrics.encode(&mut out);
+
+    let pool = data.db.state();
+    gauge(
+        &mut out,
+        "dyno_db_pool_max_connections",
+        "Maximum connections of the database pool",
+        data.db.max_size(),
+    );
+    gauge(
+        &mut out,
+        "dyno_db_pool_connections",
+        "Open connections of the database pool",
+        pool.connections,
+    );
+    gauge(
+        &mut out,
+        "dyno_db_pool_idle_connections",
+        "Idle connections of the database pool",
+        pool.idle_connections,
+    );
+    gauge(
+        &mut out,
+        "dyno_db_pool_in_use_connections",
+        "Connections of the database pool that is checked out",
+        pool.connections.saturating_sub(pool.idle_connections),
+    );
+
+    let active = data.get_active();
+    gauge(
+        &mut out,
+        "dyno_active_sessions",
+        "Active dynotest session of the station, 0 or 1",
+        u8::from(active.is_some()),
+    );
+    gauge(
+        &mut out,
+        "dyno_active_session_seconds",
+        "Seconds since the active session is started",
+        active.map_or(0, |x| (Utc::now() - x.start).num_seconds().max(0)),
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(out))
+}
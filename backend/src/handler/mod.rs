@@ -1,12 +1,33 @@
-use actix_web::{guard::GuardContext, http::header, web::ServiceConfig};
+use actix_web::web::ServiceConfig;
 use dyno_core::{ActiveResponse, ApiResponse, DynoConfig, DynoErr, DynoResult};
 
-use crate::{actions, middlewares::JwtUserMiddleware, models::user::User};
+use crate::{
+    actions,
+    middlewares::{ApiKeyMiddleware, JwtUserMiddleware},
+    models::{api_key::ApiScope, user::User},
+};
 
+pub mod api_key;
+pub mod approval;
+pub mod assignment;
+pub mod audit;
+pub mod audit_log;
 pub mod auth;
+pub mod class;
+pub mod comment;
 pub mod dyno;
+pub mod health;
 pub mod history;
 pub mod info;
+pub mod label;
+pub mod login_failure;
+pub mod metrics;
+pub mod permission;
+pub mod resumable;
+pub mod search;
+pub mod share;
+pub mod two_factor;
+pub mod usage;
 pub mod user;
 pub mod ws;
 
@@ -16,24 +37,94 @@ pub fn api(conf: &mut ServiceConfig) {
     conf.service(
         web::scope("/api")
             .service(check_health)
+            .service(health::health_live)
+            .service(health::health_ready)
             .service(auth::me)
             .service(auth::register_user)
             .service(auth::login_user)
             .service(auth::logout_user)
+            .service(auth::forgot_password)
+            .service(auth::reset_password)
+            .service(auth::verify_email)
+            .service(auth::resend_verification)
+            .service(two_factor::get_two_factor)
+            .service(two_factor::setup_two_factor)
+            .service(two_factor::enable_two_factor)
+            .service(two_factor::login_two_factor)
+            .service(two_factor::regenerate_recovery_codes)
+            .service(two_factor::disable_two_factor)
+            .service(two_factor::reset_two_factor)
             .service(user::get_user)
             .service(user::add_user)
             .service(user::update_user)
             .service(user::delete_user)
+            .service(dyno::export_dyno)
+            .service(label::get_labels)
+            .service(label::get_tags)
             .service(dyno::get_dyno)
             .service(dyno::add_dyno)
+            .service(dyno::delete_dyno)
+            .service(dyno::preview_import)
+            .service(dyno::import_dyno)
+            .service(resumable::create_upload)
+            .service(resumable::get_upload)
+            .service(resumable::put_upload_chunk)
+            .service(resumable::finalize_upload)
+            .service(dyno::get_file)
+            .service(dyno::share_dyno)
+            .service(dyno::verify_dyno)
+            .service(comment::get_series)
+            .service(comment::get_comments)
+            .service(comment::add_comment)
+            .service(comment::delete_comment)
+            .service(comment::add_annotation)
+            .service(comment::delete_annotation)
+            .service(label::get_dyno_labels)
+            .service(label::update_dyno_labels)
+            .service(share::get_share_links)
+            .service(share::create_share_link)
+            .service(share::revoke_share_link)
+            .service(share::get_shared)
+            .service(share::get_shared_file)
+            .service(usage::get_storage_usage)
             .service(history::history)
+            .service(class::get_classes)
+            .service(class::create_class)
+            .service(class::update_class)
+            .service(class::delete_class)
+            .service(class::get_class_members)
+            .service(class::enroll_class_members)
+            .service(class::remove_class_member)
+            .service(assignment::get_assignments)
+            .service(assignment::create_assignment)
+            .service(assignment::export_grades)
+            .service(assignment::update_assignment)
+            .service(assignment::delete_assignment)
+            .service(assignment::get_submissions)
+            .service(assignment::submit_assignment)
+            .service(assignment::grade_submission)
+            .service(api_key::get_api_keys)
+            .service(api_key::create_api_key)
+            .service(api_key::revoke_api_key)
+            .service(approval::get_approvals)
+            .service(approval::approve_account)
+            .service(approval::reject_account)
+            .service(approval::request_role)
+            .service(permission::get_my_permissions)
+            .service(permission::get_permissions)
+            .service(permission::update_permissions)
+            .service(permission::update_access_role)
+            .service(login_failure::get_login_failures)
+            .service(audit::start_audit)
+            .service(audit::get_audit)
+            .service(audit_log::get_audit_log)
+            .service(search::search)
             .service(info::get_info)
             .service(get_active)
             .service(post_active)
             .service(post_non_active),
     )
-    .service(ws::websocket_endpoint)
-    .service(dyno::get_file);
+    .service(ws::websocket_endpoint);
 }
 
 #[cfg_attr(debug_assert, derive(Debug))]
@@ -52,6 +143,30 @@ pub struct DynoUrlsQueries {
     pub max: Option<i64>,
     pub all: Option<bool>,
     pub admin: Option<bool>,
+    /// dynotest submitted to the class or history of its submitters, see [`crate::models::class`]
+    pub class_id: Option<i64>,
+    /// only dynotest that has the tag, see [`crate::models::label`]
+    pub tag: Option<String>,
+}
+
+/// response of the `status` that has no [`DynoErr`] kind, ex: `409`, `413`, `429`, `503`. the body
+/// is the same error body of [`DynoErr`] with the `any` kind, so it never disagree with the status.
+pub fn error_response(
+    status: actix_web::http::StatusCode,
+    message: impl ToString,
+) -> actix_web::HttpResponse {
+    use actix_web::ResponseError;
+    let body = DynoErr::any_error(message.to_string())
+        .error_response()
+        .into_body();
+    actix_web::HttpResponse::build(status)
+        .content_type(actix_web::http::header::ContentType::json())
+        .body(body)
+}
+
+/// `409 Conflict` with the error body, when the request is valid but the current state refuse it
+pub fn conflict(message: impl ToString) -> actix_web::HttpResponse {
+    error_response(actix_web::http::StatusCode::CONFLICT, message)
 }
 
 #[actix_web::get("/health")]
@@ -98,27 +213,36 @@ pub async fn get_active(
     )
 }
 
-#[inline]
-pub fn guard_desktop(guard_ctx: &GuardContext) -> bool {
-    match guard_ctx.head().headers().get(header::USER_AGENT) {
-        Some(ok) => ok.to_str().is_ok_and(|x| x.contains("Dyno/Desktop")),
-        None => false,
-    }
-}
-
-#[actix_web::post("/active", guard = "guard_desktop")]
+/// # Active Endpoint `post_active`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/active`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::ApiKeyMiddleware`] with `post_active` scope
+/// BODY(JSON)          => ['dyno_core::DynoConfig']
+/// -----------------------------------------------------------------
+#[actix_web::post("/active")]
 pub async fn post_active(
+    api_key: ApiKeyMiddleware,
     actix_web::web::Json(conf): actix_web::web::Json<DynoConfig>,
     data: actix_web::web::Data<crate::ServerState>,
-) -> impl actix_web::Responder {
+) -> DynoResult<actix_web::HttpResponse> {
+    api_key.require(ApiScope::PostActive)?;
     data.change_active_dyno(conf);
-    actix_web::HttpResponse::Ok().finish()
+    Ok(actix_web::HttpResponse::Ok().finish())
 }
 
-#[actix_web::post("/non_active", guard = "guard_desktop")]
+/// # Active Endpoint `post_non_active`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/non_active`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::ApiKeyMiddleware`] with `post_active` scope
+/// -----------------------------------------------------------------
+#[actix_web::post("/non_active")]
 pub async fn post_non_active(
+    api_key: ApiKeyMiddleware,
     data: actix_web::web::Data<crate::ServerState>,
-) -> impl actix_web::Responder {
+) -> DynoResult<actix_web::HttpResponse> {
+    api_key.require(ApiScope::PostActive)?;
     data.set_active(None);
-    actix_web::HttpResponse::Ok().finish()
+    Ok(actix_web::HttpResponse::Ok().finish())
 }
@@ -0,0 +1,170 @@
+use actix_web::{get, patch, put, web, HttpResponse};
+use dyno_core::{ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, permission as permission_actions,
+        user as user_actions,
+    },
+    middlewares::{perm, Auditor, JwtUserMiddleware, RequireAdmin, RequirePermission},
+    models::permission::{AccessRole, Permission, RolePermissions, UpdateAccessRole},
+};
+
+/// # Permission Endpoint `get_my_permissions`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/me/permissions`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::permission::RolePermissions']
+///
+/// access role and permissions of the logged in user, used to show the allowed menu.
+/// -----------------------------------------------------------------
+#[get("/me/permissions")]
+pub async fn get_my_permissions(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let role = permission_actions::select_access_role(&mut conn, session.id)?;
+        permission_actions::select_all(&mut conn)?
+            .into_iter()
+            .find(|x| x.role == role)
+            .ok_or(DynoErr::not_found_error("Access role is not exists"))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|permissions| HttpResponse::Ok().json(ApiResponse::success(permissions)))
+}
+
+/// # Permission Endpoint `get_permissions`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/permissions`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => [Vec<'crate::models::permission::RolePermissions'>]
+/// -----------------------------------------------------------------
+#[get("/admin/permissions")]
+pub async fn get_permissions(
+    _: RequirePermission<perm::UserManage>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| permission_actions::select_all(&mut conn))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|permissions| HttpResponse::Ok().json(ApiResponse::success(permissions)))
+}
+
+/// # Permission Endpoint `update_permissions`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/permissions/{role}`
+/// GUARD               => `PUT`
+/// HEADER/COOKIES      => [`crate::middlewares::RequireAdmin`]
+/// BODY(JSON)          => [Vec<'crate::models::permission::Permission'>]
+/// RESPONSE(JSON)      => ['crate::models::permission::RolePermissions']
+///
+/// replace every permission of the role, `admin` always has every permission and can not be edited.
+/// -----------------------------------------------------------------
+#[put("/admin/permissions/{role}")]
+pub async fn update_permissions(
+    role: web::Path<AccessRole>,
+    web::Json(mut permissions): web::Json<Vec<Permission>>,
+    RequireAdmin(session): RequireAdmin,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let role = role.into_inner();
+    if role == AccessRole::Admin {
+        return Err(DynoErr::bad_request_error(
+            "Admin always has every permission",
+        ));
+    }
+    permissions.sort_by_key(|x| x.as_str());
+    permissions.dedup();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let before = permission_actions::select_all(conn)?
+                .into_iter()
+                .find(|x| x.role == role)
+                .map(|x| x.permissions)
+                .unwrap_or_default();
+            permission_actions::replace(conn, role, &permissions)?;
+            audit_log_actions::insert(
+                conn,
+                auditor.entry(Some(session.id), "permission.update").diff(
+                    Some(&RolePermissions {
+                        role,
+                        permissions: before,
+                    }),
+                    Some(&RolePermissions {
+                        role,
+                        permissions: permissions.clone(),
+                    }),
+                ),
+            )?;
+            Ok(RolePermissions { role, permissions })
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|permissions| HttpResponse::Ok().json(ApiResponse::success(permissions)))
+}
+
+/// # Permission Endpoint `update_access_role`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/users/{id}/access-role`
+/// GUARD               => `PATCH`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// BODY(JSON)          => ['crate::models::permission::UpdateAccessRole']
+/// RESPONSE(JSON)      => ['dyno_core::users::UserResponse']
+///
+/// the user need to login again to have the admin role in the token changed. only admin can grant
+/// or remove `admin` and change its own role, see [`permission_actions::check_account_change`].
+/// -----------------------------------------------------------------
+#[patch("/admin/users/{id}/access-role")]
+pub async fn update_access_role(
+    id: web::Path<i64>,
+    web::Json(UpdateAccessRole { role }): web::Json<UpdateAccessRole>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            permission_actions::check_account_change(conn, session.id, id, Some(role))?;
+            let before = permission_actions::select_access_role(conn, id)
+                .map_err(|_| DynoErr::not_found_error("User is not exists"))?;
+            if id == session.id && role != AccessRole::Admin && before == AccessRole::Admin {
+                return Err(DynoErr::bad_request_error(
+                    "Admin can not remove the admin role of its own account",
+                ));
+            }
+            if permission_actions::update_access_role(conn, id, role)? == 0 {
+                return Err(DynoErr::not_found_error("User is not exists"));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "user.access_role")
+                    .target("user", id)
+                    .diff(Some(&before), Some(&role)),
+            )?;
+            user_actions::find_by_id(conn, id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|user| HttpResponse::Ok().json(ApiResponse::success(user.into_user_response())))
+}
@@ -0,0 +1,301 @@
+use std::{
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
+use dyno_core::{
+    crypto::compare_checksums, dynotests::DynoTestDataInfo, serde, ApiResponse, CompresedSaver,
+    DynoErr, DynoResult,
+};
+use futures::TryStreamExt;
+
+use crate::{
+    actions::{self, audit_log as audit_log_actions, upload as upload_actions},
+    checksum,
+    handler::dyno::{store_dyno, DynoData, QueryDuplicate, StoredDyno},
+    middlewares::{Auditor, UploaderMiddleware},
+    models::upload::{NewUploadSession, UpdateUploadSession},
+    upload::{self, UploadError, UploadResult},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryUploadSize {
+    pub size: i64,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryUploadOffset {
+    pub offset: i64,
+}
+
+#[inline]
+pub(crate) fn part_path(tmp_path: &std::path::Path, uuid: &str) -> PathBuf {
+    tmp_path.join(format!("{uuid}.part"))
+}
+
+/// # Dynotest Endpoint `create_upload`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/uploads?size={size}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::UploaderMiddleware`]
+/// BODY(BINARY)        => compressed 'dyno_core::model::dynotests::DynoTestDataInfo'
+/// RESPONSE(JSON)      => ['crate::models::upload::UploadProgress']
+///
+/// -----------------------------------------------------------------
+#[post("/dyno/uploads")]
+pub async fn create_upload(
+    web::Query(QueryUploadSize { size }): web::Query<QueryUploadSize>,
+    info: web::Bytes,
+    UploaderMiddleware(session): UploaderMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> UploadResult<HttpResponse> {
+    if size <= 0 {
+        return Err(DynoErr::bad_request_error("Upload size should be greater than zero").into());
+    }
+    if size as u64 > data.cfg.upload.max_field_size {
+        return Err(UploadError::TooLarge(format!(
+            "Upload size is larger than {} bytes",
+            data.cfg.upload.max_field_size
+        )));
+    }
+    DynoTestDataInfo::decompress(&info).map_err(|err| {
+        DynoErr::bad_request_error(format!("Upload session 'info' body is invalid - {err}"))
+    })?;
+
+    let dbpool = data.db.clone();
+    let blk_result = web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| {
+                upload_actions::insert(
+                    &mut conn,
+                    NewUploadSession::new(session.id, info.to_vec(), size),
+                )
+            })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?;
+
+    blk_result
+        .map(|s| HttpResponse::Ok().json(ApiResponse::success(s.into_response())))
+        .map_err(From::from)
+}
+
+/// # Dynotest Endpoint `get_upload`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/uploads/{uuid}`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::UploaderMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::upload::UploadProgress']
+///
+/// -----------------------------------------------------------------
+#[get("/dyno/uploads/{uuid}")]
+pub async fn get_upload(
+    uuid: web::Path<String>,
+    UploaderMiddleware(session): UploaderMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let uuid = uuid.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| upload_actions::select(&mut conn, &uuid, session.id))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|s| HttpResponse::Ok().json(ApiResponse::success(s.into_response())))
+}
+
+/// # Dynotest Endpoint `put_upload_chunk`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/uploads/{uuid}?offset={offset}`
+/// GUARD               => `PUT`
+/// HEADER/COOKIES      => [`crate::middlewares::UploaderMiddleware`]
+/// BODY(BINARY)        => chunk of the compressed data starting at `offset`
+/// RESPONSE(JSON)      => ['crate::models::upload::UploadProgress']
+///
+/// chunk that start after the received offset is responded with `409 Conflict` and the current
+/// progress, chunk that overlap already received bytes is only appended from the received offset.
+/// chunk for a session that is still receiving other chunk is responded with `409 Conflict`.
+/// -----------------------------------------------------------------
+#[put("/dyno/uploads/{uuid}")]
+pub async fn put_upload_chunk(
+    req: HttpRequest,
+    uuid: web::Path<String>,
+    web::Query(QueryUploadOffset { offset }): web::Query<QueryUploadOffset>,
+    mut body: web::Payload,
+    UploaderMiddleware(session): UploaderMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> UploadResult<HttpResponse> {
+    let uuid = uuid.into_inner();
+    let Some(_lock) = data.upload_locks.try_lock(uuid.as_str()) else {
+        return Ok(super::conflict("Upload session is receiving other chunk"));
+    };
+    let dbpool = data.db.clone();
+    let upload_session = web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| upload_actions::select(&mut conn, &uuid, session.id))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    if upload_session.dyno_id.is_some() || offset > upload_session.received || offset < 0 {
+        return Ok(
+            HttpResponse::Conflict().json(ApiResponse::success(upload_session.into_response()))
+        );
+    }
+
+    let mut budget = upload::UploadBudget::from_request(&req, &data.cfg.upload)?;
+    let part_path = part_path(&data.cfg.upload.tmp_path, &upload_session.uuid.to_string());
+    let (received, size) = (upload_session.received, upload_session.size);
+    let tmp_path = data.cfg.upload.tmp_path.clone();
+    let mut file = web::block(move || {
+        std::fs::create_dir_all(tmp_path)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(part_path)?;
+        // discard bytes of previous chunk that is never acknowledged
+        file.set_len(received as u64)?;
+        file.seek(SeekFrom::End(0)).map(|_| file)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map_err(DynoErr::internal_server_error)?;
+
+    let mut skip = (received - offset) as usize;
+    let mut written = received;
+    while let Some(chunk) = body
+        .try_next()
+        .await
+        .map_err(DynoErr::internal_server_error)?
+    {
+        let chunk = if skip >= chunk.len() {
+            skip -= chunk.len();
+            continue;
+        } else {
+            chunk.slice(std::mem::take(&mut skip)..)
+        };
+        written += chunk.len() as i64;
+        if written > size {
+            return Err(UploadError::TooLarge(format!(
+                "Chunk is exceeding the upload size of {size} bytes"
+            )));
+        }
+        budget.consume_chunk(chunk.len())?;
+        file = web::block(move || file.write_all(&chunk).map(|_| file))
+            .await
+            .map_err(DynoErr::internal_server_error)?
+            .map_err(DynoErr::internal_server_error)?;
+    }
+
+    let dbpool = data.db.clone();
+    let id = upload_session.id;
+    web::block(move || {
+        file.sync_data().map_err(DynoErr::internal_server_error)?;
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| {
+                upload_actions::update(&mut conn, id, UpdateUploadSession::received(written))
+            })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|s| HttpResponse::Ok().json(ApiResponse::success(s.into_response())))
+    .map_err(From::from)
+}
+
+/// # Dynotest Endpoint `finalize_upload`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/uploads/{uuid}/finalize?duplicate={return|link}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::UploaderMiddleware`]
+/// RESPONSE(JSON)      => `id` of the created [`crate::models::dyno::Dynos`]
+///
+/// finalizing the same session again return the same `id`, so retries is idempotent.
+/// session that is still receiving chunk is responded with `409 Conflict`.
+/// -----------------------------------------------------------------
+#[post("/dyno/uploads/{uuid}/finalize")]
+pub async fn finalize_upload(
+    uuid: web::Path<String>,
+    web::Query(QueryDuplicate { duplicate }): web::Query<QueryDuplicate>,
+    UploaderMiddleware(session): UploaderMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let uuid = uuid.into_inner();
+    // the session is claimed until finalized, so the same session never insert two `Dynos`
+    let Some(lock) = data.upload_locks.try_lock(uuid.as_str()) else {
+        return Ok(super::conflict("Upload session is receiving other chunk"));
+    };
+    let state = data.clone();
+    web::block(move || {
+        let _lock = lock;
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        let upload_session = upload_actions::select(&mut conn, &uuid, session.id)?;
+        if let Some(dyno_id) = upload_session.dyno_id {
+            return Ok(dyno_id);
+        }
+        if upload_session.received != upload_session.size {
+            return Err(DynoErr::bad_request_error(format!(
+                "Upload is not complete, received {} of {} bytes",
+                upload_session.received, upload_session.size
+            )));
+        }
+
+        let info = DynoTestDataInfo::decompress(&upload_session.info)?;
+        let part_path = part_path(&state.cfg.upload.tmp_path, &uuid);
+        let checksum = checksum::checksum_file(&part_path)?;
+        if !compare_checksums(checksum.as_bytes(), info.checksum_hex.as_bytes()) {
+            return Err(DynoErr::expectation_failed_error(
+                "Failed receive data, checksum of uploaded data is not the same",
+            ));
+        }
+
+        let actor_id = session.id;
+        actions::transaction(&mut conn, |conn| {
+            let dyno_id = store_dyno(
+                conn,
+                state.storage.as_ref(),
+                &state.cfg.quotas,
+                session,
+                StoredDyno {
+                    data: DynoData::File(part_path),
+                    checksum,
+                    config: info.config,
+                    start: info.start,
+                    stop: info.stop,
+                    imported: false,
+                    on_duplicate: duplicate,
+                },
+            )?;
+            upload_actions::update(
+                conn,
+                upload_session.id,
+                UpdateUploadSession::finished(dyno_id),
+            )?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(actor_id), "dyno.upload")
+                    .target("dyno", dyno_id)
+                    .details(&upload_session.size),
+            )
+            .map(|_| dyno_id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|id| HttpResponse::Ok().json(ApiResponse::success(id)))
+}
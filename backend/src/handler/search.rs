@@ -0,0 +1,56 @@
+use actix_web::{get, web, HttpResponse};
+use dyno_core::{ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::search as search_actions,
+    middlewares::{perm, JwtUserMiddleware, RequirePermission},
+    models::search::{SearchKind, SearchQueries, SearchResult},
+};
+
+const DEFAULT_RESULTS: i64 = 10;
+const MAX_RESULTS: i64 = 50;
+
+/// # Search Endpoint `search`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/search{query}` [query = `SearchQueries`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::search::SearchResult'>]
+///
+/// full-text search of user name/nim/email, dynotest title/notes/tags/metadata and the vehicle
+/// of dyno_info, ranked by the best match first. every word of `q` is matched as a prefix.
+/// user can only find themself unless it has `user.manage` permission, dynotest and dyno_info
+/// only when it is readable by the user, see [`crate::handler::dyno::readable`].
+/// -----------------------------------------------------------------
+#[get("/search")]
+pub async fn search(
+    web::Query(SearchQueries { q, max }): web::Query<SearchQueries>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    read_all: Option<RequirePermission<perm::DynoReadAll>>,
+    user_manage: Option<RequirePermission<perm::UserManage>>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let Some(query) = search_actions::fts_query(&q) else {
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(Vec::<SearchResult>::new())));
+    };
+    let max = max.unwrap_or(DEFAULT_RESULTS).clamp(1, MAX_RESULTS);
+    let dbpool = data.db.clone();
+    let (read_all, user_manage) = (read_all.is_some(), user_manage.is_some());
+
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let mut results =
+            search_actions::search(&mut conn, &query, session.id, read_all, user_manage, max)?;
+        for result in results.iter_mut().filter(|x| x.title.is_empty()) {
+            result.title = match result.kind {
+                SearchKind::User => format!("User #{}", result.id),
+                SearchKind::Dyno => format!("Dynotest #{}", result.id),
+                SearchKind::Info => format!("Vehicle #{}", result.id),
+            };
+        }
+        Ok(results)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|results| HttpResponse::Ok().json(ApiResponse::success(results)))
+}
@@ -0,0 +1,326 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use dyno_core::{
+    chrono::{Duration, NaiveDateTime, Utc},
+    dynotests::DynoTest,
+    serde, ApiResponse, BufferData, CompresedSaver, CsvSaver, DynoConfig, DynoErr, DynoResult,
+};
+
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, dyno as dyno_actions, info as info_actions,
+        share as share_actions,
+    },
+    handler::dyno::QueryFile,
+    middlewares::{Auditor, JwtUserMiddleware},
+    models::{
+        dyno::Dynos,
+        share::{NewShareLink, ShareLink, ShareLinkCreated},
+    },
+    token, DynoDBPooledConnection,
+};
+
+/// default and maximum lifetime of a share link in days
+const DEFAULT_LINK_DAYS: i64 = 7;
+const MAX_LINK_DAYS: i64 = 90;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryShareLink {
+    pub days: Option<i64>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct QueryShareLinks {
+    pub dyno_id: Option<i64>,
+}
+
+/// peak values of a dynotest, `None` when the column is not exists in the data
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct DynoPeaks {
+    pub max_horsepower: Option<f64>,
+    pub rpm_at_max_horsepower: Option<f64>,
+    pub max_torque: Option<f64>,
+    pub rpm_at_max_torque: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub max_rpm: Option<f64>,
+}
+
+impl DynoPeaks {
+    /// compute the peaks from the csv export of the data, so it follow the same columns
+    /// that is downloaded by the user.
+    pub(crate) fn from_buffer(buffer: &BufferData) -> DynoResult<Self> {
+        let csv = buffer.save_csv_into_bytes()?;
+        let mut reader = csv::Reader::from_reader(csv.as_slice());
+        let headers = reader
+            .headers()
+            .map_err(DynoErr::serialize_error)?
+            .iter()
+            .map(|h| h.to_lowercase().replace([' ', '_', '-'], ""))
+            .collect::<Vec<_>>();
+        let column = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| headers.iter().position(|h| h == name))
+        };
+        let hp = column(&["horsepower", "hp"]);
+        let torque = column(&["torque"]);
+        let speed = column(&["speed"]);
+        let rpm = column(&["rpmengine", "rpm", "rpmroda"]);
+
+        let mut peaks = Self::default();
+        for record in reader.records() {
+            let record = record.map_err(DynoErr::serialize_error)?;
+            let value = |col: Option<usize>| {
+                col.and_then(|c| record.get(c))
+                    .and_then(|x| x.trim().parse::<f64>().ok())
+            };
+            let rpm_value = value(rpm);
+            if let Some(v) = value(hp) {
+                if peaks.max_horsepower.map_or(true, |max| v > max) {
+                    peaks.max_horsepower = Some(v);
+                    peaks.rpm_at_max_horsepower = rpm_value;
+                }
+            }
+            if let Some(v) = value(torque) {
+                if peaks.max_torque.map_or(true, |max| v > max) {
+                    peaks.max_torque = Some(v);
+                    peaks.rpm_at_max_torque = rpm_value;
+                }
+            }
+            if let Some(v) = value(speed) {
+                peaks.max_speed = Some(peaks.max_speed.map_or(v, |max| max.max(v)));
+            }
+            if let Some(v) = rpm_value {
+                peaks.max_rpm = Some(peaks.max_rpm.map_or(v, |max| max.max(v)));
+            }
+        }
+        Ok(peaks)
+    }
+}
+
+/// public view of a shared dynotest, `dyno.data_url` is pointed into the share link
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SharedDyno {
+    pub dyno: DynoTest,
+    pub config: Option<DynoConfig>,
+    pub peaks: DynoPeaks,
+    pub expires_at: NaiveDateTime,
+}
+
+/// find the [`Dynos`] of a valid share `token`, expired and revoked link is treated as not exists
+fn resolve(conn: &mut DynoDBPooledConnection, token: &str) -> DynoResult<(ShareLink, Dynos)> {
+    let link = share_actions::select_by_token_hash(conn, &token::hash_token(token))?;
+    if !link.is_valid() {
+        return Err(DynoErr::not_found_error("Share link is expired or revoked"));
+    }
+    let dyno = dyno_actions::select_by_id(conn, link.dyno_id)?;
+    Ok((link, dyno))
+}
+
+/// # Share Endpoint `create_share_link`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/{id}/links?days={days}`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::share::ShareLinkCreated']
+///
+/// only the owner can share the dynotest, link is valid for `days` (default 7, max 90).
+/// -----------------------------------------------------------------
+#[post("/dyno/{id}/links")]
+pub async fn create_share_link(
+    id: web::Path<i64>,
+    web::Query(QueryShareLink { days }): web::Query<QueryShareLink>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dyno_id = id.into_inner();
+    let days = days.unwrap_or(DEFAULT_LINK_DAYS).clamp(1, MAX_LINK_DAYS);
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            if !dyno_actions::is_exists(conn, dyno_id, session.id)? {
+                return Err(DynoErr::forbidden_error(
+                    "Dynotest is not exists or not owned by the user",
+                ));
+            }
+            let token = token::generate_token();
+            let link = share_actions::insert(
+                conn,
+                NewShareLink {
+                    dyno_id,
+                    user_id: session.id,
+                    token_hash: token::hash_token(&token),
+                    expires_at: Utc::now().naive_utc() + Duration::days(days),
+                },
+            )?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "share_link.create")
+                    .target("share_link", link.id)
+                    .details(&link.clone().into_response()),
+            )?;
+            Ok(ShareLinkCreated {
+                id: link.id,
+                dyno_id,
+                url: format!("/share?token={token}"),
+                token,
+                expires_at: link.expires_at,
+            })
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|created| HttpResponse::Ok().json(ApiResponse::success(created)))
+}
+
+/// # Share Endpoint `get_share_links`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/links?dyno_id={dyno_id}`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => [Vec<'crate::models::share::ShareLinkResponse'>]
+///
+/// -----------------------------------------------------------------
+#[get("/dyno/links")]
+pub async fn get_share_links(
+    web::Query(QueryShareLinks { dyno_id }): web::Query<QueryShareLinks>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let dbpool = data.db.clone();
+    web::block(move || {
+        dbpool
+            .get()
+            .map_err(DynoErr::database_error)
+            .and_then(|mut conn| share_actions::select_many(&mut conn, session.id, dyno_id))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|links| {
+        HttpResponse::Ok().json(ApiResponse::success(
+            links
+                .into_iter()
+                .map(ShareLink::into_response)
+                .collect::<Vec<_>>(),
+        ))
+    })
+}
+
+/// # Share Endpoint `revoke_share_link`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/dyno/links/{id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::share::ShareLinkResponse']
+///
+/// -----------------------------------------------------------------
+#[delete("/dyno/links/{id}")]
+pub async fn revoke_share_link(
+    id: web::Path<i64>,
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    auditor: Auditor,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let link = share_actions::revoke(conn, id, session.id)?.into_response();
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "share_link.revoke")
+                    .target("share_link", id)
+                    .details(&link),
+            )?;
+            Ok(link)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|link| HttpResponse::Ok().json(ApiResponse::success(link)))
+}
+
+/// # Share Endpoint `get_shared`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/share/{token}`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => public, no login required
+/// RESPONSE(JSON)      => ['SharedDyno']
+///
+/// -----------------------------------------------------------------
+#[get("/share/{token}")]
+pub async fn get_shared(
+    token: web::Path<String>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let token = token.into_inner();
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let (link, dyno) = resolve(&mut conn, &token)?;
+        let config = match dyno.info_id {
+            Some(info_id) => info_actions::select(&mut conn, info_id)
+                .ok()
+                .map(|x| x.into_response()),
+            None => None,
+        };
+        let buffer = BufferData::decompress(storage.get(&dyno.data_url)?)?;
+        let peaks = DynoPeaks::from_buffer(&buffer)?;
+
+        let mut dyno = dyno.into_response();
+        dyno.data_url = format!("/api/share/{token}/file");
+        Ok(SharedDyno {
+            dyno,
+            config,
+            peaks,
+            expires_at: link.expires_at,
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|shared| HttpResponse::Ok().json(ApiResponse::success(shared)))
+}
+
+/// # Share Endpoint `get_shared_file`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/share/{token}/file{query}` [query = `QueryFile`]
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => public, no login required
+/// BODY(BINARY)        => data of the dynotest converted into `tp`
+///
+/// -----------------------------------------------------------------
+#[get("/share/{token}/file")]
+pub async fn get_shared_file(
+    token: web::Path<String>,
+    web::Query(QueryFile { tp }): web::Query<QueryFile>,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let token = token.into_inner();
+    let dbpool = data.db.clone();
+    let storage = data.storage.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        let (_, dyno) = resolve(&mut conn, &token)?;
+        let file = format!("{}-{}.dyno", dyno.id, dyno.uuid);
+        storage
+            .get(&dyno.data_url)
+            .and_then(|bytes| tp.convert(bytes))
+            .map(|data| (file, data))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|(file, data)| tp.response(file, data))
+}
@@ -0,0 +1,414 @@
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use dyno_core::{ApiResponse, DynoErr, DynoResult, UserSession};
+
+use super::auth::{failed_attempt, from_station, login_response, record_failure};
+use crate::{
+    actions::{
+        self, audit_log as audit_log_actions, two_factor as two_factor_actions,
+        user as user_actions,
+    },
+    middlewares::{
+        perm, too_many_requests, ApiKeyMiddleware, Auditor, JwtUserMiddleware, RateLimit,
+        RequirePermission,
+    },
+    models::{
+        login_failure::NewLoginFailure,
+        two_factor::{
+            NewRecoveryCode, RecoveryCodes, TwoFactorCode, TwoFactorLogin, TwoFactorSetup,
+            TwoFactorStatus,
+        },
+        user::User,
+    },
+    token, totp, DynoDBPooledConnection, ServerState,
+};
+
+/// user of the session, or of the unused challenge when enrolling from the second login step
+fn resolve_user(
+    conn: &mut DynoDBPooledConnection,
+    session: Option<UserSession>,
+    challenge: Option<&str>,
+) -> DynoResult<User> {
+    if let Some(session) = session {
+        return user_actions::find_by_id(conn, session.id);
+    }
+    let Some(challenge) = challenge else {
+        return Err(DynoErr::unauthorized_error(
+            "You are not logged in, please provide token",
+        ));
+    };
+    let challenge = two_factor_actions::select_challenge(conn, &token::hash_token(challenge))?;
+    if !challenge.is_valid() {
+        return Err(DynoErr::unauthorized_error(
+            "Login challenge is expired, login again",
+        ));
+    }
+    user_actions::find_by_id(conn, challenge.user_id)
+}
+
+/// generate new recovery codes of the user, previous codes is invalidated
+fn new_recovery_codes(
+    state: &ServerState,
+    conn: &mut DynoDBPooledConnection,
+    user_id: i64,
+) -> DynoResult<RecoveryCodes> {
+    let codes = totp::generate_recovery_codes(state.cfg.two_factor.recovery_codes);
+    let news = codes
+        .iter()
+        .map(|code| NewRecoveryCode {
+            user_id,
+            code_hash: totp::hash_recovery_code(code),
+        })
+        .collect();
+    two_factor_actions::replace_recovery_codes(conn, user_id, news)?;
+    Ok(RecoveryCodes { codes })
+}
+
+/// TOTP code of the enabled user, or unused recovery code when `allow_recovery`
+fn check_code(
+    state: &ServerState,
+    conn: &mut DynoDBPooledConnection,
+    user: &User,
+    code: &str,
+    allow_recovery: bool,
+) -> DynoResult<bool> {
+    let Some(secret) = user
+        .totp_secret
+        .as_deref()
+        .filter(|_| user.is_two_factor_enabled())
+    else {
+        return Err(DynoErr::bad_request_error(
+            "Two-factor authentication is not enabled",
+        ));
+    };
+    if let Some(step) = totp::verify(&state.cfg.two_factor, secret, &user.nim, code) {
+        // replayed code is rejected, 6 digit code is never a recovery code
+        return two_factor_actions::accept_totp_step(conn, user.id, step);
+    }
+    if allow_recovery {
+        return two_factor_actions::use_recovery_code(
+            conn,
+            user.id,
+            &totp::hash_recovery_code(code),
+        );
+    }
+    Ok(false)
+}
+
+/// # Two Factor Endpoint `get_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::two_factor::TwoFactorStatus']
+/// -----------------------------------------------------------------
+#[get("/auth/2fa")]
+pub async fn get_two_factor(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        let user = user_actions::find_by_id(&mut conn, session.id)?;
+        Ok(TwoFactorStatus {
+            enabled: user.is_two_factor_enabled(),
+            required: state.cfg.two_factor.is_required(&session.role),
+            recovery_codes_left: two_factor_actions::count_recovery_codes(&mut conn, user.id)?,
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|status| HttpResponse::Ok().json(ApiResponse::success(status)))
+}
+
+/// # Two Factor Endpoint `setup_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa/setup`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] or `challenge` in the body
+/// BODY(JSON)          => ['crate::models::two_factor::TwoFactorCode'] `code` is ignored
+/// RESPONSE(JSON)      => ['crate::models::two_factor::TwoFactorSetup']
+///
+/// start the enrollment with new secret, TOTP is enabled after the code is confirmed in `/api/auth/2fa/enable`.
+/// -----------------------------------------------------------------
+#[post("/auth/2fa/setup")]
+pub async fn setup_two_factor(
+    session: Option<JwtUserMiddleware>,
+    web::Json(TwoFactorCode { challenge, .. }): web::Json<TwoFactorCode>,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        let user = resolve_user(&mut conn, session.map(|x| x.0), challenge.as_deref())?;
+        if user.is_two_factor_enabled() {
+            return Err(DynoErr::bad_request_error(
+                "Two-factor authentication is already enabled, disable it first",
+            ));
+        }
+        let secret = totp::generate_secret();
+        let (uri, qr) = totp::provisioning(&state.cfg.two_factor, &secret, &user.nim)?;
+        user_actions::set_totp_secret(&mut conn, user.id, &secret)?;
+        Ok(TwoFactorSetup { secret, uri, qr })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|setup| HttpResponse::Ok().json(ApiResponse::success(setup)))
+}
+
+/// # Two Factor Endpoint `enable_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa/enable`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`] or `challenge` in the body
+/// BODY(JSON)          => ['crate::models::two_factor::TwoFactorCode']
+/// RESPONSE(JSON)      => ['crate::models::two_factor::RecoveryCodes']
+///
+/// confirm the enrollment with the code from the authenticator app,
+/// the recovery codes is only shown once in this response.
+/// -----------------------------------------------------------------
+#[post("/auth/2fa/enable", wrap = "RateLimit::new(\"2fa\")")]
+pub async fn enable_two_factor(
+    session: Option<JwtUserMiddleware>,
+    web::Json(TwoFactorCode { code, challenge }): web::Json<TwoFactorCode>,
+    auditor: Auditor,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let user = resolve_user(conn, session.map(|x| x.0), challenge.as_deref())?;
+            if user.is_two_factor_enabled() {
+                return Err(DynoErr::bad_request_error(
+                    "Two-factor authentication is already enabled",
+                ));
+            }
+            let Some(secret) = user.totp_secret.as_deref() else {
+                return Err(DynoErr::bad_request_error(
+                    "Start the two-factor enrollment first",
+                ));
+            };
+            let accepted = match totp::verify(&state.cfg.two_factor, secret, &user.nim, &code) {
+                Some(step) => two_factor_actions::accept_totp_step(conn, user.id, step)?,
+                None => false,
+            };
+            if !accepted {
+                return Err(DynoErr::unauthorized_error("Two-factor code is wrong"));
+            }
+            user_actions::enable_totp(conn, user.id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(user.id), "user.2fa_enable")
+                    .target("user", user.id),
+            )?;
+            new_recovery_codes(&state, conn, user.id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|codes| HttpResponse::Ok().json(ApiResponse::success(codes)))
+}
+
+/// # Two Factor Endpoint `login_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa/login`
+/// GUARD               => `POST`
+/// BODY(JSON)          => ['crate::models::two_factor::TwoFactorLogin']
+/// RESPONSE(JSON)      => ['dyno_core::crypto::TokenDetails']
+///
+/// second login step with the challenge from `/api/auth/login`, `code` is TOTP code or
+/// unused recovery code. failed code is counted in the same backoff as the password.
+/// -----------------------------------------------------------------
+#[post("/auth/2fa/login", wrap = "RateLimit::new(\"2fa\")")]
+pub async fn login_two_factor(
+    web::Json(TwoFactorLogin { challenge, code }): web::Json<TwoFactorLogin>,
+    station: Option<ApiKeyMiddleware>,
+    auditor: Auditor,
+    data: web::Data<ServerState>,
+    req: HttpRequest,
+) -> DynoResult<HttpResponse> {
+    let failure = failed_attempt(&req, &data.cfg.rate_limit, "");
+    let state = data.clone();
+    let user = web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        let challenge =
+            two_factor_actions::select_challenge(&mut conn, &token::hash_token(&challenge))?;
+        if !challenge.is_valid() {
+            return Err(DynoErr::unauthorized_error(
+                "Login challenge is expired, login again",
+            ));
+        }
+        let user = user_actions::find_by_id(&mut conn, challenge.user_id)?;
+        if !user.is_two_factor_enabled() {
+            return Err(DynoErr::bad_request_error(
+                "Enroll two-factor authentication before login",
+            ));
+        }
+        let nim_key = format!("nim:{}", user.nim);
+        if let Some(retry_after) = state.limiter.blocked(&nim_key) {
+            return Ok(Err(Some(retry_after)));
+        }
+        if !check_code(&state, &mut conn, &user, &code, true)? {
+            let blocked = state.limiter.failure(&state.cfg.rate_limit, &nim_key);
+            record_failure(
+                &mut conn,
+                &auditor,
+                NewLoginFailure {
+                    nim: user.nim,
+                    reason: "wrong_totp".to_owned(),
+                    ..failure
+                },
+            )?;
+            return Ok(Err(blocked));
+        }
+        actions::transaction(&mut conn, |conn| {
+            if !two_factor_actions::mark_challenge_used(conn, challenge.id)? {
+                return Err(DynoErr::unauthorized_error(
+                    "Login challenge is already used, login again",
+                ));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(user.id), "auth.login")
+                    .target("user", user.id)
+                    .details(&"2fa"),
+            )
+        })?;
+        state.limiter.success(&nim_key);
+        Ok(Ok(user))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+
+    match user {
+        Ok(user) => login_response(&data, &user, from_station(station)),
+        Err(Some(retry_after)) => Ok(too_many_requests(retry_after)),
+        Err(None) => Err(DynoErr::unauthorized_error("Two-factor code is wrong")),
+    }
+}
+
+/// # Two Factor Endpoint `regenerate_recovery_codes`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa/recovery`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['crate::models::two_factor::TwoFactorCode'] with TOTP code
+/// RESPONSE(JSON)      => ['crate::models::two_factor::RecoveryCodes']
+/// -----------------------------------------------------------------
+#[post("/auth/2fa/recovery", wrap = "RateLimit::new(\"2fa\")")]
+pub async fn regenerate_recovery_codes(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    web::Json(TwoFactorCode { code, .. }): web::Json<TwoFactorCode>,
+    auditor: Auditor,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let user = user_actions::find_by_id(conn, session.id)?;
+            if !check_code(&state, conn, &user, &code, false)? {
+                return Err(DynoErr::unauthorized_error("Two-factor code is wrong"));
+            }
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(user.id), "user.2fa_recovery")
+                    .target("user", user.id),
+            )?;
+            new_recovery_codes(&state, conn, user.id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|codes| HttpResponse::Ok().json(ApiResponse::success(codes)))
+}
+
+/// # Two Factor Endpoint `disable_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/auth/2fa/disable`
+/// GUARD               => `POST`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// BODY(JSON)          => ['crate::models::two_factor::TwoFactorCode'] with TOTP or recovery code
+/// RESPONSE(JSON)      => message
+///
+/// admin can not disable it when `DYNO_2FA_REQUIRED_ADMIN` is set.
+/// -----------------------------------------------------------------
+#[post("/auth/2fa/disable", wrap = "RateLimit::new(\"2fa\")")]
+pub async fn disable_two_factor(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    web::Json(TwoFactorCode { code, .. }): web::Json<TwoFactorCode>,
+    auditor: Auditor,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    if data.cfg.two_factor.is_required(&session.role) {
+        return Err(DynoErr::forbidden_error(
+            "Two-factor authentication is required for admin",
+        ));
+    }
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            let user = user_actions::find_by_id(conn, session.id)?;
+            if !check_code(&state, conn, &user, &code, true)? {
+                return Err(DynoErr::unauthorized_error("Two-factor code is wrong"));
+            }
+            user_actions::disable_totp(conn, user.id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(user.id), "user.2fa_disable")
+                    .target("user", user.id),
+            )?;
+            two_factor_actions::delete_recovery_codes(conn, user.id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "Two-factor authentication is disabled".to_owned(),
+    )))
+}
+
+/// # Two Factor Endpoint `reset_two_factor`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/admin/users/{id}/2fa`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => message
+///
+/// remove TOTP of the user that lost the authenticator and every recovery code,
+/// admin with required TOTP should enroll it again in the next login.
+/// -----------------------------------------------------------------
+#[delete("/admin/users/{id}/2fa")]
+pub async fn reset_two_factor(
+    id: web::Path<i64>,
+    RequirePermission(session, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
+    data: web::Data<ServerState>,
+) -> DynoResult<HttpResponse> {
+    let id = id.into_inner();
+    let dbpool = data.db.clone();
+    web::block(move || {
+        let mut conn = dbpool.get().map_err(DynoErr::database_error)?;
+        actions::transaction(&mut conn, |conn| {
+            user_actions::disable_totp(conn, id)?;
+            audit_log_actions::insert(
+                conn,
+                auditor
+                    .entry(Some(session.id), "user.2fa_reset")
+                    .target("user", id),
+            )?;
+            two_factor_actions::delete_recovery_codes(conn, id)
+        })
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)??;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "Two-factor authentication is reset".to_owned(),
+    )))
+}
@@ -0,0 +1,37 @@
+use actix_web::{get, web, HttpResponse};
+use dyno_core::{ApiResponse, DynoErr, DynoResult};
+
+use crate::{
+    actions::{dyno as dyno_actions, permission as permission_actions},
+    middlewares::JwtUserMiddleware,
+};
+
+/// # Storage Endpoint `get_storage_usage`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/me/storage`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::JwtUserMiddleware`]
+/// RESPONSE(JSON)      => ['crate::models::dyno::StorageUsage']
+///
+/// archived dynotest is not counted in the quota of the user access role.
+/// -----------------------------------------------------------------
+#[get("/me/storage")]
+pub async fn get_storage_usage(
+    JwtUserMiddleware(session): JwtUserMiddleware,
+    data: web::Data<crate::ServerState>,
+) -> DynoResult<HttpResponse> {
+    let state = data.clone();
+    web::block(move || {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        let access_role = permission_actions::select_access_role(&mut conn, session.id)?;
+        let quota = state.cfg.quotas.for_role(access_role);
+        dyno_actions::usage(&mut conn, session.id).map(|usage| (usage, quota.clone()))
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map(|(mut usage, quota)| {
+        usage.max_count = quota.max_count;
+        usage.max_bytes = quota.max_bytes;
+        HttpResponse::Ok().json(ApiResponse::success(usage))
+    })
+}
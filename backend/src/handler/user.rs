@@ -1,8 +1,14 @@
 use crate::{
-    actions::user as user_actions,
+    actions::{
+        self, audit_log as audit_log_actions, class as class_actions,
+        permission as permission_actions, user as user_actions,
+    },
     handler::UserUrlsQueries,
-    middlewares::JwtAdminMiddleware,
-    models::user::{NewUser, UpdateUser, User},
+    middlewares::{perm, Auditor, RequirePermission},
+    models::{
+        permission::AccessRole,
+        user::{NewUser, UpdateUser, User},
+    },
 };
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 use dyno_core::{
@@ -14,7 +20,7 @@ use dyno_core::{
 #[get("/users")]
 pub async fn get_user(
     web::Query(UserUrlsQueries { id, max }): web::Query<UserUrlsQueries>,
-    _: JwtAdminMiddleware,
+    _: RequirePermission<perm::UserManage>,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
     let dbpool = data.db.clone();
@@ -43,24 +49,33 @@ pub async fn get_user(
 #[post("/users")]
 pub async fn add_user(
     web::Json(jsons): web::Json<OneOrMany<UserRegistration>>,
-    _: JwtAdminMiddleware,
+    RequirePermission(admin, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
     let dbpool = data.db.clone();
+    let actor_id = admin.id;
     let blk_result = web::block(move || {
         dbpool
             .get()
             .map_err(DynoErr::database_error)
-            .and_then(|mut conn| match jsons {
-                OneOrMany::One(user) => NewUser::from_registration(user)
-                    .and_then(|new| user_actions::insert_new(&mut conn, new)),
-                OneOrMany::Many(manys) => {
-                    let many = manys
+            .and_then(|mut conn| {
+                let news = match jsons {
+                    OneOrMany::One(user) => vec![NewUser::from_registration(user)?],
+                    OneOrMany::Many(manys) => manys
                         .into_iter()
                         .filter_map(|user| NewUser::from_registration(user).ok())
-                        .collect::<Vec<_>>();
-                    user_actions::insert_many(&mut conn, many).map_err(DynoErr::database_error)
-                }
+                        .collect::<Vec<_>>(),
+                };
+                let nims = news.iter().map(|x| x.nim.clone()).collect::<Vec<_>>();
+                actions::transaction(&mut conn, |conn| {
+                    let inserted = user_actions::insert_many(conn, news)?;
+                    audit_log_actions::insert(
+                        conn,
+                        auditor.entry(Some(actor_id), "user.create").details(&nims),
+                    )?;
+                    Ok(inserted)
+                })
             })
     })
     .await
@@ -75,17 +90,42 @@ pub async fn add_user(
 pub async fn update_user(
     user_id: web::Path<u32>,
     web::Json(user_update): web::Json<UserUpdate>,
-    _: JwtAdminMiddleware,
+    RequirePermission(admin, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
     let dbpool = data.db.clone();
     let id = user_id.into_inner();
+    let actor_id = admin.id;
     let user_response = web::block(move || {
         dbpool
             .get()
             .map_err(DynoErr::database_error)
             .and_then(|mut conn| {
-                user_actions::update_by_id(&mut conn, id as _, UpdateUser::from(user_update))
+                actions::transaction(&mut conn, |conn| {
+                    let role = user_update.role;
+                    permission_actions::check_account_change(
+                        conn,
+                        actor_id,
+                        id as _,
+                        role.map(AccessRole::from),
+                    )?;
+                    let before = user_actions::find_by_id(conn, id as _)?;
+                    let updated =
+                        user_actions::update_by_id(conn, id as _, UpdateUser::from(user_update))?;
+                    if let Some(role) = role {
+                        permission_actions::sync_access_role(conn, id as _, role)?;
+                    }
+                    let after = user_actions::find_by_id(conn, id as _)?;
+                    audit_log_actions::insert(
+                        conn,
+                        auditor
+                            .entry(Some(actor_id), "user.update")
+                            .target("user", id as _)
+                            .diff(Some(&before), Some(&after)),
+                    )?;
+                    Ok(updated)
+                })
             })
     })
     .await
@@ -97,25 +137,65 @@ pub async fn update_user(
     }
 }
 
+/// # User Endpoint `delete_user`
+/// -----------------------------------------------------------------
+/// URL                 => `/api/users/{user_id}`
+/// GUARD               => `DELETE`
+/// HEADER/COOKIES      => [`crate::middlewares::RequirePermission`] with `user.manage`
+/// RESPONSE(JSON)      => number of deleted user
+///
+/// admin account can only be deleted by admin. lecturer of any class is refused with
+/// `409 Conflict`, see [`crate::actions::user::delete_with_references`] for the other references
+/// of the user.
+/// -----------------------------------------------------------------
 #[delete("/users/{user_id}")]
 pub async fn delete_user(
     user_id: web::Path<u32>,
-    _: JwtAdminMiddleware,
+    RequirePermission(admin, ..): RequirePermission<perm::UserManage>,
+    auditor: Auditor,
     data: web::Data<crate::ServerState>,
 ) -> impl Responder {
     let dbpool = data.db.clone();
     let id = user_id.into_inner();
+    let actor_id = admin.id;
     let user_response = web::block(move || {
         dbpool
             .get()
             .map_err(DynoErr::database_error)
-            .and_then(|mut conn| user_actions::delete_by_id(&mut conn, id as _))
+            .and_then(|mut conn| {
+                actions::transaction(&mut conn, |conn| {
+                    if id as i64 == actor_id {
+                        return Err(DynoErr::bad_request_error(
+                            "Can not delete your own account",
+                        ));
+                    }
+                    permission_actions::check_account_change(conn, actor_id, id as _, None)?;
+                    let before = user_actions::find_by_id(conn, id as _)?;
+                    let classes = class_actions::count_by_lecturer(conn, id as _)?;
+                    if classes > 0 {
+                        return Ok(Err(format!(
+                            "User is the lecturer of {classes} classes, \
+                            change the lecturer of the classes or delete them first"
+                        )));
+                    }
+                    let deleted = user_actions::delete_with_references(conn, id as _, actor_id)?;
+                    audit_log_actions::insert(
+                        conn,
+                        auditor
+                            .entry(Some(actor_id), "user.delete")
+                            .target("user", id as _)
+                            .diff(Some(&before), None::<&User>),
+                    )?;
+                    Ok(Ok(deleted))
+                })
+            })
     })
     .await
     .map_err(DynoErr::internal_server_error)?;
 
     match user_response {
-        Ok(ok) => Ok(HttpResponse::Ok().json(ApiResponse::success(ok))),
+        Ok(Ok(ok)) => Ok(HttpResponse::Ok().json(ApiResponse::success(ok))),
+        Ok(Err(reason)) => Ok(super::conflict(reason)),
         Err(err) => Err(err),
     }
 }
@@ -1,9 +1,14 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use actix::{Actor, Addr, AsyncContext, Handler, StreamHandler};
 use actix_web::{get, web, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
 use dyno_core::crossbeam_channel::Sender;
 
-use crate::ServerState;
+use crate::{middlewares::ApiKeyMiddleware, models::api_key::ApiScope, ServerState};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Msg(Vec<u8>);
@@ -19,13 +24,49 @@ pub enum WsMessage {
     Msg(Msg),
 }
 
+/// the broadcast loop wait for a message at most this long before it beat the [`Heartbeat`]
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// last time the broadcast loop is running, the loop is stuck or stopped when it is not beaten
+/// for a few [`HEARTBEAT_INTERVAL`]
+#[derive(Debug, Default, Clone)]
+pub struct Heartbeat(Arc<Mutex<Option<Instant>>>);
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Some(Instant::now());
+        }
+    }
+
+    /// `None` when the loop never beat yet
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|last| last.map(|x| x.elapsed()))
+    }
+}
+
 pub struct WsConn {
     tx: Sender<WsMessage>,
+    /// only connection with `publish_live` api key can broadcast, other connection only receive
+    publisher: bool,
 }
 
 impl WsConn {
-    pub fn new(tx: Sender<WsMessage>) -> Self {
-        Self { tx }
+    pub fn new(tx: Sender<WsMessage>, publisher: bool) -> Self {
+        Self { tx, publisher }
+    }
+
+    fn publish(&self, msg: Vec<u8>) {
+        if !self.publisher {
+            dyno_core::log::warn!("Websocket message from non publisher connection is dropped");
+            return;
+        }
+        if let Err(err) = self.tx.send(WsMessage::Msg(Msg(msg))) {
+            dyno_core::log::error!("MPSC SEND ERROR: {err}")
+        }
     }
 }
 impl Handler<Msg> for WsConn {
@@ -55,36 +96,36 @@ impl Actor for WsConn {
 
 /// Handler for ws::Message message
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsConn {
-    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, _ctx: &mut Self::Context) {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match msg {
-            Ok(ws::Message::Ping(msg)) => {
-                if let Err(err) = self.tx.send(WsMessage::Msg(Msg(msg.to_vec()))) {
-                    dyno_core::log::error!("MPSC SEND ERROR: {err}")
-                }
-            }
-            Ok(ws::Message::Text(msg)) => {
-                if let Err(err) = self.tx.send(WsMessage::Msg(Msg(msg.into_bytes().to_vec()))) {
-                    dyno_core::log::error!("MPSC SEND ERROR: {err}")
-                }
-            }
-            Ok(ws::Message::Binary(msg)) => {
-                if let Err(err) = self.tx.send(WsMessage::Msg(Msg(msg.to_vec()))) {
-                    dyno_core::log::error!("MPSC SEND ERROR: {err}")
-                }
-            }
+            Ok(ws::Message::Ping(msg)) if self.publisher => self.publish(msg.to_vec()),
+            Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
+            Ok(ws::Message::Text(msg)) => self.publish(msg.into_bytes().to_vec()),
+            Ok(ws::Message::Binary(msg)) => self.publish(msg.to_vec()),
             Ok(_) => {}
             Err(err) => dyno_core::log::error!("Websocket Error: {err}"),
         }
     }
 }
 
+/// # Websocket Endpoint `websocket_endpoint`
+/// -----------------------------------------------------------------
+/// URL                 => `/ws`
+/// GUARD               => `GET`
+/// HEADER/COOKIES      => [`crate::middlewares::ApiKeyMiddleware`] with `publish_live` scope to publish
+///
+/// connection without the api key only receive the live data that is published by the station.
+/// -----------------------------------------------------------------
 #[get("/ws")]
 pub async fn websocket_endpoint(
     req: HttpRequest,
     stream: web::Payload,
+    api_key: Option<ApiKeyMiddleware>,
     data: web::Data<ServerState>,
 ) -> HttpResponse {
-    match ws::WsResponseBuilder::new(WsConn::new(data.ws_sender.clone()), &req, stream).start() {
+    let publisher = api_key.is_some_and(|x| x.has(ApiScope::PublishLive));
+    let conn = WsConn::new(data.ws_sender.clone(), publisher);
+    match ws::WsResponseBuilder::new(conn, &req, stream).start() {
         Ok(response) => response,
         Err(err) => err.error_response(),
     }
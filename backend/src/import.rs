@@ -0,0 +1,238 @@
+use std::io::Cursor;
+
+use calamine::Reader;
+use dyno_core::{chrono::NaiveDateTime, serde, BufferData, Data, DynoConfig, DynoErr, DynoResult};
+
+/// number of rows returned in [`ImportPreview`]
+pub const PREVIEW_ROWS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetFormat {
+    Csv,
+    Excel,
+}
+
+impl SheetFormat {
+    pub fn from_file_name(name: impl AsRef<str>) -> Option<Self> {
+        let name = name.as_ref().to_lowercase();
+        if name.ends_with(".csv") || name.ends_with(".txt") {
+            Some(Self::Csv)
+        } else if name.ends_with(".xlsx") || name.ends_with(".xls") || name.ends_with(".ods") {
+            Some(Self::Excel)
+        } else {
+            None
+        }
+    }
+}
+
+/// which column header of the spreadsheet is mapped into which field of [`Data`],
+/// column that is `None` is left with default value.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ColumnMapping {
+    /// elapsed time in seconds since the start of the test
+    pub time: Option<String>,
+    pub speed: Option<String>,
+    pub odo: Option<String>,
+    pub rpm_roda: Option<String>,
+    pub rpm_engine: Option<String>,
+    pub torque: Option<String>,
+    pub horsepower: Option<String>,
+    pub temp: Option<String>,
+    /// numbers is written with `,` as decimal separator, ex: `12,5`
+    #[serde(default)]
+    pub decimal_comma: bool,
+}
+
+impl ColumnMapping {
+    /// mapped column in the same order as destructured in [`Sheet::into_buffer`]
+    fn columns(&self) -> [Option<&String>; 8] {
+        [
+            self.time.as_ref(),
+            self.speed.as_ref(),
+            self.odo.as_ref(),
+            self.rpm_roda.as_ref(),
+            self.rpm_engine.as_ref(),
+            self.torque.as_ref(),
+            self.horsepower.as_ref(),
+            self.temp.as_ref(),
+        ]
+    }
+}
+
+/// json part `spec` of multipart POST `/api/dyno/import`
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ImportSpec {
+    pub mapping: ColumnMapping,
+    pub config: DynoConfig,
+    pub start: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+}
+
+pub struct ImportedBuffer {
+    pub buffer: BufferData,
+    /// duration of the test in milliseconds, taken from the last row of `time` column
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Sheet {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Sheet {
+    pub fn parse(bytes: &[u8], format: SheetFormat) -> DynoResult<Self> {
+        let sheet = match format {
+            SheetFormat::Csv => Self::parse_csv(bytes),
+            SheetFormat::Excel => Self::parse_excel(bytes),
+        }?;
+        if sheet.headers.is_empty() {
+            return Err(DynoErr::bad_request_error(
+                "Spreadsheet has no header row to be mapped",
+            ));
+        }
+        Ok(sheet)
+    }
+
+    fn parse_csv(bytes: &[u8]) -> DynoResult<Self> {
+        let first_line = bytes.split(|b| *b == b'\n').next().unwrap_or_default();
+        let delimiter = if first_line.contains(&b';') && !first_line.contains(&b',') {
+            b';'
+        } else {
+            b','
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(bytes);
+
+        let headers = reader
+            .headers()
+            .map_err(DynoErr::bad_request_error)?
+            .iter()
+            .map(ToOwned::to_owned)
+            .collect();
+        let rows = reader
+            .records()
+            .map(|record| {
+                record
+                    .map(|r| r.iter().map(ToOwned::to_owned).collect::<Vec<_>>())
+                    .map_err(DynoErr::bad_request_error)
+            })
+            .collect::<DynoResult<Vec<_>>>()?;
+        Ok(Self { headers, rows })
+    }
+
+    fn parse_excel(bytes: &[u8]) -> DynoResult<Self> {
+        let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(bytes))
+            .map_err(DynoErr::bad_request_error)?;
+        let range = workbook
+            .worksheet_range_at(0)
+            .ok_or(DynoErr::bad_request_error("Spreadsheet has no worksheet"))?
+            .map_err(DynoErr::bad_request_error)?;
+
+        let mut rows = range
+            .rows()
+            .map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>());
+        let headers = rows.next().unwrap_or_default();
+        Ok(Self {
+            headers,
+            rows: rows.collect(),
+        })
+    }
+
+    pub fn preview(&self) -> ImportPreview {
+        ImportPreview {
+            headers: self.headers.clone(),
+            rows: self.rows.iter().take(PREVIEW_ROWS).cloned().collect(),
+            total_rows: self.rows.len(),
+        }
+    }
+
+    fn column_index(&self, name: &str) -> DynoResult<usize> {
+        self.headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name.trim()))
+            .ok_or(DynoErr::bad_request_error(format!(
+                "Mapped column `{name}` is not exists in spreadsheet"
+            )))
+    }
+
+    pub fn into_buffer(self, mapping: &ColumnMapping) -> DynoResult<ImportedBuffer> {
+        let mut indexes = [None; 8];
+        for (idx, column) in mapping.columns().into_iter().enumerate() {
+            if let Some(column) = column {
+                indexes[idx] = Some(self.column_index(column)?);
+            }
+        }
+        if indexes.iter().skip(1).all(Option::is_none) {
+            return Err(DynoErr::bad_request_error(
+                "Column mapping should map at least one data column",
+            ));
+        }
+
+        let parse = |row_idx: usize, row: &[String], col: Option<usize>| -> DynoResult<f64> {
+            let Some(col) = col else { return Ok(0.0) };
+            let cell = row.get(col).map(|x| x.trim()).unwrap_or_default();
+            if cell.is_empty() {
+                return Ok(0.0);
+            }
+            let cell = if mapping.decimal_comma {
+                cell.replace('.', "").replace(',', ".")
+            } else {
+                cell.to_owned()
+            };
+            cell.parse::<f64>().map_err(|_| {
+                DynoErr::bad_request_error(format!(
+                    "Value `{cell}` in row {} column `{}` is not a number",
+                    row_idx + 2,
+                    self.headers[col]
+                ))
+            })
+        };
+
+        let mut buffer = BufferData::new();
+        let mut duration_ms = 0;
+        let mut pushed = 0usize;
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            if row.iter().all(|x| x.trim().is_empty()) {
+                continue;
+            }
+            let [time, speed, odo, rpm_roda, rpm_engine, torque, horsepower, temp] = indexes;
+            let elapsed = parse(row_idx, row, time)?;
+            duration_ms = (elapsed * 1000.0) as i64;
+            buffer.push_from_data(&Data {
+                speed: parse(row_idx, row, speed)?.into(),
+                odo: parse(row_idx, row, odo)?.into(),
+                rpm_roda: parse(row_idx, row, rpm_roda)?.into(),
+                rpm_engine: parse(row_idx, row, rpm_engine)?.into(),
+                torque: parse(row_idx, row, torque)?.into(),
+                horsepower: parse(row_idx, row, horsepower)?.into(),
+                temp: parse(row_idx, row, temp)?.into(),
+                ..Default::default()
+            });
+            pushed += 1;
+        }
+        if pushed == 0 {
+            return Err(DynoErr::bad_request_error("Spreadsheet has no data rows"));
+        }
+
+        Ok(ImportedBuffer {
+            buffer,
+            duration_ms,
+        })
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+
+use dyno_core::{
+    chrono::{Duration, NaiveDateTime, Utc},
+    crypto::compare_checksums,
+    log, serde, DynoErr, DynoResult,
+};
+
+use crate::{actions::dyno as dyno_actions, checksum, ServerState};
+
+/// object that is newer than this is never flagged as orphan,
+/// because its `Dynos` row may not be inserted yet.
+const ORPHAN_GRACE_HOURS: i64 = 1;
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct AuditStatus {
+    pub running: bool,
+    pub last: Option<AuditReport>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct AuditEntry {
+    pub dyno_id: i64,
+    pub key: String,
+    pub expected: String,
+    /// `None` when the file is missing or can not be read
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct AuditReport {
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+    pub checked: usize,
+    pub mismatches: Vec<AuditEntry>,
+    pub missing: Vec<AuditEntry>,
+    /// stored keys that has no `Dynos` row
+    pub orphans: Vec<String>,
+    /// orphans that is removed by the repair step
+    pub removed: Vec<String>,
+}
+
+/// mark the audit as running, fail when another audit is still running.
+pub fn try_start(state: &ServerState) -> DynoResult<()> {
+    let mut status = state
+        .audit
+        .lock()
+        .map_err(|_| DynoErr::internal_server_error("Audit status lock is poisoned"))?;
+    if status.running {
+        return Err(DynoErr::bad_request_error(
+            "Storage audit is already running",
+        ));
+    }
+    status.running = true;
+    Ok(())
+}
+
+fn finish(state: &ServerState, report: Option<AuditReport>) {
+    if let Ok(mut status) = state.audit.lock() {
+        status.running = false;
+        if report.is_some() {
+            status.last = report;
+        }
+    }
+}
+
+/// run the audit when no other audit is running
+pub fn run(state: &ServerState, repair: bool) -> DynoResult<AuditReport> {
+    try_start(state)?;
+    run_started(state, repair)
+}
+
+/// run the audit that is already marked running by [`try_start`]
+pub fn run_started(state: &ServerState, repair: bool) -> DynoResult<AuditReport> {
+    let result = audit(state, repair);
+    match &result {
+        Ok(report) => log::info!(
+            "[AUDIT] checked {} dynos, {} mismatches, {} missing, {} orphans, {} removed",
+            report.checked,
+            report.mismatches.len(),
+            report.missing.len(),
+            report.orphans.len(),
+            report.removed.len()
+        ),
+        Err(err) => log::error!("[AUDIT] failed - {err}"),
+    }
+    finish(state, result.as_ref().ok().cloned());
+    result
+}
+
+fn audit(state: &ServerState, repair: bool) -> DynoResult<AuditReport> {
+    let started_at = Utc::now().naive_utc();
+    // list before selecting rows, so object stored while auditing always has its row selected
+    let objects = state.storage.list()?;
+    let dynos = state
+        .db
+        .get()
+        .map_err(DynoErr::database_error)
+        .and_then(|mut conn| dyno_actions::select_all(&mut conn))?;
+
+    let stored = objects
+        .iter()
+        .map(|x| (x.key.as_str(), x))
+        .collect::<HashMap<_, _>>();
+    let mut mismatches = vec![];
+    let mut missing = vec![];
+    let mut unsized_urls = HashMap::new();
+    for dyno in &dynos {
+        let entry = |actual| AuditEntry {
+            dyno_id: dyno.id,
+            key: dyno.data_url.clone(),
+            expected: dyno.data_checksum.clone(),
+            actual,
+        };
+        let Some(object) = stored.get(dyno.data_url.as_str()) else {
+            missing.push(entry(None));
+            continue;
+        };
+        if dyno.data_size == 0 && object.size > 0 {
+            unsized_urls.insert(dyno.data_url.as_str(), object.size as i64);
+        }
+        match state.storage.get(&dyno.data_url) {
+            Ok(bytes) => {
+                let actual = checksum::checksum_bytes(&bytes);
+                if !compare_checksums(actual.as_bytes(), dyno.data_checksum.as_bytes()) {
+                    mismatches.push(entry(Some(actual)));
+                }
+            }
+            Err(err) => {
+                log::error!("[AUDIT] failed to read {} - {err}", dyno.data_url);
+                missing.push(entry(None));
+            }
+        }
+    }
+
+    // record stored before the size is tracked, so it is counted in the user quota
+    if !unsized_urls.is_empty() {
+        let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+        for (data_url, size) in unsized_urls {
+            dyno_actions::update_data_size(&mut conn, data_url, size)?;
+        }
+    }
+
+    let referenced = dynos
+        .iter()
+        .map(|x| x.data_url.as_str())
+        .collect::<HashSet<_>>();
+    let grace = started_at - Duration::hours(ORPHAN_GRACE_HOURS);
+    let orphans = objects
+        .iter()
+        .filter(|x| !referenced.contains(x.key.as_str()))
+        .filter(|x| x.modified.map_or(true, |modified| modified < grace))
+        .map(|x| x.key.clone())
+        .collect::<Vec<_>>();
+
+    let mut removed = vec![];
+    if repair {
+        for key in &orphans {
+            match state.storage.delete(key) {
+                Ok(()) => removed.push(key.clone()),
+                Err(err) => log::error!("[AUDIT] failed to remove orphan {key} - {err}"),
+            }
+        }
+    }
+
+    Ok(AuditReport {
+        started_at,
+        finished_at: Utc::now().naive_utc(),
+        checked: dynos.len(),
+        mismatches,
+        missing,
+        orphans,
+        removed,
+    })
+}
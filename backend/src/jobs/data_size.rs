@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use dyno_core::{log, DynoErr, DynoResult};
+
+use crate::{actions::dyno as dyno_actions, ServerState};
+
+/// set `data_size` of every [`crate::models::dyno::Dynos`] that is still `0` from the size of its
+/// stored object, so record created before the size is tracked is counted in the user quota.
+/// returns the number of updated `data_url`.
+pub fn backfill(state: &ServerState) -> DynoResult<usize> {
+    let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+    let unsized_urls = dyno_actions::select_unsized_data_urls(&mut conn)?;
+    if unsized_urls.is_empty() {
+        return Ok(0);
+    }
+
+    let sizes = state
+        .storage
+        .list()?
+        .into_iter()
+        .filter(|x| x.size > 0)
+        .map(|x| (x.key, x.size as i64))
+        .collect::<HashMap<_, _>>();
+    let mut updated = 0;
+    for data_url in &unsized_urls {
+        match sizes.get(data_url) {
+            Some(&size) => {
+                dyno_actions::update_data_size(&mut conn, data_url, size)?;
+                updated += 1;
+            }
+            None => log::warn!("[DATA SIZE] stored object of {data_url} is missing"),
+        }
+    }
+    Ok(updated)
+}
@@ -0,0 +1,134 @@
+pub mod audit;
+pub mod data_size;
+pub mod retention;
+pub mod upload_cleanup;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use dyno_core::{log, DynoResult};
+
+use crate::ServerState;
+
+/// how often the scheduler check for due jobs
+const TICK: Duration = Duration::from_secs(60);
+
+/// job that run periodically by the background scheduler
+struct ScheduledJob {
+    name: &'static str,
+    interval: Duration,
+    last_run: Instant,
+    run: fn(&ServerState) -> DynoResult<()>,
+}
+
+fn scheduled_jobs(state: &ServerState) -> Vec<ScheduledJob> {
+    let hours = |h: u64| Duration::from_secs(h * 60 * 60);
+    let now = Instant::now();
+    let mut jobs = vec![];
+    if let Some(interval) = state.cfg.jobs.audit_interval_hours {
+        jobs.push(ScheduledJob {
+            name: "storage audit",
+            interval: hours(interval),
+            last_run: now,
+            run: |state| audit::run(state, state.cfg.jobs.audit_repair).map(|_| ()),
+        });
+    }
+    if let (Some(interval), Some(months)) = (
+        state.cfg.jobs.retention_interval_hours,
+        state.cfg.jobs.archive_unverified_months,
+    ) {
+        jobs.push(ScheduledJob {
+            name: "retention",
+            interval: hours(interval),
+            last_run: now,
+            run: |state| {
+                state
+                    .cfg
+                    .jobs
+                    .archive_unverified_months
+                    .map_or(Ok(()), |months| retention::run(state, months).map(|_| ()))
+            },
+        });
+        log::info!("Retention archive unverified dynotest older than {months} months");
+    }
+    if let (Some(interval), Some(days)) = (
+        state.cfg.jobs.retention_interval_hours,
+        state.cfg.jobs.login_failure_retention_days,
+    ) {
+        jobs.push(ScheduledJob {
+            name: "login failure retention",
+            interval: hours(interval),
+            last_run: now,
+            run: |state| {
+                state
+                    .cfg
+                    .jobs
+                    .login_failure_retention_days
+                    .map_or(Ok(()), |days| {
+                        retention::prune_login_failures(state, days).map(|_| ())
+                    })
+            },
+        });
+        log::info!("Retention delete failed login older than {days} days");
+    }
+    if let Some(interval) = state.cfg.jobs.upload_cleanup_interval_hours {
+        jobs.push(ScheduledJob {
+            name: "upload cleanup",
+            interval: hours(interval),
+            last_run: now,
+            run: |state| upload_cleanup::run(state).map(|_| ()),
+        });
+    }
+    jobs
+}
+
+/// spawn thread that run every [`ScheduledJob`] when it is due, until `running` is `false`
+pub fn spawn_scheduler(state: ServerState, running: Arc<AtomicBool>) {
+    let mut jobs = scheduled_jobs(&state);
+    if jobs.is_empty() {
+        log::info!("No scheduled jobs is configured");
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("dyno-scheduler".to_owned())
+        .spawn(move || {
+            log::info!("Running scheduler for {} jobs", jobs.len());
+            while running.load(Ordering::Relaxed) {
+                std::thread::sleep(TICK);
+                for job in jobs.iter_mut() {
+                    if job.last_run.elapsed() < job.interval {
+                        continue;
+                    }
+                    job.last_run = Instant::now();
+                    log::info!("[JOB] running {}", job.name);
+                    if let Err(err) = (job.run)(&state) {
+                        log::error!("[JOB] {} failed - {err}", job.name);
+                    }
+                }
+            }
+            log::info!("Stop scheduler");
+        });
+    if let Err(err) = spawned {
+        log::error!("Failed to spawn scheduler thread - {err}");
+    }
+}
+
+/// spawn thread that run [`data_size::backfill`] once at startup, so the quota of record created
+/// before the size is tracked does not wait for the storage audit
+pub fn spawn_backfill(state: ServerState) {
+    let spawned = std::thread::Builder::new()
+        .name("dyno-backfill".to_owned())
+        .spawn(move || match data_size::backfill(&state) {
+            Ok(0) => {}
+            Ok(updated) => log::info!("[JOB] data size is backfilled for {updated} dynos data"),
+            Err(err) => log::error!("[JOB] data size backfill failed - {err}"),
+        });
+    if let Err(err) = spawned {
+        log::error!("Failed to spawn data size backfill thread - {err}");
+    }
+}
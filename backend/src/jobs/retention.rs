@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use dyno_core::{
+    chrono::{Duration, Utc},
+    log, DynoErr, DynoResult,
+};
+
+use crate::{
+    actions::{dyno as dyno_actions, login_failure as login_failure_actions},
+    storage, ServerState,
+};
+
+/// move the data of unverified dynotest that is older than `months` into `archive/`,
+/// data that is shared with a dynotest that is not eligible is kept in place.
+pub fn run(state: &ServerState, months: u32) -> DynoResult<usize> {
+    let before = Utc::now().naive_utc() - Duration::days(i64::from(months) * 30);
+    let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+
+    let mut eligible = HashMap::<String, i64>::new();
+    for dyno in dyno_actions::select_archivable(&mut conn, before)? {
+        *eligible.entry(dyno.data_url).or_default() += 1;
+    }
+
+    let mut archived = 0;
+    for (data_url, count) in eligible {
+        if dyno_actions::count_by_data_url(&mut conn, &data_url)? != count {
+            continue;
+        }
+        let archive_key = storage::archive_key(&data_url);
+        if let Err(err) = state.storage.rename(&data_url, &archive_key) {
+            log::error!("[RETENTION] failed to archive {data_url} - {err}");
+            continue;
+        }
+        archived += dyno_actions::archive(&mut conn, &data_url, &archive_key)?;
+    }
+    log::info!("[RETENTION] archived {archived} dynotest");
+    Ok(archived)
+}
+
+/// delete failed login record that is older than `days`
+pub fn prune_login_failures(state: &ServerState, days: u32) -> DynoResult<usize> {
+    let before = Utc::now().naive_utc() - Duration::days(i64::from(days));
+    let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+    let deleted = login_failure_actions::delete_before(&mut conn, before)?;
+    log::info!("[RETENTION] deleted {deleted} failed login older than {days} days");
+    Ok(deleted)
+}
@@ -0,0 +1,38 @@
+use dyno_core::{
+    chrono::{Duration, Utc},
+    log, DynoErr, DynoResult,
+};
+
+use crate::{actions::upload as upload_actions, handler::resumable::part_path, ServerState};
+
+/// remove resumable upload session that is not updated for `Upload::session_expiry_hours`
+/// together with its `.part` file, session that is busy with a chunk or finalize is skipped.
+pub fn run(state: &ServerState) -> DynoResult<usize> {
+    let before =
+        Utc::now().naive_utc() - Duration::hours(state.cfg.upload.session_expiry_hours as i64);
+    let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+
+    let mut removed = 0;
+    for session in upload_actions::select_expired(&mut conn, before)? {
+        let uuid = session.uuid.to_string();
+        let Some(_lock) = state.upload_locks.try_lock(uuid.as_str()) else {
+            continue;
+        };
+        if upload_actions::delete_expired(&mut conn, session.id, before)? == 0 {
+            continue;
+        }
+        let part_path = part_path(&state.cfg.upload.tmp_path, &uuid);
+        match std::fs::remove_file(&part_path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                log::error!(
+                    "[UPLOAD CLEANUP] failed to remove {} - {err}",
+                    part_path.display()
+                )
+            }
+            _ => {}
+        }
+        removed += 1;
+    }
+    log::info!("[UPLOAD CLEANUP] removed {removed} expired upload session");
+    Ok(removed)
+}
@@ -0,0 +1,84 @@
+mod smtp;
+
+use std::sync::Arc;
+
+use dyno_core::{log, DynoResult};
+
+use crate::config;
+
+pub use smtp::SmtpMailer;
+
+/// plain text email sent by the server
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// how email is delivered, SMTP server or only logged when SMTP is not configured
+pub trait Mailer: std::fmt::Debug + Send + Sync {
+    /// human readable name of the mailer, used in logs
+    fn name(&self) -> &'static str;
+
+    /// send the mail, blocking until it is accepted by the server
+    fn send(&self, mail: Mail) -> DynoResult<()>;
+}
+
+/// development mailer enabled by `DYNO_MAIL_LOG`, only the recipient and subject are logged
+/// because the body contains the live reset and verification token.
+#[derive(Debug, Default)]
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send(&self, Mail { to, subject, .. }: Mail) -> DynoResult<()> {
+        log::warn!("[MAIL] SMTP is not configured, mail to {to} - {subject} is not sent");
+        Ok(())
+    }
+}
+
+pub type DynoMailer = Arc<dyn Mailer>;
+
+/// configured [`Mailer`] with the url of the frontend used in the link inside the mail
+#[derive(Debug, Clone)]
+pub struct MailSender {
+    pub mailer: DynoMailer,
+    pub public_url: String,
+}
+
+impl MailSender {
+    /// send the mail in the blocking pool without waiting for it, so the response time does not
+    /// depend on the SMTP server nor reveal whether the mail is sent. failure is only logged.
+    pub fn send_detached(&self, mail: Mail) {
+        let mailer = self.mailer.clone();
+        actix_web::rt::task::spawn_blocking(move || {
+            let subject = mail.subject.clone();
+            if let Err(err) = mailer.send(mail) {
+                log::error!("Failed to send `{subject}` mail - {err}");
+            }
+        });
+    }
+}
+
+/// create the [`MailSender`] configured by [`config::Mail`], `None` when mail is disabled because
+/// neither SMTP nor `DYNO_MAIL_LOG` is set or `DYNO_PUBLIC_URL` is missing. the server still
+/// start, only password reset and email verification respond `503 Service Unavailable`.
+pub fn init(cfg: &config::Mail) -> DynoResult<Option<MailSender>> {
+    let Some(public_url) = cfg.public_url.clone().filter(|_| cfg.is_enabled()) else {
+        log::warn!(
+            "Mail is disabled, set `DYNO_SMTP_HOST` (or `DYNO_MAIL_LOG=true` for development) \
+            and `DYNO_PUBLIC_URL` to enable password reset and email verification"
+        );
+        return Ok(None);
+    };
+    let mailer: DynoMailer = match &cfg.smtp {
+        Some(smtp) => Arc::new(SmtpMailer::new(smtp, &cfg.from)?),
+        None => Arc::new(LogMailer),
+    };
+    log::info!("✅ Using {} mailer", mailer.name());
+    Ok(Some(MailSender { mailer, public_url }))
+}
@@ -0,0 +1,61 @@
+use dyno_core::{DynoErr, DynoResult};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::{authentication::Credentials, SmtpTransport},
+    Message, Transport,
+};
+
+use super::{Mail, Mailer};
+use crate::config;
+
+/// deliver mail through SMTP, ex: local MailHog `DYNO_SMTP_HOST=127.0.0.1 DYNO_SMTP_PORT=1025`
+#[derive(Debug)]
+pub struct SmtpMailer {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(cfg: &config::Smtp, from: &str) -> DynoResult<Self> {
+        let mut builder = if cfg.tls {
+            SmtpTransport::starttls_relay(&cfg.host).map_err(DynoErr::internal_server_error)?
+        } else {
+            SmtpTransport::builder_dangerous(&cfg.host)
+        }
+        .port(cfg.port);
+        if let Some(username) = &cfg.username {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                cfg.password.clone().unwrap_or_default(),
+            ));
+        }
+        let from = from.parse::<Mailbox>().map_err(|err| {
+            DynoErr::internal_server_error(format!("`DYNO_MAIL_FROM` is invalid - {err}"))
+        })?;
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn send(&self, Mail { to, subject, body }: Mail) -> DynoResult<()> {
+        let to = to.parse::<Mailbox>().map_err(DynoErr::bad_request_error)?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(DynoErr::internal_server_error)?;
+        self.transport
+            .send(&message)
+            .map(|_| ())
+            .map_err(DynoErr::internal_server_error)
+    }
+}
@@ -1,10 +1,19 @@
 mod actions;
+mod checksum;
 mod config;
 mod handler;
+mod import;
+mod jobs;
+mod mailer;
+mod metrics;
 mod middlewares;
 mod models;
 mod schema;
 mod seeder;
+mod storage;
+mod token;
+mod totp;
+mod upload;
 
 use std::{
     collections::HashSet,
@@ -24,6 +33,21 @@ mod db {
     pub type DynoDBConnManager = diesel::r2d2::ConnectionManager<DynoDBConn>;
     pub type DynoDBPool = diesel::r2d2::Pool<DynoDBConnManager>;
     pub type DynoDBPooledConnection = diesel::r2d2::PooledConnection<DynoDBConnManager>;
+
+    /// sqlite only enforce `REFERENCES` and `ON DELETE CASCADE` when `foreign_keys` is enabled,
+    /// and it is a per connection setting, so it is set on every connection of the pool.
+    /// `busy_timeout` make a writer wait for the lock of [`crate::actions::transaction`]
+    /// instead of failing with `database is locked`.
+    #[derive(Debug, Clone, Copy)]
+    pub struct DynoDBCustomizer;
+
+    impl diesel::r2d2::CustomizeConnection<DynoDBConn, diesel::r2d2::Error> for DynoDBCustomizer {
+        fn on_acquire(&self, conn: &mut DynoDBConn) -> Result<(), diesel::r2d2::Error> {
+            use diesel::connection::SimpleConnection;
+            conn.batch_execute("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;")
+                .map_err(diesel::r2d2::Error::QueryError)
+        }
+    }
 }
 use db::*;
 use handler::ws::WsConn;
@@ -63,6 +87,8 @@ async fn main() -> DynoResult<()> {
     );
 
     let start_ws = start.clone();
+    let ws_metrics = app_state.metrics.clone();
+    let ws_heartbeat = app_state.ws_heartbeat.clone();
     actix_web::rt::task::spawn_blocking(move || {
         log::info!("Running spawn actix runtime for websocket clients handler");
         let mut clients = HashSet::<Addr<WsConn>>::new();
@@ -70,18 +96,22 @@ async fn main() -> DynoResult<()> {
             if !start_ws.load(std::sync::atomic::Ordering::Relaxed) {
                 break;
             }
-            if let Ok(msg) = rx.recv() {
+            ws_heartbeat.beat();
+            if let Ok(msg) = rx.recv_timeout(handler::ws::HEARTBEAT_INTERVAL) {
                 match msg {
                     WsMessage::Disconn(addr) => {
                         log::info!("[MSG] websocket: {addr:?}");
                         clients.remove(&addr);
+                        ws_metrics.set_ws_clients(clients.len());
                     }
                     WsMessage::Conn(addr) => {
                         log::info!("[MSG] websocket: {addr:?}");
                         clients.insert(addr);
+                        ws_metrics.set_ws_clients(clients.len());
                     }
                     WsMessage::Msg(msg) => {
                         log::info!("[MSG] websocket: OnMessage");
+                        ws_metrics.ws_frame();
                         for client in &clients {
                             let msg = msg.clone();
                             client.do_send(msg);
@@ -93,7 +123,19 @@ async fn main() -> DynoResult<()> {
         log::info!("Stop spawn actix runtime for websocket clients handler");
     });
 
+    jobs::spawn_backfill(app_state.clone());
+    jobs::spawn_scheduler(app_state.clone(), start.clone());
+
     let root_path = get_and_check_path(&app_state.cfg.app_public_path, "root/");
+    // `Files` only serve the frontend bundle, dyno data is downloaded through `/api/dyno/{id}/file`
+    let storage_cfg = &app_state.cfg.storage;
+    if storage_cfg.s3.is_none() && storage_cfg.local_path.starts_with(&root_path) {
+        return Err(DynoErr::internal_server_error(format!(
+            "Storage path {} should not be inside the static served path {}",
+            storage_cfg.local_path.display(),
+            root_path.display()
+        )));
+    }
 
     let http_server = HttpServer::new(move || {
         App::new()
@@ -106,12 +148,14 @@ async fn main() -> DynoResult<()> {
                     .max_age(3600),
             )
             .configure(handler::api)
+            .service(handler::metrics::get_metrics)
             .service(
                 Files::new("/", root_path.clone())
                     .index_file("index.html")
                     .guard(guard::Get()),
             )
             .default_service(web::get().to(index))
+            .wrap(middlewares::HttpMetrics)
             .wrap(Logger::default())
     });
     log::info!("Running spawn actix runtime main server");
@@ -136,9 +180,18 @@ async fn main() -> DynoResult<()> {
 pub struct ServerState {
     pub db: DynoDBPool,
     pub cfg: config::ServerConfig,
+    pub storage: storage::DynoStorage,
+    /// `None` when mail is disabled, see [`mailer::init`]
+    pub mail: Option<mailer::MailSender>,
     pub active: Arc<Mutex<Option<ActiveUser>>>,
+    pub upload_locks: upload::UploadLocks,
+    pub audit: Arc<Mutex<jobs::audit::AuditStatus>>,
+    pub limiter: middlewares::Limiter,
+    pub metrics: metrics::Metrics,
 
     pub ws_sender: Sender<WsMessage>,
+    pub ws_heartbeat: handler::ws::Heartbeat,
+    pub started_at: std::time::Instant,
 }
 
 impl ServerState {
@@ -171,9 +224,14 @@ impl ServerState {
 
 fn server_init(ws_sender: Sender<WsMessage>) -> DynoResult<ServerState> {
     let cfg = config::ServerConfig::init();
+    let storage = storage::init(&cfg.storage)?;
+    let mail = mailer::init(&cfg.mail)?;
     let manager = DynoDBConnManager::new(&cfg.database_url);
 
-    match diesel::r2d2::Pool::builder().build(manager) {
+    match diesel::r2d2::Pool::builder()
+        .connection_customizer(Box::new(DynoDBCustomizer))
+        .build(manager)
+    {
         Ok(db) => {
             log::info!("✅ Connection to the database is successful!");
             match db
@@ -187,8 +245,16 @@ fn server_init(ws_sender: Sender<WsMessage>) -> DynoResult<ServerState> {
             Ok(ServerState {
                 db,
                 cfg,
+                storage,
+                mail,
                 active: Default::default(),
+                upload_locks: Default::default(),
+                audit: Default::default(),
+                limiter: Default::default(),
+                metrics: Default::default(),
                 ws_sender,
+                ws_heartbeat: Default::default(),
+                started_at: std::time::Instant::now(),
             })
         }
         Err(err) => Err(DynoErr::database_error(format!(
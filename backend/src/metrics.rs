@@ -0,0 +1,221 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// upper bound in seconds of every bucket of `dyno_http_request_duration_seconds`
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+/// route label of the request that is not matched by any route, so unknown paths
+/// can not grow the label set
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+#[derive(Debug, Default, Clone)]
+struct RouteStats {
+    statuses: BTreeMap<u16, u64>,
+    /// cumulative count of every [`LATENCY_BUCKETS`]
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+}
+
+/// frames counted in the current second, the rate of the last complete second is kept
+#[derive(Debug, Clone, Copy)]
+struct FrameRate {
+    start: Instant,
+    count: u64,
+    per_second: f64,
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            count: 0,
+            per_second: 0.0,
+        }
+    }
+}
+
+/// in memory counters exposed by `GET /metrics` in the prometheus text format,
+/// every clone share the same counters.
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    /// keyed by `(method, route pattern)`, ex: `("GET", "/api/dyno/{id}")`
+    http: Arc<Mutex<HashMap<(String, String), RouteStats>>>,
+    ws_clients: Arc<AtomicU64>,
+    ws_frames: Arc<AtomicU64>,
+    ws_frame_rate: Arc<Mutex<FrameRate>>,
+    upload_bytes: Arc<AtomicU64>,
+}
+
+impl Metrics {
+    pub fn http_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        let Ok(mut http) = self.http.lock() else {
+            return;
+        };
+        let stats = http
+            .entry((method.to_owned(), route.to_owned()))
+            .or_default();
+        let secs = elapsed.as_secs_f64();
+        *stats.statuses.entry(status).or_default() += 1;
+        for (bucket, le) in stats.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= le {
+                *bucket += 1;
+            }
+        }
+        stats.count += 1;
+        stats.sum_secs += secs;
+    }
+
+    /// number of connected websocket clients of the broadcast loop
+    pub fn set_ws_clients(&self, clients: usize) {
+        self.ws_clients.store(clients as u64, Ordering::Relaxed);
+    }
+
+    /// one frame published by the station and relayed to every client
+    pub fn ws_frame(&self) {
+        self.ws_frames.fetch_add(1, Ordering::Relaxed);
+        let Ok(mut rate) = self.ws_frame_rate.lock() else {
+            return;
+        };
+        let elapsed = rate.start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            rate.per_second = rate.count as f64 / elapsed.as_secs_f64();
+            rate.start = Instant::now();
+            rate.count = 0;
+        }
+        rate.count += 1;
+    }
+
+    pub fn ws_clients(&self) -> u64 {
+        self.ws_clients.load(Ordering::Relaxed)
+    }
+
+    pub fn upload_bytes(&self, len: u64) {
+        self.upload_bytes.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn ws_frames_per_second(&self) -> f64 {
+        let Ok(rate) = self.ws_frame_rate.lock() else {
+            return 0.0;
+        };
+        match rate.start.elapsed() {
+            // no frame in the last complete second, the station stopped publishing
+            elapsed if elapsed >= Duration::from_secs(2) => 0.0,
+            elapsed if elapsed >= Duration::from_secs(1) => {
+                rate.count as f64 / elapsed.as_secs_f64()
+            }
+            _ => rate.per_second,
+        }
+    }
+
+    /// write every counter of this [`Metrics`] in the prometheus text format
+    pub fn encode(&self, out: &mut String) {
+        if let Ok(http) = self.http.lock() {
+            let mut routes = http.iter().collect::<Vec<_>>();
+            routes.sort_by(|a, b| a.0.cmp(b.0));
+
+            header(
+                out,
+                "dyno_http_requests_total",
+                "counter",
+                "HTTP requests per route and status",
+            );
+            for ((method, route), stats) in &routes {
+                for (status, count) in &stats.statuses {
+                    let _ = writeln!(
+                        out,
+                        "dyno_http_requests_total{{method=\"{method}\",route=\"{}\",status=\"{status}\"}} {count}",
+                        escape(route)
+                    );
+                }
+            }
+
+            header(
+                out,
+                "dyno_http_request_duration_seconds",
+                "histogram",
+                "HTTP request latency per route",
+            );
+            for ((method, route), stats) in &routes {
+                let labels = format!("method=\"{method}\",route=\"{}\"", escape(route));
+                for (count, le) in stats.buckets.iter().zip(LATENCY_BUCKETS) {
+                    let _ = writeln!(
+                        out,
+                        "dyno_http_request_duration_seconds_bucket{{{labels},le=\"{le}\"}} {count}"
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "dyno_http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                    stats.count
+                );
+                let _ = writeln!(
+                    out,
+                    "dyno_http_request_duration_seconds_sum{{{labels}}} {}",
+                    stats.sum_secs
+                );
+                let _ = writeln!(
+                    out,
+                    "dyno_http_request_duration_seconds_count{{{labels}}} {}",
+                    stats.count
+                );
+            }
+        }
+
+        gauge(
+            out,
+            "dyno_ws_clients",
+            "Connected websocket clients",
+            self.ws_clients(),
+        );
+        counter(
+            out,
+            "dyno_ws_frames_relayed_total",
+            "Frames published by the station and relayed to the websocket clients",
+            self.ws_frames.load(Ordering::Relaxed),
+        );
+        gauge(
+            out,
+            "dyno_ws_frames_per_second",
+            "Frames relayed in the last complete second",
+            self.ws_frames_per_second(),
+        );
+        counter(
+            out,
+            "dyno_upload_bytes_total",
+            "Bytes received by the upload and import endpoints",
+            self.upload_bytes.load(Ordering::Relaxed),
+        );
+    }
+}
+
+fn header(out: &mut String, name: &str, tp: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {tp}");
+}
+
+pub fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, "gauge", help);
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// escape label value as required by the text format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,99 @@
+use std::future::ready;
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use dyno_core::{model::UserSession, DynoErr, DynoResult};
+use futures::future::LocalBoxFuture;
+
+use crate::{
+    actions::api_key as api_key_actions,
+    models::api_key::{ApiKey, ApiScope},
+    token,
+};
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// valid api key of a station from the `X-Api-Key` header, revoked or expired key is rejected.
+/// the scope is checked by the handler with [`ApiKeyMiddleware::require`]
+pub struct ApiKeyMiddleware(pub ApiKey);
+
+impl ApiKeyMiddleware {
+    pub fn require(&self, scope: ApiScope) -> DynoResult<()> {
+        if self.0.scopes.contains(scope) {
+            Ok(())
+        } else {
+            Err(DynoErr::forbidden_error(format!(
+                "Api key does not have `{}` scope",
+                scope.as_str()
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn has(&self, scope: ApiScope) -> bool {
+        self.0.scopes.contains(scope)
+    }
+}
+
+impl FromRequest for ApiKeyMiddleware {
+    type Error = DynoErr;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let state = req.app_data::<web::Data<crate::ServerState>>().cloned();
+        let key_hash = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|x| x.to_str().ok())
+            .map(token::hash_token);
+        Box::pin(async move {
+            let Some(state) = state else {
+                return Err(DynoErr::internal_server_error("No ServerState Data"));
+            };
+            let Some(key_hash) = key_hash else {
+                return Err(DynoErr::unauthorized_error(format!(
+                    "Api key is required in `{API_KEY_HEADER}` header"
+                )));
+            };
+            web::block(move || {
+                let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+                let api_key = api_key_actions::select_by_key_hash(&mut conn, &key_hash)?
+                    .filter(ApiKey::is_valid)
+                    .ok_or(DynoErr::unauthorized_error(
+                        "Api key is invalid, revoked or expired",
+                    ))?;
+                api_key_actions::touch(&mut conn, api_key.id)?;
+                Ok(Self(api_key))
+            })
+            .await
+            .map_err(DynoErr::internal_server_error)?
+        })
+    }
+}
+
+/// user of the JWT, or the active user of the station when the request has api key with
+/// [`ApiScope::Upload`], so the station can upload without the token of the student.
+pub struct UploaderMiddleware(pub UserSession);
+
+impl FromRequest for UploaderMiddleware {
+    type Error = DynoErr;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let jwt = super::jwt_from_req(req);
+        if jwt.is_ok() || !req.headers().contains_key(API_KEY_HEADER) {
+            return Box::pin(ready(jwt.map(Self)));
+        }
+        let state = req.app_data::<web::Data<crate::ServerState>>().cloned();
+        let api_key = ApiKeyMiddleware::from_request(req, payload);
+        Box::pin(async move {
+            api_key.await?.require(ApiScope::Upload)?;
+            state
+                .and_then(|state| state.get_active())
+                .and_then(|active| active.user)
+                .map(Self)
+                .ok_or(DynoErr::forbidden_error(
+                    "No active user in the station to upload for",
+                ))
+        })
+    }
+}
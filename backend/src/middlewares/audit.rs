@@ -0,0 +1,34 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use dyno_core::DynoErr;
+
+use crate::models::audit_log::NewAuditLog;
+
+/// ip of the request to write [`NewAuditLog`] with, ex:
+///
+/// ```ignore
+/// audit_log_actions::insert(&mut conn, auditor.entry(Some(session.id), "user.delete"))?;
+/// ```
+#[derive(Clone)]
+pub struct Auditor {
+    ip: Option<String>,
+}
+
+impl Auditor {
+    pub fn entry(&self, actor_id: Option<i64>, action: impl Into<String>) -> NewAuditLog {
+        NewAuditLog::new(actor_id, action).ip(self.ip.clone())
+    }
+}
+
+impl FromRequest for Auditor {
+    type Error = DynoErr;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let ip = req
+            .app_data::<web::Data<crate::ServerState>>()
+            .map(|data| super::client_ip(req, &data.cfg.rate_limit));
+        ready(Ok(Self { ip }))
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    time::Instant,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web,
+};
+use futures::future::LocalBoxFuture;
+
+use crate::metrics::UNMATCHED_ROUTE;
+
+/// count every request and its latency into [`crate::metrics::Metrics`], labeled by the matched
+/// route pattern (ex: `/api/dyno/{id}`) instead of the path, wrapped around the whole `App`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for HttpMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = HttpMetricsService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpMetricsService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct HttpMetricsService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for HttpMetricsService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let state = req.app_data::<web::Data<crate::ServerState>>().cloned();
+        let method = req.method().to_string();
+        let start = Instant::now();
+
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            if let Some(state) = state {
+                let route = res.request().match_pattern();
+                state.metrics.http_request(
+                    &method,
+                    route.as_deref().unwrap_or(UNMATCHED_ROUTE),
+                    res.status().as_u16(),
+                    start.elapsed(),
+                );
+            }
+            Ok(res)
+        })
+    }
+}
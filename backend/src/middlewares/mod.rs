@@ -1,8 +1,20 @@
+mod api_key;
+mod audit;
+mod metrics;
+mod permission;
+mod rate_limit;
+
 use std::future::{ready, Ready};
 
-use actix_web::{dev::Payload, http, web, FromRequest, HttpMessage, HttpRequest};
+use actix_web::{dev::Payload, http, web, FromRequest, HttpRequest};
 use dyno_core::{crypto::TokenDetails, model::UserSession, DynoErr, DynoResult};
 
+pub use api_key::{ApiKeyMiddleware, UploaderMiddleware, API_KEY_HEADER};
+pub use audit::Auditor;
+pub use metrics::HttpMetrics;
+pub use permission::{perm, RequireAdmin, RequirePermission};
+pub use rate_limit::{client_ip, too_many_requests, Limiter, RateLimit};
+
 fn jwt_from_req(req: &HttpRequest) -> DynoResult<UserSession> {
     let Some(data) = req.app_data::<web::Data<crate::ServerState>>() else {
         return Err(DynoErr::internal_server_error("No ServerState Data"));
@@ -38,7 +50,6 @@ fn jwt_from_req(req: &HttpRequest) -> DynoResult<UserSession> {
 }
 
 pub struct JwtUserMiddleware(pub UserSession);
-pub struct JwtAdminMiddleware(pub UserSession);
 
 impl FromRequest for JwtUserMiddleware {
     type Error = DynoErr;
@@ -47,18 +58,3 @@ impl FromRequest for JwtUserMiddleware {
         ready(jwt_from_req(req).map(Self))
     }
 }
-
-impl FromRequest for JwtAdminMiddleware {
-    type Error = DynoErr;
-    type Future = Ready<Result<Self, Self::Error>>;
-    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        match jwt_from_req(req) {
-            Ok(sess) if sess.role.is_admin() => {
-                req.extensions_mut().insert(sess);
-                ready(Ok(Self(sess)))
-            }
-            Ok(_) => ready(Err(DynoErr::forbidden_error("Admin Access Required!"))),
-            Err(err) => ready(Err(err)),
-        }
-    }
-}
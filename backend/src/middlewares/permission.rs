@@ -0,0 +1,99 @@
+use std::marker::PhantomData;
+
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use dyno_core::{model::UserSession, DynoErr};
+use futures::future::LocalBoxFuture;
+
+use crate::{
+    actions::permission as permission_actions,
+    models::permission::{AccessRole, Permission},
+};
+
+/// type level [`Permission`] that is required by [`RequirePermission`]
+pub trait PermissionMarker {
+    const PERMISSION: Permission;
+}
+
+macro_rules! permission_markers {
+    ($($name:ident),* $(,)?) => {$(
+        #[doc = concat!("marker of [`Permission::", stringify!($name), "`]")]
+        pub struct $name;
+        impl PermissionMarker for $name {
+            const PERMISSION: Permission = Permission::$name;
+        }
+    )*};
+}
+
+pub mod perm {
+    use super::{Permission, PermissionMarker};
+    permission_markers!(DynoVerify, DynoReadAll, UserManage, StationOperate);
+}
+
+/// user of the JWT whose access role is granted the permission `P`, ex:
+/// `RequirePermission<perm::UserManage>`
+pub struct RequirePermission<P: PermissionMarker>(pub UserSession, PhantomData<P>);
+
+impl<P: PermissionMarker + 'static> FromRequest for RequirePermission<P> {
+    type Error = DynoErr;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let session = super::jwt_from_req(req);
+        let state = req.app_data::<web::Data<crate::ServerState>>().cloned();
+        let req = req.clone();
+        Box::pin(async move {
+            let session = session?;
+            let Some(state) = state else {
+                return Err(DynoErr::internal_server_error("No ServerState Data"));
+            };
+            let user_id = session.id;
+            let granted = web::block(move || {
+                let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+                permission_actions::has_permission(&mut conn, user_id, P::PERMISSION)
+            })
+            .await
+            .map_err(DynoErr::internal_server_error)??;
+            if !granted {
+                return Err(DynoErr::forbidden_error(format!(
+                    "Permission `{}` Required!",
+                    P::PERMISSION.as_str()
+                )));
+            }
+            req.extensions_mut().insert(session);
+            Ok(Self(session, PhantomData))
+        })
+    }
+}
+
+/// user of the JWT whose access role is [`AccessRole::Admin`], the access role is read from the
+/// database so it is never granted by a stale token or by any [`Permission`] of other role
+pub struct RequireAdmin(pub UserSession);
+
+impl FromRequest for RequireAdmin {
+    type Error = DynoErr;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let session = super::jwt_from_req(req);
+        let state = req.app_data::<web::Data<crate::ServerState>>().cloned();
+        let req = req.clone();
+        Box::pin(async move {
+            let session = session?;
+            let Some(state) = state else {
+                return Err(DynoErr::internal_server_error("No ServerState Data"));
+            };
+            let user_id = session.id;
+            let role = web::block(move || {
+                let mut conn = state.db.get().map_err(DynoErr::database_error)?;
+                permission_actions::select_access_role(&mut conn, user_id)
+            })
+            .await
+            .map_err(DynoErr::internal_server_error)??;
+            if role != AccessRole::Admin {
+                return Err(DynoErr::forbidden_error("Admin Access Role Required!"));
+            }
+            req.extensions_mut().insert(session);
+            Ok(Self(session))
+        })
+    }
+}
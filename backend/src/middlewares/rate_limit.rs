@@ -0,0 +1,241 @@
+use std::{
+    collections::HashMap,
+    future::{ready, Ready},
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, StatusCode},
+    web, HttpRequest, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+
+use crate::config;
+
+/// the map never grow larger than this, so spoofed keys can not exhaust memory
+const MAX_ENTRIES: usize = 10_000;
+
+/// make room for a new key when the map is full, expired entries is dropped first and then the
+/// least recently used tenth, so the eviction scan is not repeated on every new key.
+fn evict<V>(
+    map: &mut HashMap<String, V>,
+    last_used: impl Fn(&V) -> Instant,
+    is_expired: impl Fn(&V) -> bool,
+) {
+    if map.len() < MAX_ENTRIES {
+        return;
+    }
+    map.retain(|_, x| !is_expired(x));
+    if map.len() < MAX_ENTRIES {
+        return;
+    }
+    let mut used = map.values().map(&last_used).collect::<Vec<_>>();
+    let (_, cutoff, _) = used.select_nth_unstable(MAX_ENTRIES / 10);
+    let cutoff = *cutoff;
+    map.retain(|_, x| last_used(x) > cutoff);
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    last: Instant,
+    blocked_until: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start: Instant,
+    last: Instant,
+    count: u32,
+}
+
+/// in memory counter of failed attempts and requests, keyed by `"{scope}:{ip}"` or `"nim:{nim}"`.
+/// failure after `free_attempts` is blocked with exponential backoff, and `lockout_attempts`
+/// failure lock the key for `lockout_minutes`, failures is forgotten after the lockout duration.
+#[derive(Debug, Default, Clone)]
+pub struct Limiter {
+    failures: Arc<Mutex<HashMap<String, Failures>>>,
+    requests: Arc<Mutex<HashMap<String, Window>>>,
+}
+
+impl Limiter {
+    /// remaining blocked duration of the `key`, `None` when it is allowed
+    pub fn blocked(&self, key: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let failures = self.failures.lock().ok()?;
+        failures
+            .get(key)
+            .and_then(|x| x.blocked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// record failed attempt of the `key`, return the blocked duration after this failure
+    pub fn failure(&self, cfg: &config::RateLimit, key: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().ok()?;
+        if !failures.contains_key(key) {
+            evict(
+                &mut failures,
+                |x| x.last,
+                |x| now.duration_since(x.last) >= cfg.lockout(),
+            );
+        }
+        let entry = failures.entry(key.to_owned()).or_insert(Failures {
+            count: 0,
+            last: now,
+            blocked_until: None,
+        });
+        if now.duration_since(entry.last) >= cfg.lockout() {
+            entry.count = 0;
+        }
+        entry.count += 1;
+        entry.last = now;
+        entry.blocked_until = cfg.backoff(entry.count).map(|dur| now + dur);
+        entry.blocked_until.map(|until| until - now)
+    }
+
+    /// forget failed attempts of the `key` after successful attempt
+    pub fn success(&self, key: &str) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.remove(key);
+        }
+    }
+
+    /// count request of the `key` in fixed window, return remaining window when it exceed the limit
+    pub fn hit(&self, cfg: &config::RateLimit, key: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let window = Duration::from_secs(cfg.window_secs);
+        let mut requests = self.requests.lock().ok()?;
+        if !requests.contains_key(key) {
+            evict(
+                &mut requests,
+                |x| x.last,
+                |x| now.duration_since(x.start) >= window,
+            );
+        }
+        let entry = requests.entry(key.to_owned()).or_insert(Window {
+            start: now,
+            last: now,
+            count: 0,
+        });
+        if now.duration_since(entry.start) >= window {
+            entry.start = now;
+            entry.count = 0;
+        }
+        entry.last = now;
+        entry.count += 1;
+        (entry.count > cfg.requests).then(|| window.saturating_sub(now.duration_since(entry.start)))
+    }
+}
+
+/// ip of the client, `X-Forwarded-For` is only used when `DYNO_RATE_LIMIT_TRUST_PROXY` is enabled
+pub fn client_ip(req: &HttpRequest, cfg: &config::RateLimit) -> String {
+    let forwarded = cfg
+        .trust_proxy
+        .then(|| {
+            req.connection_info()
+                .realip_remote_addr()
+                .map(ToOwned::to_owned)
+        })
+        .flatten();
+    forwarded
+        .or_else(|| req.peer_addr().map(|addr| addr.ip().to_string()))
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
+/// `429 Too Many Requests` with `Retry-After` header in seconds
+pub fn too_many_requests(retry_after: Duration) -> HttpResponse {
+    let secs = retry_after.as_secs().max(1);
+    let mut response = crate::handler::error_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!("Too many attempts, try again in {secs} seconds"),
+    );
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, header::HeaderValue::from(secs));
+    response
+}
+
+/// limit request rate per ip of the wrapped endpoint, and block the ip with backoff after
+/// responses with `401 Unauthorized`, ex: `#[post("/auth/login", wrap = "RateLimit::new(\"login\")")]`.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    scope: &'static str,
+}
+
+impl RateLimit {
+    pub const fn new(scope: &'static str) -> Self {
+        Self { scope }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+            scope: self.scope,
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    scope: &'static str,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let Some(state) = req.app_data::<web::Data<crate::ServerState>>().cloned() else {
+            return Box::pin(
+                async move { service.call(req).await.map(|res| res.map_into_left_body()) },
+            );
+        };
+        let cfg = &state.cfg.rate_limit;
+        let key = format!("{}:{}", self.scope, client_ip(req.request(), cfg));
+        if let Some(retry_after) = state
+            .limiter
+            .blocked(&key)
+            .or_else(|| state.limiter.hit(cfg, &key))
+        {
+            dyno_core::log::warn!("[RATE LIMIT] {key} is blocked for {retry_after:?}");
+            let response = too_many_requests(retry_after);
+            return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+        }
+
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            // success does not forget the failures of the ip, or a login into an owned account
+            // would reset the backoff between guesses, they decay after `lockout()` instead
+            if res.status() == StatusCode::UNAUTHORIZED {
+                state.limiter.failure(&state.cfg.rate_limit, &key);
+            }
+            Ok(res.map_into_left_body())
+        })
+    }
+}
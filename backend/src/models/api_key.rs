@@ -0,0 +1,184 @@
+use std::str::FromStr;
+
+use crate::schema::api_keys;
+use dyno_core::chrono::{NaiveDateTime, Utc};
+use dyno_core::{serde, DynoErr};
+
+/// what the holder of an [`ApiKey`] is allowed to do
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(crate = "serde", rename_all = "snake_case")]
+pub enum ApiScope {
+    /// broadcast live dyno data through the websocket
+    PublishLive,
+    /// set and clear the active dyno and user of the station
+    PostActive,
+    /// upload dynotest data on behalf of the active user of the station
+    Upload,
+}
+
+impl ApiScope {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PublishLive => "publish_live",
+            Self::PostActive => "post_active",
+            Self::Upload => "upload",
+        }
+    }
+}
+
+impl FromStr for ApiScope {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "publish_live" => Ok(Self::PublishLive),
+            "post_active" => Ok(Self::PostActive),
+            "upload" => Ok(Self::Upload),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown api key scope `{s}`"
+            ))),
+        }
+    }
+}
+
+/// scopes of an [`ApiKey`], saved as comma separated text
+#[derive(
+    serde::Deserialize, serde::Serialize, diesel::FromSqlRow, Debug, Default, Clone, PartialEq, Eq,
+)]
+#[serde(crate = "serde", transparent)]
+pub struct ApiScopes(pub Vec<ApiScope>);
+
+impl ApiScopes {
+    #[inline]
+    pub fn contains(&self, scope: ApiScope) -> bool {
+        self.0.contains(&scope)
+    }
+}
+
+impl std::fmt::Display for ApiScopes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scopes = self.0.iter().map(ApiScope::as_str).collect::<Vec<_>>();
+        f.write_str(&scopes.join(","))
+    }
+}
+
+impl FromStr for ApiScopes {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(ApiScope::from_str)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for ApiScopes
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+/// long lived key of a station, ex: the desktop app of the dyno, or a script
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Identifiable, diesel::Selectable)]
+#[diesel(table_name = api_keys)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub station: String,
+    /// first characters of the key, to recognize the key in the admin list
+    pub key_prefix: String,
+    /// sha256 hex of the key, the key itself is only shown once when it is created
+    pub key_hash: String,
+    pub scopes: ApiScopes,
+    pub created_by: i64,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ApiKey {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.revoked_at.is_none()
+            && self
+                .expires_at
+                .map_or(true, |expires_at| expires_at > Utc::now().naive_utc())
+    }
+
+    pub fn into_response(self) -> ApiKeyResponse {
+        ApiKeyResponse {
+            active: self.is_valid(),
+            id: self.id,
+            name: self.name,
+            station: self.station,
+            key_prefix: self.key_prefix,
+            scopes: self.scopes,
+            created_by: self.created_by,
+            last_used_at: self.last_used_at,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey {
+    pub name: String,
+    pub station: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    /// [`ApiScopes`] as comma separated text
+    pub scopes: String,
+    pub created_by: i64,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct CreateApiKey {
+    pub name: String,
+    pub station: String,
+    pub scopes: Vec<ApiScope>,
+    /// never expire when it is `None`
+    pub expires_days: Option<i64>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ApiKeyResponse {
+    pub id: i64,
+    pub name: String,
+    pub station: String,
+    pub key_prefix: String,
+    pub scopes: ApiScopes,
+    pub active: bool,
+    pub created_by: i64,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// response of the created key, `key` is never shown again
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ApiKeyCreated {
+    pub key: String,
+    pub api_key: ApiKeyResponse,
+}
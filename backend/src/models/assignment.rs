@@ -0,0 +1,152 @@
+use crate::schema::{assignments, submission_dynos, submissions};
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::serde;
+
+/// practicum task of the class, the submitted dynotests should satisfy the requirement
+/// of `engine_cc`, `engine_stroke` and `min_runs`
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(
+    Clone,
+    diesel::Queryable,
+    diesel::Identifiable,
+    diesel::Selectable,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(crate = "serde")]
+#[diesel(table_name = assignments)]
+pub struct Assignment {
+    pub id: i64,
+    pub class_id: i64,
+    pub title: String,
+    pub description: String,
+    /// required `cc` of the motor in the dynotest info, ex: `150`
+    pub engine_cc: Option<i16>,
+    /// required `stroke` of the motor in the dynotest info, ex: `4`
+    pub engine_stroke: Option<i16>,
+    /// minimum count of the submitted dynotests
+    pub min_runs: i32,
+    pub deadline: NaiveDateTime,
+    pub created_by: i64,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl Assignment {
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        dyno_core::chrono::Utc::now().naive_utc() > self.deadline
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize, diesel::Insertable)]
+#[serde(crate = "serde")]
+#[diesel(table_name = assignments)]
+pub struct NewAssignment {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub engine_cc: Option<i16>,
+    #[serde(default)]
+    pub engine_stroke: Option<i16>,
+    #[serde(default = "default_min_runs")]
+    pub min_runs: i32,
+    pub deadline: NaiveDateTime,
+    /// filled from the path and the session, not from the body
+    #[serde(skip)]
+    pub class_id: i64,
+    #[serde(skip)]
+    pub created_by: i64,
+}
+
+const fn default_min_runs() -> i32 {
+    1
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize, diesel::AsChangeset)]
+#[serde(crate = "serde")]
+#[diesel(table_name = assignments)]
+pub struct UpdateAssignment {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub engine_cc: Option<i16>,
+    pub engine_stroke: Option<i16>,
+    pub min_runs: Option<i32>,
+    pub deadline: Option<NaiveDateTime>,
+    #[serde(skip)]
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(
+    Clone,
+    diesel::Queryable,
+    diesel::Identifiable,
+    diesel::Selectable,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(crate = "serde")]
+#[diesel(table_name = submissions)]
+pub struct Submission {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub user_id: i64,
+    pub analysis: String,
+    pub grade: Option<f32>,
+    pub comment: Option<String>,
+    pub graded_by: Option<i64>,
+    pub graded_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = submissions)]
+pub struct NewSubmission {
+    pub assignment_id: i64,
+    pub user_id: i64,
+    pub analysis: String,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = submission_dynos)]
+pub struct NewSubmissionDyno {
+    pub submission_id: i64,
+    pub dyno_id: i64,
+}
+
+/// body of the student submission, resubmitting replace the previous dynotests and analysis
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SubmitAssignment {
+    pub dyno_ids: Vec<i64>,
+    pub analysis: String,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct GradeSubmission {
+    pub grade: f32,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// [`Submission`] with the student name and the submitted dynotests
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SubmissionResponse {
+    #[serde(flatten)]
+    pub submission: Submission,
+    pub nim: String,
+    pub name: String,
+    pub dyno_ids: Vec<i64>,
+}
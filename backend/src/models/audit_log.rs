@@ -0,0 +1,194 @@
+use crate::schema::audit_log;
+use dyno_core::{
+    chrono::{NaiveDate, NaiveDateTime},
+    serde,
+    serde_json::{self, Map, Value},
+};
+
+/// field of the serialized value that is never written into the [`AuditLog::changes`]
+const REDACTED_FIELDS: &[&str] = &["password", "secret", "hash", "token", "recovery"];
+/// field that is changed by every update, not worth to be recorded
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Selectable)]
+#[diesel(table_name = audit_log)]
+pub struct AuditLog {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub changes: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// entry of the `audit_log` table, written with [`crate::actions::audit_log::insert`]
+///
+/// ```ignore
+/// NewAuditLog::new(Some(session.id), "user.delete")
+///     .target("user", user.id)
+///     .diff(Some(&user), None::<&User>)
+/// ```
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewAuditLog {
+    pub actor_id: Option<i64>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub changes: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl NewAuditLog {
+    /// `action` is the `{target}.{verb}`, ex: `user.update`, `dyno.verify`
+    pub fn new(actor_id: Option<i64>, action: impl Into<String>) -> Self {
+        Self {
+            actor_id,
+            action: action.into(),
+            target_type: None,
+            target_id: None,
+            changes: None,
+            ip: None,
+        }
+    }
+
+    pub fn target(mut self, target_type: &str, target_id: i64) -> Self {
+        self.target_type = Some(target_type.to_owned());
+        self.target_id = Some(target_id);
+        self
+    }
+
+    pub fn ip(mut self, ip: Option<String>) -> Self {
+        self.ip = ip;
+        self
+    }
+
+    /// only the changed fields between `before` and `after`, `None` is the value before it
+    /// is created or after it is deleted
+    pub fn diff<B, A>(mut self, before: Option<&B>, after: Option<&A>) -> Self
+    where
+        B: serde::Serialize,
+        A: serde::Serialize,
+    {
+        let to_value = |x: Option<Value>| x.map(redact).unwrap_or(Value::Null);
+        let before = to_value(before.and_then(|x| serde_json::to_value(x).ok()));
+        let after = to_value(after.and_then(|x| serde_json::to_value(x).ok()));
+        let changes = diff_value(before, after);
+        self.changes = (!changes.is_empty()).then(|| Value::Object(changes).to_string());
+        self
+    }
+
+    /// the `details` of the action that has no before state, ex: the `verified` of `dyno.verify`
+    pub fn details(self, details: &impl serde::Serialize) -> Self {
+        self.diff(None::<&()>, Some(details))
+    }
+}
+
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| {
+                    if REDACTED_FIELDS.iter().any(|x| k.contains(x)) {
+                        (k, Value::String("[redacted]".to_owned()))
+                    } else {
+                        (k, redact(v))
+                    }
+                })
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// `{"field": {"before": .., "after": ..}}` of every field which is different, a value which is
+/// not an object is put in the field `value`
+fn diff_value(before: Value, after: Value) -> Map<String, Value> {
+    let change = |before: Value, after: Value| {
+        let mut map = Map::new();
+        map.insert("before".to_owned(), before);
+        map.insert("after".to_owned(), after);
+        Value::Object(map)
+    };
+    let (mut before, mut after) = match (before, after) {
+        (Value::Object(before), Value::Object(after)) => (before, after),
+        (Value::Object(before), Value::Null) => (before, Map::new()),
+        (Value::Null, Value::Object(after)) => (Map::new(), after),
+        (Value::Null, Value::Null) => return Map::new(),
+        (before, after) => {
+            let mut map = Map::new();
+            if before != after {
+                map.insert("value".to_owned(), change(before, after));
+            }
+            return map;
+        }
+    };
+    let mut keys = before
+        .keys()
+        .chain(after.keys())
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| !IGNORED_FIELDS.contains(&key.as_str()))
+        .filter_map(|key| {
+            let b = before.remove(&key).unwrap_or(Value::Null);
+            let a = after.remove(&key).unwrap_or(Value::Null);
+            (b != a).then(|| (key, change(b, a)))
+        })
+        .collect()
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct AuditLogResponse {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub actor_nim: Option<String>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub changes: Option<Value>,
+    pub ip: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AuditLogResponse {
+    pub fn new(log: AuditLog, actor_nim: Option<String>, actor_name: Option<String>) -> Self {
+        Self {
+            id: log.id,
+            actor_id: log.actor_id,
+            actor_nim,
+            actor_name,
+            action: log.action,
+            target_type: log.target_type,
+            target_id: log.target_id,
+            changes: log.changes.and_then(|x| serde_json::from_str(&x).ok()),
+            ip: log.ip,
+            created_at: log.created_at,
+        }
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct AuditLogFilter {
+    pub actor_id: Option<i64>,
+    /// prefix of the action, ex: `user` match `user.update` and `user.delete`
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// 1-based page of the result, ordered by the newest first
+    pub page: Option<i64>,
+    pub max: Option<i64>,
+}
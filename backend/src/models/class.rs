@@ -0,0 +1,82 @@
+use crate::schema::{class_members, classes};
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::serde;
+
+/// practicum class of a course in a semester, taught by the `lecturer_id` user
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(
+    Clone,
+    diesel::Queryable,
+    diesel::Identifiable,
+    diesel::Selectable,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(crate = "serde")]
+#[diesel(table_name = classes)]
+pub struct Class {
+    pub id: i64,
+    pub name: String,
+    pub course: String,
+    /// ex: `2023/2024 Ganjil`
+    pub semester: String,
+    pub lecturer_id: i64,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize, diesel::Insertable)]
+#[serde(crate = "serde")]
+#[diesel(table_name = classes)]
+pub struct NewClass {
+    pub name: String,
+    pub course: String,
+    pub semester: String,
+    pub lecturer_id: i64,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize, diesel::AsChangeset)]
+#[serde(crate = "serde")]
+#[diesel(table_name = classes)]
+pub struct UpdateClass {
+    pub name: Option<String>,
+    pub course: Option<String>,
+    pub semester: Option<String>,
+    pub lecturer_id: Option<i64>,
+    #[serde(skip)]
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = class_members)]
+pub struct NewClassMember {
+    pub class_id: i64,
+    pub user_id: i64,
+    pub group_name: Option<String>,
+}
+
+/// enroll the users into the class, the group of enrolled user is replaced
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct EnrollMembers {
+    pub user_ids: Vec<i64>,
+    /// practicum group inside the class, ex: `A1`
+    #[serde(default)]
+    pub group_name: Option<String>,
+}
+
+/// enrolled user of the class with the name of the user
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize, diesel::Queryable)]
+#[serde(crate = "serde")]
+pub struct ClassMemberResponse {
+    pub user_id: i64,
+    pub nim: String,
+    pub name: String,
+    pub group_name: Option<String>,
+    pub joined_at: NaiveDateTime,
+}
@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use crate::schema::{dyno_annotations, dyno_comments};
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::{derive_more, serde, BufferData, DynoErr};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = dyno_comments)]
+pub struct NewComment {
+    pub dyno_id: i64,
+    pub user_id: i64,
+    pub parent_id: Option<i64>,
+    pub body: String,
+}
+
+/// body of a new comment, `parent_id` is the id of the replied comment in the same dynotest
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct CommentRequest {
+    pub body: String,
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+}
+
+/// comment of a dynotest with the name of the author, threads is built from `parent_id`
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize, diesel::Queryable)]
+#[serde(crate = "serde")]
+pub struct CommentResponse {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub user_id: i64,
+    pub parent_id: Option<i64>,
+    pub body: String,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub nim: String,
+    pub name: String,
+}
+
+/// axis of the chart where the [`Annotation`] is anchored
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    derive_more::Display,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum AnnotationAnchor {
+    Rpm,
+    /// seconds from the start of the dynotest
+    Time,
+}
+
+impl AnnotationAnchor {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rpm => "rpm",
+            Self::Time => "time",
+        }
+    }
+}
+
+impl FromStr for AnnotationAnchor {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rpm" => Ok(Self::Rpm),
+            "time" => Ok(Self::Time),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown annotation anchor `{s}`"
+            ))),
+        }
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for AnnotationAnchor
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::serialize::ToSql<diesel::sql_types::Text, B>
+    for AnnotationAnchor
+where
+    str: diesel::serialize::ToSql<diesel::sql_types::Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        self.as_str()
+            .to_sql(out)
+            .map(|_| diesel::serialize::IsNull::No)
+            .map_err(Into::into)
+    }
+}
+
+/// marker on the chart of a dynotest, ex: `knock here` at `rpm` 7500
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(
+    Clone,
+    diesel::Queryable,
+    diesel::Identifiable,
+    diesel::Selectable,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(crate = "serde")]
+#[diesel(table_name = dyno_annotations)]
+pub struct Annotation {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub user_id: i64,
+    pub anchor: AnnotationAnchor,
+    pub position: f64,
+    pub label: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize, diesel::Insertable)]
+#[serde(crate = "serde")]
+#[diesel(table_name = dyno_annotations)]
+pub struct NewAnnotation {
+    pub anchor: AnnotationAnchor,
+    pub position: f64,
+    pub label: String,
+    /// filled from the path and the session, not from the body
+    #[serde(skip)]
+    pub dyno_id: i64,
+    #[serde(skip)]
+    pub user_id: i64,
+}
+
+/// range of the axis where the [`Annotation`] is anchored, used to place the markers
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SeriesRange {
+    pub max_rpm: Option<f64>,
+    /// seconds from the start to the stop of the dynotest
+    pub duration: f64,
+}
+
+/// data of the dynotest with the discussion and the chart annotations
+#[derive(serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct DynoSeries {
+    pub data: BufferData,
+    pub range: SeriesRange,
+    pub comments: Vec<CommentResponse>,
+    pub annotations: Vec<Annotation>,
+}
@@ -30,6 +30,15 @@ pub struct Dynos {
     pub stop: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    /// readable by every logged in user, not only the owner and admin
+    pub shared: bool,
+    /// size in bytes of the stored data
+    pub data_size: i64,
+    /// moved into `archive/` by the retention job, not counted in the user quota
+    pub archived: bool,
+    /// user editable title, see [`crate::models::label::DynoLabels`]
+    pub title: Option<String>,
+    pub notes: Option<String>,
 }
 
 impl Dynos {
@@ -40,7 +49,7 @@ impl Dynos {
             user_id: self.user_id,
             info_id: self.info_id,
             uuid: self.uuid.into_inner(),
-            data_url: self.data_url,
+            data_url: format!("/api/dyno/{}/file", self.id),
             data_checksum: self.data_checksum,
             verified: self.verified.is_some_and(|x| x),
             start: self.start,
@@ -62,6 +71,8 @@ pub struct NewDynos {
     pub data_checksum: String,
     pub start: NaiveDateTime,
     pub stop: NaiveDateTime,
+    pub imported: bool,
+    pub data_size: i64,
 }
 
 impl NewDynos {
@@ -84,6 +95,21 @@ impl NewDynos {
             data_checksum,
             start,
             stop,
+            imported: false,
+            data_size: 0,
         }
     }
 }
+
+/// storage used by a user, `max_*` is the quota of the user role, `None` is unlimited
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct StorageUsage {
+    pub count: i64,
+    pub bytes: i64,
+    pub archived_count: i64,
+    pub archived_bytes: i64,
+    pub max_count: Option<i64>,
+    pub max_bytes: Option<i64>,
+}
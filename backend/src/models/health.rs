@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+use dyno_core::serde;
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Fail,
+}
+
+/// result of one readiness check, `detail` explain the failure or the measured value
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct HealthCheck {
+    pub status: HealthStatus,
+    /// time taken by the check in milliseconds
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// response of `/api/health/live` and `/api/health/ready`, `status` is `fail` when any of
+/// the `checks` is failed
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub uptime_secs: u64,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<&'static str, HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn new(uptime_secs: u64, checks: BTreeMap<&'static str, HealthCheck>) -> Self {
+        let status = match checks.values().all(|x| x.status == HealthStatus::Ok) {
+            true => HealthStatus::Ok,
+            false => HealthStatus::Fail,
+        };
+        Self {
+            status,
+            uptime_secs,
+            checks,
+        }
+    }
+}
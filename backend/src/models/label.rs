@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use crate::schema::{dyno_metadata, dyno_tags};
+use dyno_core::serde;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = dyno_tags)]
+pub struct NewDynoTag {
+    pub dyno_id: i64,
+    pub tag: String,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = dyno_metadata)]
+pub struct NewDynoMetadata {
+    pub dyno_id: i64,
+    pub key: String,
+    pub value: String,
+}
+
+/// user editable fields of a dynotest, the `metadata` is stored in the `dyno_metadata` table
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct DynoLabels {
+    #[serde(default)]
+    pub dyno_id: i64,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// ex: `stock`, `after remap`, `new exhaust`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
@@ -0,0 +1,36 @@
+use crate::schema::login_failures;
+use dyno_core::chrono::NaiveDateTime;
+use dyno_core::serde;
+
+/// audit trail of a failed login attempt
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(
+    Clone,
+    diesel::Queryable,
+    diesel::Identifiable,
+    diesel::Selectable,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(crate = "serde")]
+#[diesel(table_name = login_failures)]
+pub struct LoginFailure {
+    pub id: i64,
+    pub nim: String,
+    pub ip: String,
+    pub user_agent: Option<String>,
+    /// `unknown_nim`, `wrong_password` or `blocked`
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable, serde::Serialize)]
+#[serde(crate = "serde")]
+#[diesel(table_name = login_failures)]
+pub struct NewLoginFailure {
+    pub nim: String,
+    pub ip: String,
+    pub user_agent: Option<String>,
+    pub reason: String,
+}
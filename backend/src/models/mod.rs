@@ -3,12 +3,28 @@ use dyno_core::{
     serde, DynoConfig, UserSession,
 };
 
+pub mod api_key;
+pub mod assignment;
+pub mod audit_log;
+pub mod class;
+pub mod comment;
 pub mod dyno;
+pub mod health;
 pub mod history;
 pub mod info;
+pub mod label;
+pub mod login_failure;
+pub mod permission;
+pub mod reset;
 pub mod role;
+pub mod search;
+pub mod share;
+pub mod status;
+pub mod two_factor;
+pub mod upload;
 pub mod user;
 pub mod uuid;
+pub mod verification;
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(crate = "serde")]
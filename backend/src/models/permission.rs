@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use crate::schema::role_permissions;
+use dyno_core::{role::Roles, serde, DynoErr};
+
+/// role of the account that decide the [`Permission`], [`Roles`] in the token is only kept
+/// in sync to know the admin, `Admin` has every permission
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum AccessRole {
+    #[default]
+    Student,
+    /// lecturer and assistant of the class
+    Lecturer,
+    /// operator of the dyno station
+    Technician,
+    Admin,
+}
+
+impl AccessRole {
+    pub const ALL: [Self; 4] = [Self::Student, Self::Lecturer, Self::Technician, Self::Admin];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Student => "student",
+            Self::Lecturer => "lecturer",
+            Self::Technician => "technician",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// [`Roles`] that is saved in the token of the account with this role
+    #[inline]
+    pub const fn roles(&self) -> Roles {
+        match self {
+            Self::Admin => Roles::Admin,
+            _ => Roles::User,
+        }
+    }
+}
+
+impl From<Roles> for AccessRole {
+    fn from(value: Roles) -> Self {
+        match value {
+            Roles::Admin => Self::Admin,
+            _ => Self::Student,
+        }
+    }
+}
+
+impl FromStr for AccessRole {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "student" => Ok(Self::Student),
+            "lecturer" => Ok(Self::Lecturer),
+            "technician" => Ok(Self::Technician),
+            "admin" => Ok(Self::Admin),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown access role `{s}`"
+            ))),
+        }
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for AccessRole
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::serialize::ToSql<diesel::sql_types::Text, B>
+    for AccessRole
+where
+    str: diesel::serialize::ToSql<diesel::sql_types::Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        self.as_str()
+            .to_sql(out)
+            .map(|_| diesel::serialize::IsNull::No)
+            .map_err(Into::into)
+    }
+}
+
+/// action that is granted to an [`AccessRole`] in `role_permissions`
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(crate = "serde")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum Permission {
+    /// mark dynotest of other user as verified
+    #[serde(rename = "dyno.verify")]
+    DynoVerify,
+    /// read dynotest, history and info of every user
+    #[serde(rename = "dyno.read_all")]
+    DynoReadAll,
+    /// manage account, approval, role and permission
+    #[serde(rename = "user.manage")]
+    UserManage,
+    /// manage the dyno station, api keys and storage audit
+    #[serde(rename = "station.operate")]
+    StationOperate,
+}
+
+impl Permission {
+    pub const ALL: [Self; 4] = [
+        Self::DynoVerify,
+        Self::DynoReadAll,
+        Self::UserManage,
+        Self::StationOperate,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::DynoVerify => "dyno.verify",
+            Self::DynoReadAll => "dyno.read_all",
+            Self::UserManage => "user.manage",
+            Self::StationOperate => "station.operate",
+        }
+    }
+}
+
+impl FromStr for Permission {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dyno.verify" => Ok(Self::DynoVerify),
+            "dyno.read_all" => Ok(Self::DynoReadAll),
+            "user.manage" => Ok(Self::UserManage),
+            "station.operate" => Ok(Self::StationOperate),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown permission `{s}`"
+            ))),
+        }
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for Permission
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::serialize::ToSql<diesel::sql_types::Text, B>
+    for Permission
+where
+    str: diesel::serialize::ToSql<diesel::sql_types::Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        self.as_str()
+            .to_sql(out)
+            .map(|_| diesel::serialize::IsNull::No)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, diesel::Queryable, diesel::Selectable, diesel::Insertable)]
+#[diesel(table_name = role_permissions)]
+pub struct RolePermission {
+    pub role: AccessRole,
+    pub permission: Permission,
+}
+
+/// permissions of a role, used to list and replace the mapping
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RolePermissions {
+    pub role: AccessRole,
+    pub permissions: Vec<Permission>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct UpdateAccessRole {
+    pub role: AccessRole,
+}
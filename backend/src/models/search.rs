@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use dyno_core::{derive_more, serde, DynoErr};
+
+/// kind of the document in the `search_index` table
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    derive_more::Display,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum SearchKind {
+    /// name, nim and email of the user
+    User,
+    /// title, notes, tags and metadata of the dynotest
+    Dyno,
+    /// name and engine of the vehicle in `dyno_info`
+    Info,
+}
+
+impl SearchKind {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Dyno => "dyno",
+            Self::Info => "info",
+        }
+    }
+}
+
+impl FromStr for SearchKind {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Self::User),
+            "dyno" => Ok(Self::Dyno),
+            "info" => Ok(Self::Info),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown search kind `{s}`"
+            ))),
+        }
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for SearchKind
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::serialize::ToSql<diesel::sql_types::Text, B>
+    for SearchKind
+where
+    str: diesel::serialize::ToSql<diesel::sql_types::Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        self.as_str()
+            .to_sql(out)
+            .map(|_| diesel::serialize::IsNull::No)
+            .map_err(Into::into)
+    }
+}
+
+/// one ranked document of the `search_index`, `score` is higher for the better match
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::QueryableByName, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SearchResult {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub kind: SearchKind,
+    /// id of the user, dynotest or dyno_info depend on the `kind`
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub id: i64,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub title: String,
+    /// matched part of the document, the matched terms are wrapped in `[` `]`
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub snippet: String,
+    #[diesel(sql_type = diesel::sql_types::Double)]
+    pub score: f64,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct SearchQueries {
+    pub q: String,
+    pub max: Option<i64>,
+}
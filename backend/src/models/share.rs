@@ -0,0 +1,70 @@
+use crate::schema::share_links;
+use dyno_core::chrono::{NaiveDateTime, Utc};
+use dyno_core::serde;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Identifiable, diesel::Selectable)]
+#[diesel(table_name = share_links)]
+pub struct ShareLink {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub user_id: i64,
+    /// sha256 hex of the token, the token itself is only known by the owner
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ShareLink {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now().naive_utc()
+    }
+
+    #[inline]
+    pub fn into_response(self) -> ShareLinkResponse {
+        ShareLinkResponse {
+            active: self.is_valid(),
+            id: self.id,
+            dyno_id: self.dyno_id,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ShareLinkResponse {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub active: bool,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// returned only once when the link is created, the `token` can not be retrieved again
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ShareLinkCreated {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub token: String,
+    pub url: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = share_links)]
+pub struct NewShareLink {
+    pub dyno_id: i64,
+    pub user_id: i64,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
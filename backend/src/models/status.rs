@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use dyno_core::{derive_more, serde, DynoErr};
+
+/// approval status of an account, only [`UserStatus::Active`] can login
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    derive_more::Display,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(crate = "serde", rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum UserStatus {
+    /// self registered account that is waiting for admin approval
+    Pending,
+    #[default]
+    Active,
+    Rejected,
+}
+
+impl UserStatus {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl FromStr for UserStatus {
+    type Err = DynoErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "active" => Ok(Self::Active),
+            "rejected" => Ok(Self::Rejected),
+            _ => Err(DynoErr::bad_request_error(format!(
+                "Unknown user status `{s}`"
+            ))),
+        }
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, B>
+    for UserStatus
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let from_sql = <String>::from_sql(bytes)?;
+        Self::from_str(&from_sql).map_err(|err| err.to_string().into())
+    }
+}
+
+impl<B: diesel::backend::Backend> diesel::serialize::ToSql<diesel::sql_types::Text, B>
+    for UserStatus
+where
+    str: diesel::serialize::ToSql<diesel::sql_types::Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        self.as_str()
+            .to_sql(out)
+            .map(|_| diesel::serialize::IsNull::No)
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::schema::{login_challenges, recovery_codes};
+use dyno_core::chrono::{NaiveDateTime, Utc};
+use dyno_core::serde;
+
+/// second login step of the user that has TOTP enabled or required
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Identifiable, diesel::Selectable)]
+#[diesel(table_name = login_challenges)]
+pub struct LoginChallenge {
+    pub id: i64,
+    pub user_id: i64,
+    /// sha256 hex of the challenge, the challenge itself is only sent in the login response
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl LoginChallenge {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.used_at.is_none() && self.expires_at > Utc::now().naive_utc()
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = login_challenges)]
+pub struct NewLoginChallenge {
+    pub user_id: i64,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = recovery_codes)]
+pub struct NewRecoveryCode {
+    pub user_id: i64,
+    /// sha256 hex of the normalized code, the code is only shown once to the user
+    pub code_hash: String,
+}
+
+/// response of the first login step when TOTP code is needed
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorChallenge {
+    pub challenge: String,
+    /// TOTP is required but not enrolled yet, enroll it with the challenge before login
+    pub enroll: bool,
+    pub expires_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorLogin {
+    pub challenge: String,
+    /// TOTP code or one of the recovery codes
+    pub code: String,
+}
+
+/// `challenge` is used instead of the session token when enrolling from the second login step
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorCode {
+    #[serde(default)]
+    pub code: String,
+    pub challenge: Option<String>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorSetup {
+    /// base32 secret for manual entry in the authenticator app
+    pub secret: String,
+    /// `otpauth://totp/...` provisioning uri
+    pub uri: String,
+    /// base64 png of the provisioning uri qr code
+    pub qr: Option<String>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_left: i64,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RecoveryCodes {
+    pub codes: Vec<String>,
+}
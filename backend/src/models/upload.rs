@@ -0,0 +1,93 @@
+use crate::schema::upload_sessions;
+use dyno_core::chrono::{NaiveDateTime, Utc};
+use dyno_core::serde;
+
+use super::uuid::UUID;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Identifiable, diesel::Selectable)]
+#[diesel(table_name = upload_sessions)]
+pub struct UploadSession {
+    pub id: i64,
+    pub uuid: UUID,
+    pub user_id: i64,
+    /// compressed `DynoTestDataInfo` sended when creating the session
+    pub info: Vec<u8>,
+    pub size: i64,
+    pub received: i64,
+    pub dyno_id: Option<i64>,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl UploadSession {
+    #[inline]
+    pub fn into_response(self) -> UploadProgress {
+        UploadProgress {
+            uuid: self.uuid.to_string(),
+            size: self.size,
+            offset: self.received,
+            finished: self.dyno_id.is_some(),
+            dyno_id: self.dyno_id,
+        }
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct UploadProgress {
+    pub uuid: String,
+    pub size: i64,
+    /// next offset that the client should `PUT`
+    pub offset: i64,
+    pub finished: bool,
+    pub dyno_id: Option<i64>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = upload_sessions)]
+pub struct NewUploadSession {
+    pub uuid: UUID,
+    pub user_id: i64,
+    pub info: Vec<u8>,
+    pub size: i64,
+}
+
+impl NewUploadSession {
+    pub fn new(user_id: i64, info: Vec<u8>, size: i64) -> Self {
+        Self {
+            uuid: UUID::new(),
+            user_id,
+            info,
+            size,
+        }
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default, diesel::AsChangeset)]
+#[diesel(table_name = upload_sessions)]
+pub struct UpdateUploadSession {
+    pub received: Option<i64>,
+    pub dyno_id: Option<i64>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl UpdateUploadSession {
+    pub fn received(received: i64) -> Self {
+        Self {
+            received: Some(received),
+            updated_at: Some(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    }
+    pub fn finished(dyno_id: i64) -> Self {
+        Self {
+            dyno_id: Some(dyno_id),
+            updated_at: Some(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    }
+}
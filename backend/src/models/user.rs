@@ -1,9 +1,9 @@
 use crate::schema::users;
 use dyno_core::chrono::{NaiveDateTime, Utc};
 use dyno_core::users::{UserRegistration, UserResponse, UserUpdate};
-use dyno_core::{serde, DynoResult};
+use dyno_core::{role::Roles, serde, DynoResult};
 
-use super::{role::ROLES, uuid::UUID};
+use super::{permission::AccessRole, role::ROLES, status::UserStatus, uuid::UUID};
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(
@@ -27,9 +27,30 @@ pub struct User {
     pub photo: Option<String>,
     pub updated_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    /// `None` until the user open the verification link sent into `email`
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub status: UserStatus,
+    /// role that is requested by the user and waiting for admin approval
+    pub requested_role: Option<ROLES>,
+    /// base32 TOTP secret, saved when the enrollment is started
+    pub totp_secret: Option<String>,
+    /// `None` until the enrollment is confirmed with a valid code
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    /// role that decide the permissions, see [`crate::models::permission`]
+    pub access_role: AccessRole,
 }
 
 impl User {
+    #[inline]
+    pub fn is_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    #[inline]
+    pub fn is_two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some() && self.totp_secret.is_some()
+    }
+
     pub fn into_user_response(self) -> UserResponse {
         UserResponse {
             id: self.id as _,
@@ -43,6 +64,36 @@ impl User {
             created_at: self.created_at,
         }
     }
+
+    pub fn into_approval_response(self) -> ApprovalResponse {
+        ApprovalResponse {
+            email_verified: self.is_verified(),
+            id: self.id,
+            nim: self.nim,
+            name: self.name,
+            email: self.email,
+            role: self.role.into_inner(),
+            requested_role: self.requested_role.map(ROLES::into_inner),
+            status: self.status,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// account in the admin approval queue, pending registration or role request
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ApprovalResponse {
+    pub id: i64,
+    pub nim: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub role: Roles,
+    pub requested_role: Option<Roles>,
+    pub status: UserStatus,
+    pub created_at: NaiveDateTime,
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -58,9 +109,32 @@ pub struct NewUser {
     pub role: ROLES,
     pub email: Option<String>,
     pub photo: Option<String>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub status: UserStatus,
+    pub requested_role: Option<ROLES>,
+    pub access_role: AccessRole,
 }
 
 impl NewUser {
+    /// self registered user, always created as pending [`Roles::User`] and other role
+    /// is only kept as request that need to be granted by admin
+    #[inline]
+    pub fn pending(mut self) -> Self {
+        let ROLES(role) = self.role;
+        self.requested_role = (role != Roles::User).then_some(self.role);
+        self.role = ROLES(Roles::User);
+        self.access_role = AccessRole::Student;
+        self.status = UserStatus::Pending;
+        self
+    }
+
+    /// user that need to verify the email before login, ex: self registration
+    #[inline]
+    pub fn unverified(mut self) -> Self {
+        self.email_verified_at = None;
+        self
+    }
+
     pub fn from_registration(
         UserRegistration {
             nim,
@@ -78,6 +152,10 @@ impl NewUser {
             role: ROLES(role),
             email: Some(email),
             photo: None,
+            email_verified_at: Some(Utc::now().naive_utc()),
+            status: UserStatus::Active,
+            requested_role: None,
+            access_role: AccessRole::from(role),
         })
     }
 }
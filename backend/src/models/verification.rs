@@ -0,0 +1,46 @@
+use crate::schema::email_verifications;
+use dyno_core::chrono::{NaiveDateTime, Utc};
+use dyno_core::serde;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Queryable, diesel::Identifiable, diesel::Selectable)]
+#[diesel(table_name = email_verifications)]
+pub struct EmailVerification {
+    pub id: i64,
+    pub user_id: i64,
+    /// sha256 hex of the token, the token itself is only sent in the email
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl EmailVerification {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.used_at.is_none() && self.expires_at > Utc::now().naive_utc()
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, diesel::Insertable)]
+#[diesel(table_name = email_verifications)]
+pub struct NewEmailVerification {
+    pub user_id: i64,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct VerifyEmail {
+    pub token: String,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ResendVerification {
+    pub email: String,
+}
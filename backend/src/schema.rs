@@ -1,5 +1,95 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> BigInt,
+        name -> Text,
+        station -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Text,
+        created_by -> BigInt,
+        last_used_at -> Nullable<Timestamp>,
+        expires_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    assignments (id) {
+        id -> BigInt,
+        class_id -> BigInt,
+        title -> Text,
+        description -> Text,
+        engine_cc -> Nullable<SmallInt>,
+        engine_stroke -> Nullable<SmallInt>,
+        min_runs -> Integer,
+        deadline -> Timestamp,
+        created_by -> BigInt,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> BigInt,
+        actor_id -> Nullable<BigInt>,
+        action -> Text,
+        target_type -> Nullable<Text>,
+        target_id -> Nullable<BigInt>,
+        changes -> Nullable<Text>,
+        ip -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    class_members (class_id, user_id) {
+        class_id -> BigInt,
+        user_id -> BigInt,
+        group_name -> Nullable<Text>,
+        joined_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    classes (id) {
+        id -> BigInt,
+        name -> Text,
+        course -> Text,
+        semester -> Text,
+        lecturer_id -> BigInt,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    dyno_annotations (id) {
+        id -> BigInt,
+        dyno_id -> BigInt,
+        user_id -> BigInt,
+        anchor -> Text,
+        position -> Double,
+        label -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    dyno_comments (id) {
+        id -> BigInt,
+        dyno_id -> BigInt,
+        user_id -> BigInt,
+        parent_id -> Nullable<BigInt>,
+        body -> Text,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     dyno_info (id) {
         id -> BigInt,
@@ -21,6 +111,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    dyno_metadata (dyno_id, key) {
+        dyno_id -> BigInt,
+        key -> Text,
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    dyno_tags (dyno_id, tag) {
+        dyno_id -> BigInt,
+        tag -> Text,
+    }
+}
+
 diesel::table! {
     dynos (id) {
         id -> BigInt,
@@ -34,6 +139,23 @@ diesel::table! {
         stop -> Timestamp,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        imported -> Bool,
+        shared -> Bool,
+        data_size -> BigInt,
+        archived -> Bool,
+        title -> Nullable<Text>,
+        notes -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    email_verifications (id) {
+        id -> BigInt,
+        user_id -> BigInt,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
     }
 }
 
@@ -45,6 +167,112 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    login_challenges (id) {
+        id -> BigInt,
+        user_id -> BigInt,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    login_failures (id) {
+        id -> BigInt,
+        nim -> Text,
+        ip -> Text,
+        user_agent -> Nullable<Text>,
+        reason -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    password_resets (id) {
+        id -> BigInt,
+        user_id -> BigInt,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    recovery_codes (id) {
+        id -> BigInt,
+        user_id -> BigInt,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    role_permissions (role, permission) {
+        role -> Text,
+        permission -> Text,
+    }
+}
+
+diesel::table! {
+    search_documents (id) {
+        id -> BigInt,
+        kind -> Text,
+        ref_id -> BigInt,
+    }
+}
+
+diesel::table! {
+    share_links (id) {
+        id -> BigInt,
+        dyno_id -> BigInt,
+        user_id -> BigInt,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    submission_dynos (submission_id, dyno_id) {
+        submission_id -> BigInt,
+        dyno_id -> BigInt,
+    }
+}
+
+diesel::table! {
+    submissions (id) {
+        id -> BigInt,
+        assignment_id -> BigInt,
+        user_id -> BigInt,
+        analysis -> Text,
+        grade -> Nullable<Float>,
+        comment -> Nullable<Text>,
+        graded_by -> Nullable<BigInt>,
+        graded_at -> Nullable<Timestamp>,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    upload_sessions (id) {
+        id -> BigInt,
+        uuid -> Text,
+        user_id -> BigInt,
+        info -> Binary,
+        size -> BigInt,
+        received -> BigInt,
+        dyno_id -> Nullable<BigInt>,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> BigInt,
@@ -57,12 +285,53 @@ diesel::table! {
         photo -> Nullable<Text>,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        email_verified_at -> Nullable<Timestamp>,
+        status -> Text,
+        requested_role -> Nullable<Text>,
+        totp_secret -> Nullable<Text>,
+        totp_enabled_at -> Nullable<Timestamp>,
+        access_role -> Text,
+        totp_last_step -> Nullable<BigInt>,
     }
 }
 
+diesel::joinable!(assignments -> classes (class_id));
+diesel::joinable!(audit_log -> users (actor_id));
+diesel::joinable!(class_members -> classes (class_id));
+diesel::joinable!(class_members -> users (user_id));
+diesel::joinable!(dyno_annotations -> users (user_id));
+diesel::joinable!(dyno_comments -> users (user_id));
+diesel::joinable!(dyno_metadata -> dynos (dyno_id));
+diesel::joinable!(dyno_tags -> dynos (dyno_id));
+diesel::joinable!(dynos -> dyno_info (info_id));
+diesel::joinable!(submission_dynos -> dynos (dyno_id));
+diesel::joinable!(submission_dynos -> submissions (submission_id));
+diesel::joinable!(submissions -> assignments (assignment_id));
+diesel::joinable!(submissions -> users (user_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    assignments,
+    audit_log,
+    class_members,
+    classes,
+    dyno_annotations,
+    dyno_comments,
     dyno_info,
+    dyno_metadata,
+    dyno_tags,
     dynos,
+    email_verifications,
     histories,
+    login_challenges,
+    login_failures,
+    password_resets,
+    recovery_codes,
+    role_permissions,
+    search_documents,
+    share_links,
+    submission_dynos,
+    submissions,
+    upload_sessions,
     users,
 );
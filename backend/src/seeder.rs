@@ -1,8 +1,8 @@
 use crate::{
     actions,
-    models::{role, user, uuid},
+    models::{permission::AccessRole, role, status::UserStatus, user, uuid},
 };
-use dyno_core::{log, DynoResult};
+use dyno_core::{chrono::Utc, log, DynoResult};
 
 fn seed_user(conn: &mut crate::DynoDBPooledConnection) -> DynoResult<()> {
     let password = dyno_core::crypto::hash_password("password123")?;
@@ -14,6 +14,10 @@ fn seed_user(conn: &mut crate::DynoDBPooledConnection) -> DynoResult<()> {
         role: role::ROLES(dyno_core::role::Roles::Admin),
         email: Some("e32201406@student.polije.ac.id".to_owned()),
         photo: None,
+        email_verified_at: Some(Utc::now().naive_utc()),
+        status: UserStatus::Active,
+        requested_role: None,
+        access_role: AccessRole::Admin,
     };
     if !matches!(actions::user::is_exists_by_id(conn, 1), Ok(true)) {
         log::debug!(
@@ -33,6 +37,10 @@ fn seed_user(conn: &mut crate::DynoDBPooledConnection) -> DynoResult<()> {
         role: role::ROLES(dyno_core::role::Roles::User),
         email: Some("ujicoba@email.com".to_owned()),
         photo: None,
+        email_verified_at: Some(Utc::now().naive_utc()),
+        status: UserStatus::Active,
+        requested_role: None,
+        access_role: AccessRole::Student,
     };
 
     if !matches!(actions::user::is_exists_by_nim(conn, "ujicoba"), Ok(true)) {
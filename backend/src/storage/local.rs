@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use dyno_core::{
+    chrono::{DateTime, Utc},
+    DynoErr, DynoResult,
+};
+
+use super::{validate_key, Storage, StoredObject};
+
+/// store dyno data as files inside a folder on the local filesystem
+#[derive(Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> DynoResult<PathBuf> {
+        validate_key(key).map(|key| self.root.join(key))
+    }
+
+    /// walk `dir` recursively, hidden entries (ex: `.tmp` of uploads) is skipped
+    fn walk(&self, dir: &Path, out: &mut Vec<StoredObject>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                self.walk(&path, out)?;
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            out.push(StoredObject {
+                key: relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .map(|x| DateTime::<Utc>::from(x).naive_utc()),
+            });
+        }
+        Ok(())
+    }
+
+    fn create_parent(path: &Path) -> DynoResult<()> {
+        match path.parent() {
+            Some(parent) => std::fs::create_dir_all(parent).map_err(DynoErr::internal_server_error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn put_bytes(&self, key: &str, bytes: &[u8]) -> DynoResult<()> {
+        let path = self.path(key)?;
+        Self::create_parent(&path)?;
+        std::fs::write(path, bytes).map_err(DynoErr::internal_server_error)
+    }
+
+    fn put_file(&self, key: &str, from: &Path) -> DynoResult<()> {
+        let path = self.path(key)?;
+        Self::create_parent(&path)?;
+        if std::fs::rename(from, &path).is_ok() {
+            return Ok(());
+        }
+        // `from` is in another filesystem, fallback into copy
+        std::fs::copy(from, &path).map_err(DynoErr::internal_server_error)?;
+        std::fs::remove_file(from).map_err(DynoErr::internal_server_error)
+    }
+
+    fn get(&self, key: &str) -> DynoResult<Vec<u8>> {
+        let path = self.path(key)?;
+        std::fs::read(path).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                DynoErr::not_found_error(format!("Dyno data `{key}` is not exists"))
+            }
+            _ => DynoErr::internal_server_error(err),
+        })
+    }
+
+    fn delete(&self, key: &str) -> DynoResult<()> {
+        let path = self.path(key)?;
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(DynoErr::internal_server_error(err))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn rename(&self, key: &str, to: &str) -> DynoResult<()> {
+        let (from, path) = (self.path(key)?, self.path(to)?);
+        Self::create_parent(&path)?;
+        std::fs::rename(from, path).map_err(DynoErr::internal_server_error)
+    }
+
+    fn list(&self) -> DynoResult<Vec<StoredObject>> {
+        let mut out = vec![];
+        if self.root.exists() {
+            self.walk(&self.root, &mut out)
+                .map_err(DynoErr::internal_server_error)?;
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,98 @@
+mod local;
+#[cfg(feature = "storage_s3")]
+mod s3;
+
+use std::{path::Path, sync::Arc};
+
+use dyno_core::{chrono::NaiveDateTime, DynoErr, DynoResult};
+
+use crate::config;
+
+#[cfg(feature = "storage_s3")]
+pub use self::s3::S3Storage;
+pub use local::LocalStorage;
+
+/// where the compressed dyno data files is kept, every file is addressed by a relative storage `key`
+/// (the `data_url` column of `dynos`) ex: `{user_uuid}/{id}-{uuid}.dyno`.
+pub trait Storage: std::fmt::Debug + Send + Sync {
+    /// human readable name of the backend, used in logs
+    fn name(&self) -> &'static str;
+
+    fn put_bytes(&self, key: &str, bytes: &[u8]) -> DynoResult<()>;
+
+    /// move complete file on local disk into the storage, `from` may not exists anymore after success.
+    fn put_file(&self, key: &str, from: &Path) -> DynoResult<()>;
+
+    fn get(&self, key: &str) -> DynoResult<Vec<u8>>;
+
+    fn delete(&self, key: &str) -> DynoResult<()>;
+
+    /// move stored object from `key` into `to`
+    fn rename(&self, key: &str, to: &str) -> DynoResult<()> {
+        let bytes = self.get(key)?;
+        self.put_bytes(to, &bytes)?;
+        self.delete(key)
+    }
+
+    /// every stored object, temporary files of the storage itself is not included
+    fn list(&self) -> DynoResult<Vec<StoredObject>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+    pub modified: Option<NaiveDateTime>,
+}
+
+pub type DynoStorage = Arc<dyn Storage>;
+
+/// create the [`Storage`] configured by [`config::Storage`]
+pub fn init(cfg: &config::Storage) -> DynoResult<DynoStorage> {
+    let storage: DynoStorage = match &cfg.s3 {
+        #[cfg(feature = "storage_s3")]
+        Some(s3) => Arc::new(S3Storage::new(s3)?),
+        #[cfg(not(feature = "storage_s3"))]
+        Some(_) => {
+            return Err(DynoErr::internal_server_error(
+                "`DYNO_STORAGE=s3` require backend to be compiled with `storage_s3` feature",
+            ))
+        }
+        None => Arc::new(LocalStorage::new(&cfg.local_path)),
+    };
+    dyno_core::log::info!("✅ Using {} storage for dyno data", storage.name());
+    Ok(storage)
+}
+
+/// storage key of new dyno data file
+#[inline]
+pub fn dyno_key(
+    user_uuid: impl std::fmt::Display,
+    id: i64,
+    uuid: impl std::fmt::Display,
+) -> String {
+    format!("{user_uuid}/{id}-{uuid}.dyno")
+}
+
+/// storage key of dyno data that is moved by the retention job
+#[inline]
+pub fn archive_key(key: &str) -> String {
+    format!("archive/{key}")
+}
+
+/// reject key that could escape the storage root, ex: `../users.db` or `/etc/passwd`.
+pub(crate) fn validate_key(key: &str) -> DynoResult<&str> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && !key.contains('\\')
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if valid {
+        Ok(key)
+    } else {
+        Err(DynoErr::bad_request_error(format!(
+            "Storage key `{key}` is not valid"
+        )))
+    }
+}
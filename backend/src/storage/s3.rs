@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use ::s3::{creds::Credentials, Bucket, Region};
+use dyno_core::{
+    chrono::{DateTime, Utc},
+    DynoErr, DynoResult,
+};
+
+use super::{validate_key, Storage, StoredObject};
+use crate::config;
+
+/// store dyno data as objects in S3 compatible bucket (AWS S3, MinIO, ...)
+#[derive(Debug)]
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    pub fn new(cfg: &config::S3) -> DynoResult<Self> {
+        let region = match &cfg.endpoint {
+            Some(endpoint) => Region::Custom {
+                region: cfg.region.clone(),
+                endpoint: endpoint.clone(),
+            },
+            None => cfg.region.parse().map_err(DynoErr::internal_server_error)?,
+        };
+        let credentials = Credentials::new(
+            Some(&cfg.access_key),
+            Some(&cfg.secret_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(DynoErr::internal_server_error)?;
+        let bucket = Bucket::new(&cfg.bucket, region, credentials)
+            .map_err(DynoErr::internal_server_error)?;
+        let bucket = if cfg.path_style {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
+        Ok(Self { bucket })
+    }
+
+    fn check_status(key: &str, status: u16) -> DynoResult<()> {
+        match status {
+            200..=299 => Ok(()),
+            404 => Err(DynoErr::not_found_error(format!(
+                "Dyno data `{key}` is not exists"
+            ))),
+            code => Err(DynoErr::internal_server_error(format!(
+                "S3 request for `{key}` failed with status {code}"
+            ))),
+        }
+    }
+}
+
+impl Storage for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn put_bytes(&self, key: &str, bytes: &[u8]) -> DynoResult<()> {
+        let response = self
+            .bucket
+            .put_object(validate_key(key)?, bytes)
+            .map_err(DynoErr::internal_server_error)?;
+        Self::check_status(key, response.status_code())
+    }
+
+    fn put_file(&self, key: &str, from: &Path) -> DynoResult<()> {
+        let mut file = std::fs::File::open(from).map_err(DynoErr::internal_server_error)?;
+        let status = self
+            .bucket
+            .put_object_stream(&mut file, validate_key(key)?)
+            .map_err(DynoErr::internal_server_error)?;
+        Self::check_status(key, status)?;
+        drop(file);
+        std::fs::remove_file(from).map_err(DynoErr::internal_server_error)
+    }
+
+    fn get(&self, key: &str) -> DynoResult<Vec<u8>> {
+        let response = self
+            .bucket
+            .get_object(validate_key(key)?)
+            .map_err(DynoErr::internal_server_error)?;
+        Self::check_status(key, response.status_code())?;
+        Ok(response.bytes().to_vec())
+    }
+
+    fn delete(&self, key: &str) -> DynoResult<()> {
+        let response = self
+            .bucket
+            .delete_object(validate_key(key)?)
+            .map_err(DynoErr::internal_server_error)?;
+        match response.status_code() {
+            404 => Ok(()),
+            status => Self::check_status(key, status),
+        }
+    }
+
+    fn list(&self) -> DynoResult<Vec<StoredObject>> {
+        let results = self
+            .bucket
+            .list(String::new(), None)
+            .map_err(DynoErr::internal_server_error)?;
+        Ok(results
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .map(|object| StoredObject {
+                modified: DateTime::parse_from_rfc3339(&object.last_modified)
+                    .ok()
+                    .map(|x| x.with_timezone(&Utc).naive_utc()),
+                size: object.size,
+                key: object.key,
+            })
+            .collect())
+    }
+}
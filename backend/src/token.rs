@@ -0,0 +1,14 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// random url safe token, only its [`hash_token`] should be saved in the database
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+#[inline]
+pub fn hash_token(token: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(token.as_ref()))
+}
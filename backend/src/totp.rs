@@ -0,0 +1,90 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dyno_core::{crypto::compare_checksums, DynoErr, DynoResult};
+use rand::Rng;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::config;
+
+/// RFC 6238 defaults, supported by every common authenticator app
+const DIGITS: usize = 6;
+const STEP: u64 = 30;
+/// accepted step before and after the current one, for clock drift of the phone
+const SKEW: u8 = 1;
+const RECOVERY_CODE_LEN: usize = 10;
+const RECOVERY_CODE_CHARS: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// random 160 bit secret encoded in base32
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+fn totp(cfg: &config::TwoFactor, secret: &str, account: &str) -> DynoResult<TOTP> {
+    let secret = Secret::Encoded(secret.to_owned())
+        .to_bytes()
+        .map_err(|err| DynoErr::internal_server_error(format!("Invalid TOTP secret - {err}")))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        SKEW,
+        STEP,
+        secret,
+        Some(cfg.issuer.clone()),
+        account.to_owned(),
+    )
+    .map_err(|err| DynoErr::internal_server_error(format!("Invalid TOTP - {err}")))
+}
+
+/// `otpauth://` provisioning uri and its qr code as base64 png
+pub fn provisioning(
+    cfg: &config::TwoFactor,
+    secret: &str,
+    account: &str,
+) -> DynoResult<(String, Option<String>)> {
+    let totp = totp(cfg, secret, account)?;
+    let qr = totp
+        .get_qr_base64()
+        .map_err(|err| dyno_core::log::error!("Failed to generate TOTP qr code - {err}"))
+        .ok();
+    Ok((totp.get_url(), qr))
+}
+
+/// time step of the matched code, the current step or [`SKEW`] step around it, spaces in the code
+/// is ignored. the caller should reject the step that is already accepted, see
+/// [`crate::actions::two_factor::accept_totp_step`].
+pub fn verify(cfg: &config::TwoFactor, secret: &str, account: &str, code: &str) -> Option<i64> {
+    let code = code.split_whitespace().collect::<String>();
+    if code.len() != DIGITS || !code.bytes().all(|x| x.is_ascii_digit()) {
+        return None;
+    }
+    let totp = totp(cfg, secret, account).ok()?;
+    let current = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() / STEP;
+    let skew = u64::from(SKEW);
+    (current.saturating_sub(skew)..=current + skew)
+        .find(|step| compare_checksums(totp.generate(step * STEP).as_bytes(), code.as_bytes()))
+        .map(|step| step as i64)
+}
+
+/// recovery code in the `xxxxx-xxxxx` format, without easily confused characters
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .map(|_| {
+            let code = (0..RECOVERY_CODE_LEN)
+                .map(|_| RECOVERY_CODE_CHARS[rng.gen_range(0..RECOVERY_CODE_CHARS.len())] as char)
+                .collect::<String>();
+            let (head, tail) = code.split_at(RECOVERY_CODE_LEN / 2);
+            format!("{head}-{tail}")
+        })
+        .collect()
+}
+
+/// recovery code is compared case insensitive and without the separator
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|x| x.to_ascii_lowercase())
+        .collect::<String>();
+    crate::token::hash_token(normalized)
+}
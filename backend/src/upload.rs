@@ -0,0 +1,223 @@
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use actix_multipart::Field;
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, ResponseError};
+use dyno_core::DynoErr;
+use futures::TryStreamExt;
+use tempfile::{NamedTempFile, TempPath};
+
+use crate::{checksum::Checksum, config::Upload, metrics::Metrics};
+
+/// error returned by upload endpoints, [`UploadError::TooLarge`] is responded with `413 Payload Too Large`
+#[derive(Debug)]
+pub enum UploadError {
+    TooLarge(String),
+    Dyno(DynoErr),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UploadError::TooLarge(msg) => write!(f, "{msg}"),
+            UploadError::Dyno(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<DynoErr> for UploadError {
+    fn from(value: DynoErr) -> Self {
+        Self::Dyno(value)
+    }
+}
+
+impl ResponseError for UploadError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UploadError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            UploadError::Dyno(err) => err.status_code(),
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            UploadError::TooLarge(msg) => {
+                crate::handler::error_response(StatusCode::PAYLOAD_TOO_LARGE, msg)
+            }
+            UploadError::Dyno(err) => err.error_response(),
+        }
+    }
+}
+
+pub type UploadResult<T> = Result<T, UploadError>;
+
+/// `uuid` of resumable upload session that currently has a chunk write, finalize or cleanup running.
+#[derive(Debug, Clone, Default)]
+pub struct UploadLocks(Arc<Mutex<HashSet<String>>>);
+
+impl UploadLocks {
+    /// claim the session, `None` when it is already claimed by other request or job
+    pub fn try_lock(&self, uuid: impl Into<String>) -> Option<UploadLock> {
+        let uuid = uuid.into();
+        let mut claimed = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        claimed.insert(uuid.clone()).then(|| UploadLock {
+            uuid,
+            locks: self.clone(),
+        })
+    }
+}
+
+/// claimed upload session, it is released on drop.
+pub struct UploadLock {
+    uuid: String,
+    locks: UploadLocks,
+}
+
+impl Drop for UploadLock {
+    fn drop(&mut self) {
+        let mut claimed = self.locks.0.lock().unwrap_or_else(PoisonError::into_inner);
+        claimed.remove(&self.uuid);
+    }
+}
+
+/// keep track of received bytes in one multipart request against [`Upload`] limits.
+pub struct UploadBudget {
+    max_field_size: u64,
+    max_request_size: u64,
+    received: u64,
+    /// every consumed byte is counted in `dyno_upload_bytes_total`
+    metrics: Option<Metrics>,
+}
+
+impl UploadBudget {
+    /// reject early when the `Content-Length` of the request is already over the limit.
+    pub fn from_request(req: &HttpRequest, cfg: &Upload) -> UploadResult<Self> {
+        let content_length = req
+            .headers()
+            .get(actix_web::http::header::CONTENT_LENGTH)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.parse::<u64>().ok());
+        if content_length.is_some_and(|len| len > cfg.max_request_size) {
+            return Err(UploadError::TooLarge(format!(
+                "Request body is larger than {} bytes",
+                cfg.max_request_size
+            )));
+        }
+        Ok(Self {
+            max_field_size: cfg.max_field_size,
+            max_request_size: cfg.max_request_size,
+            received: 0,
+            metrics: req
+                .app_data::<web::Data<crate::ServerState>>()
+                .map(|data| data.metrics.clone()),
+        })
+    }
+
+    /// count chunk of raw (non multipart) request body against the request limit.
+    pub fn consume_chunk(&mut self, len: usize) -> UploadResult<()> {
+        self.consume("body", 0, len)
+    }
+
+    fn consume(&mut self, field_name: &str, field_received: u64, len: usize) -> UploadResult<()> {
+        self.received += len as u64;
+        if let Some(metrics) = &self.metrics {
+            metrics.upload_bytes(len as u64);
+        }
+        if field_received > self.max_field_size {
+            return Err(UploadError::TooLarge(format!(
+                "Multipart '{field_name}' part is larger than {} bytes",
+                self.max_field_size
+            )));
+        }
+        if self.received > self.max_request_size {
+            return Err(UploadError::TooLarge(format!(
+                "Request body is larger than {} bytes",
+                self.max_request_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn field_name(field: &Field) -> String {
+    field
+        .content_disposition()
+        .get_name()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// read small multipart field (ex: `info`, `spec`) fully into memory.
+pub async fn read_field(
+    field: &mut Field,
+    budget: &mut UploadBudget,
+) -> UploadResult<web::BytesMut> {
+    let name = field_name(field);
+    let mut bytes = web::BytesMut::new();
+    while let Some(chunk) = field
+        .try_next()
+        .await
+        .map_err(DynoErr::internal_server_error)?
+    {
+        budget.consume(&name, (bytes.len() + chunk.len()) as u64, chunk.len())?;
+        bytes.extend_from_slice(&chunk)
+    }
+    Ok(bytes)
+}
+
+/// data field that is streamed into temporary file, with its checksum computed incrementally.
+pub struct StagedFile {
+    file: NamedTempFile,
+    pub checksum: String,
+    pub size: u64,
+}
+
+impl StagedFile {
+    /// close the staged file, it is still removed on drop if it is not moved into the storage.
+    pub fn into_temp_path(self) -> TempPath {
+        self.file.into_temp_path()
+    }
+}
+
+/// stream multipart field into temporary file inside `tmp_path` without buffering it in memory.
+pub async fn stream_field_to_file(
+    field: &mut Field,
+    budget: &mut UploadBudget,
+    tmp_path: impl AsRef<Path>,
+) -> UploadResult<StagedFile> {
+    let name = field_name(field);
+    let tmp_path = tmp_path.as_ref().to_path_buf();
+    let mut file = web::block(move || {
+        std::fs::create_dir_all(&tmp_path)?;
+        NamedTempFile::new_in(tmp_path)
+    })
+    .await
+    .map_err(DynoErr::internal_server_error)?
+    .map_err(DynoErr::internal_server_error)?;
+
+    let mut checksum = Checksum::default();
+    let mut size = 0u64;
+    while let Some(chunk) = field
+        .try_next()
+        .await
+        .map_err(DynoErr::internal_server_error)?
+    {
+        size += chunk.len() as u64;
+        budget.consume(&name, size, chunk.len())?;
+        checksum.update(&chunk);
+        file = web::block(move || file.write_all(&chunk).map(|_| file))
+            .await
+            .map_err(DynoErr::internal_server_error)?
+            .map_err(DynoErr::internal_server_error)?;
+    }
+
+    Ok(StagedFile {
+        file,
+        checksum: checksum.finish(),
+        size,
+    })
+}
@@ -3,6 +3,14 @@ use yew::{function_component, html, AttrValue, Children, Html, Properties};
 
 use crate::components::cards::TitleCard;
 
+/// marker drawn on the rail under the plot, `position` is the fraction of the x axis in `0.0..=1.0`
+#[derive(Clone, PartialEq)]
+pub struct ChartMarker {
+    pub label: AttrValue,
+    pub position: f64,
+    pub title: AttrValue,
+}
+
 #[derive(Clone, Properties, PartialEq)]
 pub struct ChartProps {
     pub id: AttrValue,
@@ -11,6 +19,8 @@ pub struct ChartProps {
     #[prop_or_default]
     pub top_side_button: Option<Html>,
     #[prop_or_default]
+    pub markers: Vec<ChartMarker>,
+    #[prop_or_default]
     pub children: Children,
 }
 
@@ -31,9 +41,22 @@ pub fn chart(props: &ChartProps) -> Html {
         },
         props.plot.clone(),
     );
+    let markers = props.markers.iter().map(|marker| {
+        let left = format!("left: {:.2}%", marker.position.clamp(0.0, 1.0) * 100.0);
+        html! {
+            <div class="tooltip absolute -translate-x-1/2" style={left} data-tip={marker.title.clone()}>
+                <span class="badge badge-warning badge-sm">{marker.label.clone()}</span>
+            </div>
+        }
+    });
     html! {
         <TitleCard title={props.title.clone()} top_side_button={props.top_side_button.clone()}>
             <div id={props.id.clone()}></div>
+            if !props.markers.is_empty() {
+                <div class="relative h-8 mt-1 border-t border-base-300">
+                    {for markers}
+                </div>
+            }
             {props.children.clone()}
         </TitleCard>
     }
@@ -1,5 +1,7 @@
 use web_sys::{Event, HtmlInputElement};
-use yew::{classes, html, AttrValue, Callback, Classes, Component, Properties, TargetCast};
+use yew::{
+    classes, html, AttrValue, Callback, Classes, Component, InputEvent, Properties, TargetCast,
+};
 
 #[derive(PartialEq, Properties)]
 pub struct SearchBarProps {
@@ -8,6 +10,9 @@ pub struct SearchBarProps {
     pub class: Classes,
     #[prop_or_default]
     pub placeholder: AttrValue,
+    /// emit `update_callback` on every keystroke, not only when the input is changed
+    #[prop_or_default]
+    pub live: bool,
 
     pub update_callback: Callback<String>,
 }
@@ -27,9 +32,19 @@ impl Component for SearchBar {
             value,
             class: style_class,
             placeholder,
+            live,
             update_callback,
         } = ctx.props();
 
+        let live = *live;
+        let oninput = live.then(|| {
+            let update_callback = update_callback.clone();
+            Callback::from(move |e: InputEvent| {
+                if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                    update_callback.emit(input.value());
+                }
+            })
+        });
         let update_callback = update_callback.clone();
         html! {
             <div class={classes!("inline-block", style_class.clone())}>
@@ -38,8 +53,12 @@ impl Component for SearchBar {
                     type="search"
                     value={value.clone()}
                     placeholder={placeholder.clone()}
+                    {oninput}
                     onchange={move |e: Event| {
                         e.prevent_default();
+                        if live {
+                            return;
+                        }
                         let input = e.target_dyn_into::<HtmlInputElement>();
                         if let Some(input) = input {
                             update_callback.emit(input.value());
@@ -1,13 +1,19 @@
-use dyno_core::{DynoErr, DynoResult};
+use dyno_core::{serde, ApiResponse, DynoErr, DynoResult};
 use gloo::net::http::Request;
 use web_sys::MouseEvent;
-use yew::{classes, function_component, html, use_effect_with_deps, AttrValue, Properties};
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_effect_with_deps, use_mut_ref,
+    use_state, AttrValue, Callback, Properties,
+};
 use yew_icons::{Icon, IconId};
 use yew_router::prelude::use_navigator;
 use yewdux::prelude::use_store;
 
 use crate::{
-    components::notification::{use_notification, Notification},
+    components::{
+        input::SearchBar,
+        notification::{use_notification, Notification},
+    },
     notif_error,
     route::{LinkTag, Route},
     state::AppState,
@@ -83,7 +89,8 @@ pub fn header(
                 <h1 class="text-2xl font-semibold ml-2">{title.clone()}</h1>
             </div>
             <div class="order-last">
-                <label class="swap">
+                <HeaderSearch />
+                <label class="swap ml-4">
                     <input class="hidden" type="checkbox" onclick={onchange_theme}/>
                     <Icon icon_id={IconId::HeroiconsOutlineSun}
                         class={classes!("fill-current", "w-6", "h-6", if theme.is_dark() { "swap-on" } else {"swap-off"})}
@@ -136,3 +143,112 @@ async fn logout(token: impl AsRef<str>) -> DynoResult<()> {
         Err(err) => Err(DynoErr::api_error(err)),
     }
 }
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct SearchResult {
+    /// `user`, `dyno` or `info`
+    kind: String,
+    id: i64,
+    title: String,
+    snippet: String,
+}
+
+impl SearchResult {
+    fn route(&self) -> Route {
+        match self.kind.as_str() {
+            "user" => Route::AdminUsers,
+            _ => Route::Activities,
+        }
+    }
+}
+
+/// search bar with the live results of `/api/search` in a dropdown
+#[function_component(HeaderSearch)]
+fn header_search() -> yew::Html {
+    let (state, _) = use_store::<AppState>();
+    let query = use_state(AttrValue::default);
+    let results = use_state(Vec::<SearchResult>::new);
+    // the latest query, so the response of an older query does not override the newer one
+    let latest = use_mut_ref(String::new);
+    let navigator = use_navigator();
+
+    let on_search = {
+        let (query, results, latest) = (query.clone(), results.clone(), latest.clone());
+        let token = state
+            .token_session()
+            .map(|x| format!("Bearer {x}"))
+            .unwrap_or_default();
+        Callback::from(move |q: String| {
+            query.set(q.clone().into());
+            *latest.borrow_mut() = q.clone();
+            if q.trim().is_empty() {
+                return results.set(vec![]);
+            }
+            let (results, latest, token) = (results.clone(), latest.clone(), token.clone());
+            spawn_local(async move {
+                match search(&token, &q).await {
+                    Ok(found) if *latest.borrow() == q => results.set(found),
+                    Ok(_) => (),
+                    Err(err) => dyno_core::log::error!("{err}"),
+                }
+            })
+        })
+    };
+
+    let items = results.iter().map(|x| {
+        let onclick = {
+            let (navigator, route) = (navigator.clone(), x.route());
+            let (query, results) = (query.clone(), results.clone());
+            move |e: MouseEvent| {
+                e.prevent_default();
+                query.set(AttrValue::default());
+                results.set(vec![]);
+                if let Some(nav) = &navigator {
+                    nav.push(&route);
+                }
+            }
+        };
+        html! {
+            <li key={format!("{}-{}", x.kind, x.id)}>
+                <a {onclick} class="flex flex-col items-start">
+                    <span>
+                        <span class="badge badge-outline badge-sm mr-2">{&x.kind}</span>
+                        <span class="font-bold">{&x.title}</span>
+                    </span>
+                    <span class="text-xs opacity-70">{&x.snippet}</span>
+                </a>
+            </li>
+        }
+    });
+
+    html! {
+        <div class="dropdown dropdown-end hidden md:inline-block">
+            <SearchBar live={true} placeholder="Search" value={(*query).clone()} update_callback={on_search} />
+            if !results.is_empty() {
+                <ul class="menu menu-compact dropdown-content mt-3 p-2 shadow bg-base-100 rounded-box w-96 max-h-96 overflow-y-auto">
+                    {for items}
+                </ul>
+            }
+        </div>
+    }
+}
+
+async fn search(token: &str, q: &str) -> DynoResult<Vec<SearchResult>> {
+    let response = Request::get("/api/search")
+        .query([("q", q)])
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    if !response.ok() {
+        return Err(DynoErr::api_error(
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+    response
+        .json::<ApiResponse<Vec<SearchResult>>>()
+        .await
+        .map(|x| x.payload)
+        .map_err(DynoErr::api_error)
+}
@@ -25,9 +25,12 @@ use theme::Theme;
 use crate::{
     containers::layout::Layout,
     pages::{
-        admin::{PageAdminDynos, PageAdminHistory, PageAdminInfos, PageAdminUsers},
-        PageActivities, PageDashboard, PageNotFound, PageSettingProfile, PageSignIn, PageSignUp,
-        PageSop,
+        admin::{
+            PageAdminApiKeys, PageAdminApprovals, PageAdminAuditLog, PageAdminDynos,
+            PageAdminHistory, PageAdminInfos, PageAdminPermissions, PageAdminUsers,
+        },
+        PageActivities, PageClasses, PageDashboard, PageForgot, PageImport, PageNotFound,
+        PageReset, PageSettingProfile, PageShare, PageSignIn, PageSignUp, PageSop, PageVerify,
     },
 };
 
@@ -40,7 +43,15 @@ fn router() -> Html {
     let (state, _) = use_store::<state::AppState>();
     let route = use_route::<Route>().map(|p| match state.token_session().is_none() {
         true => {
-            if matches!(p, Route::SignIn | Route::SignUp) {
+            if matches!(
+                p,
+                Route::SignIn
+                    | Route::SignUp
+                    | Route::Share
+                    | Route::Forgot
+                    | Route::Reset
+                    | Route::Verify
+            ) {
                 p
             } else {
                 Route::SignIn
@@ -55,15 +66,25 @@ fn router() -> Html {
                 Route::NotFound => with_layout!(<PageNotFound />),
                 Route::Dashboard => with_layout!(<PageDashboard/>),
                 Route::Activities => with_layout!(<PageActivities/>),
+                Route::Import => with_layout!(<PageImport/>),
+                Route::Classes => with_layout!(<PageClasses/>),
                 Route::Sop => with_layout!(<PageSop/>),
                 Route::SignIn => html! { <PageSignIn /> },
                 Route::SignUp => html! { <PageSignUp /> },
+                Route::Share => html! { <PageShare /> },
+                Route::Forgot => html! { <PageForgot /> },
+                Route::Reset => html! { <PageReset /> },
+                Route::Verify => html! { <PageVerify /> },
                 Route::Live => with_layout!(<PageLive />),
                 Route::SettingProfile => with_layout!(<PageSettingProfile />),
                 Route::AdminDynos => with_layout!(<PageAdminDynos />),
                 Route::AdminUsers => with_layout!(<PageAdminUsers />),
                 Route::AdminInfos => with_layout!(<PageAdminInfos />),
                 Route::AdminHistory => with_layout!(<PageAdminHistory />),
+                Route::AdminApprovals => with_layout!(<PageAdminApprovals />),
+                Route::AdminApiKeys => with_layout!(<PageAdminApiKeys />),
+                Route::AdminPermissions => with_layout!(<PageAdminPermissions />),
+                Route::AdminAuditLog => with_layout!(<PageAdminAuditLog />),
             }
         }
         None => {
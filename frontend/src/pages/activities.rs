@@ -1,8 +1,19 @@
 use std::ops::Deref;
 
 use crate::{
-    components::{cards::TitleCard, chart::Chart, stats::Stats, typography::Title},
-    fetch::{fetch_and_save, fetch_data_dyno, fetch_info_byid},
+    components::{
+        cards::TitleCard,
+        chart::{Chart, ChartMarker},
+        input::SearchBar,
+        stats::Stats,
+        typography::Title,
+    },
+    fetch::{fetch_and_save, fetch_info_byid},
+    pages::{
+        comments::{fetch_series, Annotation, Comment, DynoDiscussion},
+        labels::{fetch_dyno_by_tag, fetch_labels, DynoLabels, DynoLabelsEdit},
+        ShareLinks,
+    },
     state::AppState,
 };
 use dyno_core::{
@@ -21,17 +32,71 @@ use yewdux::prelude::use_store;
 #[function_component(PageActivities)]
 pub fn activities() -> Html {
     let idx_open = use_state(|| Option::<usize>::None);
+    let labels = use_state(Vec::<DynoLabels>::new);
+    let tag = use_state(AttrValue::default);
+    let tagged = use_state(|| Option::<Vec<DynoTest>>::None);
     let (state, dispatch) = use_store::<AppState>();
     let token = format!("Bearer {}", state.token_session().unwrap());
 
+    let reload_labels = {
+        let (labels, token) = (labels.clone(), token.clone());
+        Callback::from(move |_: ()| {
+            let (labels, token) = (labels.clone(), token.clone());
+            spawn_local(async move {
+                match fetch_labels(&token).await {
+                    Ok(fetched) => labels.set(fetched),
+                    Err(err) => dyno_core::log::error!("{err}"),
+                }
+            })
+        })
+    };
+    {
+        let reload_labels = reload_labels.clone();
+        use_effect_with_deps(move |_| reload_labels.emit(()), ());
+    }
+
     let on_refresh = {
         let token = token.clone();
+        let reload_labels = reload_labels.clone();
         dispatch.reduce_mut_future_callback_with(move |s, _| {
             let token = token.clone();
+            reload_labels.emit(());
             Box::pin(async move { crate::fetch::fetch_dyno(s, token).await })
         })
     };
 
+    let on_tag = {
+        let (tag, tagged, idx_open) = (tag.clone(), tagged.clone(), idx_open.clone());
+        let token = token.clone();
+        Callback::from(move |value: String| {
+            let value = value.trim().to_lowercase();
+            idx_open.set(None);
+            tag.set(value.clone().into());
+            if value.is_empty() {
+                return tagged.set(None);
+            }
+            let (tagged, token) = (tagged.clone(), token.clone());
+            spawn_local(async move {
+                match fetch_dyno_by_tag(&token, &value).await {
+                    Ok(dynos) => tagged.set(Some(dynos)),
+                    Err(err) => dyno_core::log::error!("{err}"),
+                }
+            })
+        })
+    };
+
+    let on_labels_saved = {
+        let labels = labels.clone();
+        Callback::from(move |saved: DynoLabels| {
+            let mut updated = (*labels).clone();
+            match updated.iter_mut().find(|x| x.dyno_id == saved.dyno_id) {
+                Some(x) => *x = saved,
+                None => updated.push(saved),
+            }
+            labels.set(updated);
+        })
+    };
+
     let ondownload = {
         let token = token.clone();
         Callback::from(move |(url, tp): (String, String)| {
@@ -42,46 +107,53 @@ pub fn activities() -> Html {
     };
     let is_admin = state.me().is_some_and(|x| x.role.is_admin());
 
+    let rows = match tagged.as_ref() {
+        Some(tagged) => tagged.clone(),
+        None => state.get_data().dyno().clone(),
+    };
+    let label_of = |id: i64| labels.iter().find(|x| x.dyno_id == id).cloned();
+
     let table_body = {
-        state
-            .get_data()
-            .dyno()
-            .clone()
-            .into_iter()
-            .enumerate()
-            .map(|(k, d)| {
-                html! {
-                    <tr key={k}>
-                        <td>{d.id}</td>
-                        <td>{d.info_id}</td>
-                        <td>
-                            if d.verified {
-                                <Icon icon_id={IconId::HeroiconsOutlineCheck} />
-                            } else {
-                                <Icon icon_id={IconId::HeroiconsOutlineXMark} />
-                            }
-                        </td>
-                        <td>{(d.stop - d.start).num_minutes()}</td>
-                        <td>{Local.from_utc_datetime(&d.updated_at).format("%r %v").to_string()}</td>
-                        <td>{Local.from_utc_datetime(&d.created_at).format("%r %v").to_string()}</td>
-                        <td>
-                            <button class="btn" onclick={let cb = idx_open.clone(); move |_| {
-                                cb.set(Some(k))
-                            }}>
-                                {"Detail"}
+        rows.iter().cloned().enumerate().map(|(k, d)| {
+            let label = label_of(d.id).unwrap_or_default();
+            html! {
+                <tr key={k}>
+                    <td>{d.id}</td>
+                    <td>{label.title.unwrap_or_default()}</td>
+                    <td>
+                        {for label.tags.iter().map(|t| html! {
+                            <span class="badge badge-outline mr-1">{t}</span>
+                        })}
+                    </td>
+                    <td>{d.info_id}</td>
+                    <td>
+                        if d.verified {
+                            <Icon icon_id={IconId::HeroiconsOutlineCheck} />
+                        } else {
+                            <Icon icon_id={IconId::HeroiconsOutlineXMark} />
+                        }
+                    </td>
+                    <td>{(d.stop - d.start).num_minutes()}</td>
+                    <td>{Local.from_utc_datetime(&d.updated_at).format("%r %v").to_string()}</td>
+                    <td>{Local.from_utc_datetime(&d.created_at).format("%r %v").to_string()}</td>
+                    <td>
+                        <button class="btn" onclick={let cb = idx_open.clone(); move |_| {
+                            cb.set(Some(k))
+                        }}>
+                            {"Detail"}
+                        </button>
+                        if is_admin {
+                            <button class="btn">
+                                {"Verify"}
                             </button>
-                            if is_admin {
-                                <button class="btn"> 
-                                    {"Verify"}
-                                </button>
-                                <button class="btn"> 
-                                    {"Delete"}
-                                </button>
-                            }
-                        </td>
-                    </tr>
-                }
-            })
+                            <button class="btn">
+                                {"Delete"}
+                            </button>
+                        }
+                    </td>
+                </tr>
+            }
+        })
     };
 
     let plot_color = state.plot_color();
@@ -90,6 +162,7 @@ pub fn activities() -> Html {
     <>
         <TitleCard class="mt-2" title="Aktivitas Mahasiswa" top_side_button={html!(
             <div class="inline-block float-right">
+                <SearchBar class="mr-2" placeholder="Filter by tag" value={(*tag).clone()} update_callback={on_tag} />
                 <button class="btn px-6 btn-sm normal-case btn-primary" onclick={on_refresh}>{"Refresh"}</button>
             </div>
         )}>
@@ -98,6 +171,8 @@ pub fn activities() -> Html {
                 <thead>
                 <tr>
                     <th>{"Id"}</th>
+                    <th>{"Title"}</th>
+                    <th>{"Tags"}</th>
                     <th>{"Info Id"}</th>
                     <th>{"Verified"}</th>
                     <th>{"Duration (m)"}</th>
@@ -114,9 +189,11 @@ pub fn activities() -> Html {
 
         </TitleCard>
         if let Some(open) = *idx_open {
-            if let Some(data) = state.get_data().dyno().get(open) {
+            if let Some(data) = rows.get(open) {
                 <ModalAct
                     open={idx_open.clone()}
+                    labels={label_of(data.id)}
+                    on_labels_saved={on_labels_saved}
                     data={data.clone()}
                     token={token}
                     on_download={ondownload}
@@ -132,6 +209,10 @@ pub fn activities() -> Html {
 pub struct ModalActProps {
     pub open: UseStateHandle<Option<usize>>,
     pub data: DynoTest,
+    /// `None` when the dynotest is not owned by the user
+    #[prop_or_default]
+    pub labels: Option<DynoLabels>,
+    pub on_labels_saved: Callback<DynoLabels>,
     pub token: String,
     pub on_download: Callback<(String, String)>,
     pub plot_color: PlotColor,
@@ -141,12 +222,14 @@ pub struct ModalActProps {
 enum ModalTab {
     Info,
     Graph,
+    Share,
 }
 impl ModalTab {
     const fn to_str(self) -> &'static str {
         match self {
             ModalTab::Info => "Info",
             ModalTab::Graph => "Graph",
+            ModalTab::Share => "Share",
         }
     }
 }
@@ -243,9 +326,26 @@ pub fn modal_activitas(props: &ModalActProps) -> Html {
                     >
                         {ModalTab::Graph.to_str()}
                     </a>
+                    <a
+                        class={classes!("tab", if *tabs == ModalTab::Share { "tab-active" } else {""})}
+                        onclick={let setter = tabsetter.clone(); move |e: MouseEvent|  {
+                            e.prevent_default();
+                            setter.set(ModalTab::Share);
+                        }}
+                    >
+                        {ModalTab::Share.to_str()}
+                    </a>
                 </div>
                 if *tabs == ModalTab::Info {
                     <Title class="text-center"> {"Info: "} {name} {tp} </Title>
+                    if let Some(labels) = props.labels.clone() {
+                        <DynoLabelsEdit
+                            key={labels.dyno_id}
+                            {labels}
+                            token={props.token.clone()}
+                            on_saved={props.on_labels_saved.clone()}
+                        />
+                    }
                     if let Some(conf) = t {
                         <div class="grid lg:grid-cols-3 mt-1 md:grid-cols-1 grid-cols-3 gap-2">
                             <Stats
@@ -304,6 +404,8 @@ pub fn modal_activitas(props: &ModalActProps) -> Html {
                             </tbody>
                         </table>
                     }
+                } else if *tabs == ModalTab::Share {
+                    <ShareLinks dyno_id={props.data.id} token={props.token.clone()} />
                 } else {
                     <ChartDyno
                         id={props.data.uuid.to_string()}
@@ -311,7 +413,7 @@ pub fn modal_activitas(props: &ModalActProps) -> Html {
                         tp={tp}
                         color={props.plot_color.clone()}
                         token={props.token.clone()}
-                        url={props.data.data_url.clone()}
+                        dyno_id={props.data.id}
                     />
                 }
                 <div class="modal-action">
@@ -352,25 +454,36 @@ pub struct ChartDynoProps {
     color: PlotColor,
     #[prop_or_default]
     token: String,
-    #[prop_or_default]
-    url: String,
+    dyno_id: i64,
 }
 #[function_component(ChartDyno)]
 fn chart_dyno(props: &ChartDynoProps) -> Html {
     let title = format!("Graph: {} {}", props.name, props.tp);
     let plot = use_state(DynoPlot::new);
+    let markers = use_state(Vec::<ChartMarker>::new);
+    let comments = use_state(Vec::<Comment>::new);
+    let annotations = use_state(Vec::<Annotation>::new);
+    let refresh = use_state(|| 0u32);
     let fut_handle = yew_hooks::use_async::<_, _, ()>({
-        let plot = plot.clone();
-        let url = props.url.clone();
+        let (plot, markers) = (plot.clone(), markers.clone());
+        let (comments, annotations) = (comments.clone(), annotations.clone());
+        let dyno_id = props.dyno_id;
         let token = props.token.clone();
         let color = props.color.clone();
         async move {
-            let response_data = fetch_data_dyno(url, token).await;
-            plot.set(
-                DynoPlot::new()
-                    .set_color(color)
-                    .create_dyno_plot(&response_data),
-            );
+            match fetch_series(&token, dyno_id).await {
+                Ok(series) => {
+                    plot.set(
+                        DynoPlot::new()
+                            .set_color(color)
+                            .create_dyno_plot(&series.data),
+                    );
+                    markers.set(series.markers());
+                    comments.set(series.comments);
+                    annotations.set(series.annotations);
+                }
+                Err(err) => dyno_core::log::error!("{err}"),
+            }
             Ok(())
         }
     });
@@ -381,13 +494,23 @@ fn chart_dyno(props: &ChartDynoProps) -> Html {
                 fut_handle.run();
                 || ()
             },
-            props.color.clone(),
+            (props.color.clone(), *refresh),
         );
     }
 
     let plot = plot.deref();
+    let on_changed = Callback::from(move |_: ()| refresh.set(*refresh + 1));
 
     html! {
-        <Chart id={props.id.clone()} {title} plot={plot.clone()} />
+    <>
+        <Chart id={props.id.clone()} {title} plot={plot.clone()} markers={(*markers).clone()} />
+        <DynoDiscussion
+            dyno_id={props.dyno_id}
+            token={props.token.clone()}
+            comments={(*comments).clone()}
+            annotations={(*annotations).clone()}
+            {on_changed}
+        />
+    </>
     }
 }
@@ -0,0 +1,283 @@
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    serde, ApiResponse, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::SubmitEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        input::{TextInput, ToggleInput},
+        typography::{ErrorText, HelperText},
+    },
+    state::AppState,
+};
+
+const SCOPES: [(&str, &str); 3] = [
+    ("publish_live", "Publish Live Data"),
+    ("post_active", "Post Active"),
+    ("upload", "Upload"),
+];
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ApiKeyResponse {
+    pub id: i64,
+    pub name: String,
+    pub station: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub active: bool,
+    pub created_by: i64,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct ApiKeyCreated {
+    key: String,
+    api_key: ApiKeyResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct CreateApiKey {
+    name: String,
+    station: String,
+    scopes: Vec<String>,
+    expires_days: Option<i64>,
+}
+
+fn format_time(time: Option<NaiveDateTime>) -> String {
+    time.map(|x| Local.from_utc_datetime(&x).format("%r %v").to_string())
+        .unwrap_or("-".to_owned())
+}
+
+/// api keys of the stations, the desktop app send the key in the `X-Api-Key` header
+#[function_component(PageAdminApiKeys)]
+pub fn page_admin_api_keys() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let keys = use_state(Vec::<ApiKeyResponse>::new);
+    let name = use_state(AttrValue::default);
+    let station = use_state(AttrValue::default);
+    let expires_days = use_state(AttrValue::default);
+    let scopes = use_state(Vec::<&'static str>::new);
+    let created = use_state(Option::<String>::default);
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (keys, error) = (keys.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_api_keys(&token).await {
+                        Ok(fetched) => keys.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            *refresh,
+        );
+    }
+
+    let onsubmit = {
+        let (error, refresh, created) = (error.clone(), refresh.clone(), created.clone());
+        let data = CreateApiKey {
+            name: name.to_string(),
+            station: station.to_string(),
+            scopes: scopes.iter().map(ToString::to_string).collect(),
+            expires_days: expires_days.parse().ok(),
+        };
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh, created) = (error.clone(), refresh.clone(), created.clone());
+            let (token, data) = (token.clone(), data.clone());
+            spawn_local(async move {
+                match create_api_key(&token, data).await {
+                    Ok(ok) => {
+                        created.set(Some(ok.key));
+                        error.set(AttrValue::default());
+                        refresh.set(*refresh + 1);
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_revoke = {
+        let (error, refresh) = (error.clone(), refresh.clone());
+        Callback::from(move |id: i64| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            spawn_local(async move {
+                match revoke_api_key(&token, id).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let scope_toggles = SCOPES.iter().map(|(scope, title)| {
+        let scopes = scopes.clone();
+        let checked = scopes.contains(scope);
+        let on_toggle = Callback::from(move |_| {
+            let mut toggled = (*scopes).clone();
+            match toggled.iter().position(|x| x == scope) {
+                Some(idx) => {
+                    toggled.remove(idx);
+                }
+                None => toggled.push(scope),
+            }
+            scopes.set(toggled);
+        });
+        html! {
+            <ToggleInput title={*title} value={checked} {checked} update_callback={on_toggle} />
+        }
+    });
+
+    let table_body = keys.iter().map(|d| {
+        let id = d.id;
+        let on_revoke = on_revoke.clone();
+        html! {
+            <tr key={id}>
+                <td>{id}</td>
+                <td>{&d.name}</td>
+                <td>{&d.station}</td>
+                <td><code>{format!("{}…", d.key_prefix)}</code></td>
+                <td>{d.scopes.join(", ")}</td>
+                <td>{if d.active { "active" } else { "inactive" }}</td>
+                <td>{format_time(d.last_used_at)}</td>
+                <td>{format_time(d.expires_at)}</td>
+                <td>{format_time(Some(d.created_at))}</td>
+                <td>
+                    if d.revoked_at.is_none() {
+                        <button class="btn btn-sm btn-error" onclick={move |_| on_revoke.emit(id)}>
+                            {"Revoke"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+    <>
+        <TitleCard class="mt-2" title="New Api Key">
+            <form {onsubmit}>
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-6">
+                    <TextInput
+                        title="Name"
+                        value={name.to_string()}
+                        required={true}
+                        update_callback={let name = name.clone(); move |s| name.set(s)}
+                    />
+                    <TextInput
+                        title="Station"
+                        value={station.to_string()}
+                        required={true}
+                        update_callback={let station = station.clone(); move |s| station.set(s)}
+                    />
+                    <TextInput
+                        title="Expires in Days"
+                        types="number"
+                        placeholder="never"
+                        value={expires_days.to_string()}
+                        update_callback={let expires_days = expires_days.clone(); move |s| expires_days.set(s)}
+                    />
+                </div>
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-6 mt-2">
+                    {for scope_toggles}
+                </div>
+                if let Some(key) = created.as_ref() {
+                    <HelperText class="mt-2">{"Copy the key now, it is not shown again"}</HelperText>
+                    <code class="break-all">{key}</code>
+                }
+                <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+                <button type="submit" class="btn btn-primary mt-4">{"Create"}</button>
+            </form>
+        </TitleCard>
+        <TitleCard class="mt-2" title="Api Keys">
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Id"}</th>
+                        <th>{"Name"}</th>
+                        <th>{"Station"}</th>
+                        <th>{"Key"}</th>
+                        <th>{"Scopes"}</th>
+                        <th>{"Status"}</th>
+                        <th>{"Last Used"}</th>
+                        <th>{"Expires at"}</th>
+                        <th>{"Created at"}</th>
+                        <th></th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for table_body}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_api_keys(token: &str) -> DynoResult<Vec<ApiKeyResponse>> {
+    let response = Request::get("/api/admin/api-keys")
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn create_api_key(token: &str, data: CreateApiKey) -> DynoResult<ApiKeyCreated> {
+    let response = Request::post("/api/admin/api-keys")
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn revoke_api_key(token: &str, id: i64) -> DynoResult<ApiKeyResponse> {
+    let response = Request::delete(&format!("/api/admin/api-keys/{id}"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    role::Roles,
+    serde, ApiResponse, AsStr, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        input::{SelectBox, SelectOption},
+        typography::ErrorText,
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ApprovalResponse {
+    pub id: i64,
+    pub nim: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub role: Roles,
+    pub requested_role: Option<Roles>,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct ApproveAccount {
+    role: Option<Roles>,
+}
+
+/// queue of pending registration and role request, admin approve it with the granted role or reject it
+#[function_component(PageAdminApprovals)]
+pub fn page_admin_approvals() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let accounts = use_state(Vec::<ApprovalResponse>::new);
+    let granted = use_state(HashMap::<i64, Roles>::new);
+    let rejected = use_state(bool::default);
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (accounts, error) = (accounts.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |(_, rejected)| {
+                let status = rejected.then_some("rejected");
+                spawn_local(async move {
+                    match fetch_approvals(&token, status).await {
+                        Ok(fetched) => accounts.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            (*refresh, *rejected),
+        );
+    }
+
+    let on_decide = {
+        let (error, refresh, granted) = (error.clone(), refresh.clone(), granted.clone());
+        Callback::from(move |(id, approve): (i64, bool)| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let role = granted.get(&id).copied();
+            spawn_local(async move {
+                let decided = if approve {
+                    approve_account(&token, id, ApproveAccount { role }).await
+                } else {
+                    reject_account(&token, id).await
+                };
+                match decided {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let toggle = {
+        let rejected = rejected.clone();
+        Callback::from(move |_| rejected.set(!*rejected))
+    };
+
+    let table_body = accounts.iter().map(|d| {
+        let id = d.id;
+        // the requested role is only granted when it is selected, same default as the backend
+        let role = granted.get(&id).copied().unwrap_or(d.role);
+        let on_role = {
+            let granted = granted.clone();
+            Callback::from(move |s: String| {
+                let mut roles = (*granted).clone();
+                roles.insert(id, Roles::from(s));
+                granted.set(roles);
+            })
+        };
+        let (on_approve, on_reject) = (on_decide.clone(), on_decide.clone());
+        html! {
+            <tr key={id}>
+                <td>{id}</td>
+                <td>{&d.nim}</td>
+                <td>{&d.name}</td>
+                <td>{d.email.clone().unwrap_or_default()}</td>
+                <td>{if d.email_verified { "yes" } else { "no" }}</td>
+                <td>{&d.status}</td>
+                <td>{d.role.as_str()}</td>
+                <td>{d.requested_role.map(|x| x.as_str()).unwrap_or("-")}</td>
+                <td>{Local.from_utc_datetime(&d.created_at).format("%r %v").to_string()}</td>
+                <td>
+                    <SelectBox
+                        value={role.as_str()}
+                        placeholder={"Grant Role"}
+                        title={"Role"}
+                        container_class={"w-32"}
+                        update_callback={on_role}
+                    >
+                        <SelectOption name={"Admin"} value={"admin"} />
+                        <SelectOption name={"User"} value={"user"} />
+                        <SelectOption name={"Guest"} value={"guest"} />
+                    </SelectBox>
+                </td>
+                <td>
+                    <button class="btn btn-sm btn-primary" onclick={move |_| on_approve.emit((id, true))}>
+                        {"Approve"}
+                    </button>
+                    if !*rejected {
+                        <button class="btn btn-sm" onclick={move |_| on_reject.emit((id, false))}>
+                            {"Reject"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+    <>
+        <TitleCard class="mt-2"
+            title={if *rejected { "Rejected Accounts" } else { "Approval Queue" }}
+            top_side_button={html!(
+                <button class="btn px-6 btn-sm normal-case btn-primary" onclick={toggle}>
+                    {if *rejected { "Show Queue" } else { "Show Rejected" }}
+                </button>
+            )}
+        >
+            <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Id"}</th>
+                        <th>{"NIM"}</th>
+                        <th>{"Nama"}</th>
+                        <th>{"Email"}</th>
+                        <th>{"Verified"}</th>
+                        <th>{"Status"}</th>
+                        <th>{"Role"}</th>
+                        <th>{"Requested Role"}</th>
+                        <th>{"Created at"}</th>
+                        <th>{"Grant"}</th>
+                        <th></th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for table_body}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_approvals(token: &str, status: Option<&str>) -> DynoResult<Vec<ApprovalResponse>> {
+    let mut request = Request::get("/api/admin/approvals").header("Authorization", token);
+    if let Some(status) = status {
+        request = request.query([("status", status)]);
+    }
+    let response = request.send().await.map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn approve_account(
+    token: &str,
+    id: i64,
+    data: ApproveAccount,
+) -> DynoResult<ApprovalResponse> {
+    let response = Request::post(&format!("/api/admin/approvals/{id}/approve"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn reject_account(token: &str, id: i64) -> DynoResult<ApprovalResponse> {
+    let response = Request::post(&format!("/api/admin/approvals/{id}/reject"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -0,0 +1,288 @@
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    serde,
+    serde_json::Value,
+    ApiResponse, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{cards::TitleCard, input::TextInput, typography::ErrorText},
+    state::AppState,
+};
+
+const PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct AuditLogResponse {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub actor_nim: Option<String>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<i64>,
+    pub changes: Option<Value>,
+    pub ip: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AuditLogResponse {
+    fn actor(&self) -> String {
+        match (&self.actor_nim, &self.actor_name, self.actor_id) {
+            (Some(nim), Some(name), _) => format!("{name} ({nim})"),
+            (.., Some(id)) => format!("#{id}"),
+            _ => "-".to_owned(),
+        }
+    }
+
+    fn target(&self) -> String {
+        match (&self.target_type, self.target_id) {
+            (Some(tp), Some(id)) => format!("{tp} #{id}"),
+            _ => "-".to_owned(),
+        }
+    }
+}
+
+/// filter of `/api/admin/audit-log`, empty field is not sent
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AuditLogFilter {
+    actor_id: AttrValue,
+    action: AttrValue,
+    target_type: AttrValue,
+    target_id: AttrValue,
+    from: AttrValue,
+    to: AttrValue,
+}
+
+impl AuditLogFilter {
+    fn queries(&self, page: i64) -> Vec<(&'static str, String)> {
+        let mut queries = vec![("page", page.to_string()), ("max", PAGE_SIZE.to_string())];
+        for (key, value) in [
+            ("actor_id", &self.actor_id),
+            ("action", &self.action),
+            ("target_type", &self.target_type),
+            ("target_id", &self.target_id),
+            ("from", &self.from),
+            ("to", &self.to),
+        ] {
+            let value = value.trim();
+            if !value.is_empty() {
+                queries.push((key, value.to_owned()));
+            }
+        }
+        queries
+    }
+}
+
+/// `field: before -> after` of every changed field, redacted field is shown as is
+fn changes_lines(changes: &Value) -> Vec<String> {
+    let Value::Object(map) = changes else {
+        return vec![changes.to_string()];
+    };
+    map.iter()
+        .map(
+            |(field, change)| match (change.get("before"), change.get("after")) {
+                (Some(Value::Null), Some(after)) => format!("{field}: {after}"),
+                (Some(before), Some(Value::Null)) => format!("{field}: {before} -> (none)"),
+                (Some(before), Some(after)) => format!("{field}: {before} -> {after}"),
+                _ => format!("{field}: {change}"),
+            },
+        )
+        .collect()
+}
+
+/// append-only log of administrative and data-changing action, filtered by actor, action,
+/// target and date
+#[function_component(PageAdminAuditLog)]
+pub fn page_admin_audit_log() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let logs = use_state(Vec::<AuditLogResponse>::new);
+    let editing = use_state(AuditLogFilter::default);
+    let filter = use_state(AuditLogFilter::default);
+    let page = use_state(|| 1i64);
+    let error = use_state(AttrValue::default);
+
+    {
+        let (logs, error) = (logs.clone(), error.clone());
+        use_effect_with_deps(
+            move |(filter, page)| {
+                let queries = filter.queries(*page);
+                spawn_local(async move {
+                    match fetch_audit_log(&token, queries).await {
+                        Ok(fetched) => {
+                            error.set(AttrValue::default());
+                            logs.set(fetched)
+                        }
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            ((*filter).clone(), *page),
+        );
+    }
+
+    let on_field = |set: fn(&mut AuditLogFilter, AttrValue)| {
+        let editing = editing.clone();
+        Callback::from(move |s: AttrValue| {
+            let mut updated = (*editing).clone();
+            set(&mut updated, s);
+            editing.set(updated);
+        })
+    };
+
+    let on_apply = {
+        let (editing, filter, page) = (editing.clone(), filter.clone(), page.clone());
+        Callback::from(move |_| {
+            page.set(1);
+            filter.set((*editing).clone());
+        })
+    };
+    let on_reset = {
+        let (editing, filter, page) = (editing.clone(), filter.clone(), page.clone());
+        Callback::from(move |_| {
+            page.set(1);
+            editing.set(AuditLogFilter::default());
+            filter.set(AuditLogFilter::default());
+        })
+    };
+    let on_prev = {
+        let page = page.clone();
+        Callback::from(move |_| page.set((*page - 1).max(1)))
+    };
+    let on_next = {
+        let page = page.clone();
+        Callback::from(move |_| page.set(*page + 1))
+    };
+
+    let table_body = logs.iter().map(|d| {
+        let changes = d.changes.as_ref().map(changes_lines).unwrap_or_default();
+        html! {
+            <tr key={d.id}>
+                <td>{d.id}</td>
+                <td>{Local.from_utc_datetime(&d.created_at).format("%r %v").to_string()}</td>
+                <td>{d.actor()}</td>
+                <td><span class="badge badge-ghost">{&d.action}</span></td>
+                <td>{d.target()}</td>
+                <td class="text-xs font-mono whitespace-pre-wrap max-w-md">
+                    {for changes.into_iter().map(|x| html!(<div>{x}</div>))}
+                </td>
+                <td>{d.ip.clone().unwrap_or_else(|| "-".to_owned())}</td>
+            </tr>
+        }
+    });
+
+    html! {
+    <>
+        <TitleCard class="mt-2" title={"Filter"}>
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+                <TextInput
+                    title="Actor Id"
+                    types="number"
+                    value={editing.actor_id.clone()}
+                    update_callback={on_field(|f, s| f.actor_id = s)}
+                />
+                <TextInput
+                    title="Action"
+                    placeholder="user.update"
+                    value={editing.action.clone()}
+                    update_callback={on_field(|f, s| f.action = s)}
+                />
+                <TextInput
+                    title="Target Type"
+                    placeholder="dyno"
+                    value={editing.target_type.clone()}
+                    update_callback={on_field(|f, s| f.target_type = s)}
+                />
+                <TextInput
+                    title="Target Id"
+                    types="number"
+                    value={editing.target_id.clone()}
+                    update_callback={on_field(|f, s| f.target_id = s)}
+                />
+                <TextInput
+                    title="From"
+                    types="date"
+                    value={editing.from.clone()}
+                    update_callback={on_field(|f, s| f.from = s)}
+                />
+                <TextInput
+                    title="To"
+                    types="date"
+                    value={editing.to.clone()}
+                    update_callback={on_field(|f, s| f.to = s)}
+                />
+            </div>
+            <div class="mt-4 flex gap-2">
+                <button class="btn btn-sm btn-primary" onclick={on_apply}>{"Apply"}</button>
+                <button class="btn btn-sm" onclick={on_reset}>{"Reset"}</button>
+            </div>
+        </TitleCard>
+        <TitleCard class="mt-2"
+            title={"Audit Log"}
+            top_side_button={html!(
+                <div class="btn-group">
+                    <button class="btn btn-sm" disabled={*page <= 1} onclick={on_prev}>{"«"}</button>
+                    <button class="btn btn-sm">{format!("Page {}", *page)}</button>
+                    <button class="btn btn-sm" disabled={(logs.len() as i64) < PAGE_SIZE} onclick={on_next}>{"»"}</button>
+                </div>
+            )}
+        >
+            <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Id"}</th>
+                        <th>{"Time"}</th>
+                        <th>{"Actor"}</th>
+                        <th>{"Action"}</th>
+                        <th>{"Target"}</th>
+                        <th>{"Changes"}</th>
+                        <th>{"IP"}</th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for table_body}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_audit_log(
+    token: &str,
+    queries: Vec<(&'static str, String)>,
+) -> DynoResult<Vec<AuditLogResponse>> {
+    let response = Request::get("/api/admin/audit-log")
+        .query(queries)
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
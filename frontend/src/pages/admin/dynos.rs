@@ -1,10 +1,10 @@
 use dyno_core::chrono::{Local, TimeZone};
 
-use yew::{function_component, html, use_state, Html};
+use yew::{function_component, html, platform::spawn_local, use_state, Callback, Html};
 use yew_icons::{Icon, IconId};
 use yewdux::prelude::use_store;
 
-use crate::{components::cards::TitleCard, state::AppState};
+use crate::{components::cards::TitleCard, fetch::fetch_and_save, state::AppState};
 
 #[function_component(PageAdminDynos)]
 pub fn page_admin_dynos() -> Html {
@@ -14,7 +14,15 @@ pub fn page_admin_dynos() -> Html {
         let token = format!("Bearer {}", s.token_session().unwrap());
         Box::pin(async move { crate::fetch::fetch_dyno(s, token).await })
     });
-    let _token = format!("Bearer {}", state.token_session().unwrap());
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let on_export = Callback::from(move |_| {
+        let token = token.clone();
+        spawn_local(async move {
+            if let Err(err) = fetch_and_save("/api/dyno/export", "bin", token).await {
+                dyno_core::log::error!("Failed to export dynotests: {err}");
+            }
+        })
+    });
     let table_body = {
         state
             .get_data()
@@ -56,6 +64,7 @@ pub fn page_admin_dynos() -> Html {
     <>
         <TitleCard class="mt-2" title="Dynotest Table Database" top_side_button={html!(
             <div class="inline-block float-right">
+                <button class="btn px-6 btn-sm normal-case" onclick={on_export}>{"Export Zip"}</button>
                 <button class="btn px-6 btn-sm normal-case btn-primary" onclick={on_refresh}>{"Refresh"}</button>
             </div>
         )}>
@@ -1,9 +1,17 @@
+mod api_keys;
+mod approvals;
+mod audit_log;
 mod dynos;
 mod history;
 mod infos;
+mod permissions;
 mod users;
 
+pub use api_keys::PageAdminApiKeys;
+pub use approvals::PageAdminApprovals;
+pub use audit_log::PageAdminAuditLog;
 pub use dynos::PageAdminDynos;
 pub use history::PageAdminHistory;
 pub use infos::PageAdminInfos;
+pub use permissions::PageAdminPermissions;
 pub use users::PageAdminUsers;
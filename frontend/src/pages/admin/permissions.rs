@@ -0,0 +1,249 @@
+use dyno_core::{serde, ApiResponse, DynoErr, DynoResult};
+use gloo::net::http::{Request, Response};
+use web_sys::SubmitEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        input::{SelectBox, SelectOption, TextInput},
+        typography::{ErrorText, HelperText},
+    },
+    state::AppState,
+};
+
+const ROLES: [(&str, &str); 4] = [
+    ("student", "Student"),
+    ("lecturer", "Lecturer / Assistant"),
+    ("technician", "Technician"),
+    ("admin", "Admin"),
+];
+
+const PERMISSIONS: [(&str, &str); 4] = [
+    ("dyno.verify", "Verify Dynotest"),
+    ("dyno.read_all", "Read All Dynotest"),
+    ("user.manage", "Manage Users"),
+    ("station.operate", "Operate Station"),
+];
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RolePermissions {
+    pub role: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct UpdateAccessRole {
+    role: String,
+}
+
+/// role → permission mapping, and the access role of the user
+#[function_component(PageAdminPermissions)]
+pub fn page_admin_permissions() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let mapping = use_state(Vec::<RolePermissions>::new);
+    let user_id = use_state(AttrValue::default);
+    let access_role = use_state(|| AttrValue::from("student"));
+    let info = use_state(AttrValue::default);
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (mapping, error) = (mapping.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_permissions(&token).await {
+                        Ok(fetched) => mapping.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            *refresh,
+        );
+    }
+
+    let on_toggle = {
+        let (error, refresh, mapping) = (error.clone(), refresh.clone(), mapping.clone());
+        let token = token.clone();
+        Callback::from(move |(role, permission): (String, &'static str)| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let mut permissions = mapping
+                .iter()
+                .find(|x| x.role == role)
+                .map(|x| x.permissions.clone())
+                .unwrap_or_default();
+            match permissions.iter().position(|x| x == permission) {
+                Some(idx) => {
+                    permissions.remove(idx);
+                }
+                None => permissions.push(permission.to_owned()),
+            }
+            spawn_local(async move {
+                match update_permissions(&token, &role, permissions).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let onsubmit = {
+        let (error, info) = (error.clone(), info.clone());
+        let user_id = user_id.parse::<i64>().ok();
+        let data = UpdateAccessRole {
+            role: access_role.to_string(),
+        };
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, info) = (error.clone(), info.clone());
+            let (token, data) = (token.clone(), data.clone());
+            let Some(user_id) = user_id else {
+                error.set("User id should be a number".into());
+                return;
+            };
+            spawn_local(async move {
+                match update_access_role(&token, user_id, data).await {
+                    Ok(_) => {
+                        error.set(AttrValue::default());
+                        info.set(format!("Access role of user {user_id} is updated").into());
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let table_body = mapping.iter().map(|d| {
+        let is_admin = d.role == "admin";
+        let name = ROLES
+            .iter()
+            .find(|(role, _)| *role == d.role)
+            .map_or(d.role.as_str(), |(_, name)| name);
+        let cells = PERMISSIONS.iter().map(|(permission, _)| {
+            let on_toggle = on_toggle.clone();
+            let role = d.role.clone();
+            let checked = d.permissions.iter().any(|x| x == permission);
+            html! {
+                <td>
+                    <input type="checkbox" class="checkbox" {checked} disabled={is_admin}
+                        onchange={move |_| on_toggle.emit((role.clone(), *permission))} />
+                </td>
+            }
+        });
+        html! {
+            <tr key={d.role.as_str()}>
+                <td>{name}</td>
+                {for cells}
+            </tr>
+        }
+    });
+
+    html! {
+    <>
+        <TitleCard class="mt-2" title="Role Permissions">
+            <HelperText>{"Admin always has every permission"}</HelperText>
+            <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Role"}</th>
+                        {for PERMISSIONS.iter().map(|(_, name)| html!(<th>{name}</th>))}
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for table_body}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+        <TitleCard class="mt-2" title="User Access Role">
+            <form {onsubmit}>
+                <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                    <TextInput
+                        title="User Id"
+                        types="number"
+                        value={user_id.to_string()}
+                        required={true}
+                        update_callback={let user_id = user_id.clone(); move |s| user_id.set(s)}
+                    />
+                    <SelectBox
+                        value={(*access_role).clone()}
+                        placeholder={"Access Role"}
+                        title={"Access Role"}
+                        update_callback={let access_role = access_role.clone(); move |s: String| access_role.set(s.into())}
+                    >
+                        {for ROLES.iter().map(|(role, name)| html!(<SelectOption name={*name} value={*role} />))}
+                    </SelectBox>
+                </div>
+                <HelperText class="mt-2">{info.as_ref()}</HelperText>
+                <button type="submit" class="btn btn-primary mt-4">{"Update"}</button>
+            </form>
+        </TitleCard>
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_permissions(token: &str) -> DynoResult<Vec<RolePermissions>> {
+    let response = Request::get("/api/admin/permissions")
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn update_permissions(
+    token: &str,
+    role: &str,
+    permissions: Vec<String>,
+) -> DynoResult<RolePermissions> {
+    let response = Request::put(&format!("/api/admin/permissions/{role}"))
+        .header("Authorization", token)
+        .json(&permissions)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn update_access_role(
+    token: &str,
+    user_id: i64,
+    data: UpdateAccessRole,
+) -> DynoResult<dyno_core::users::UserResponse> {
+    let response = Request::patch(&format!("/api/admin/users/{user_id}/access-role"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -0,0 +1,587 @@
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    serde, ApiResponse, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::SubmitEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html, Properties,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        input::{TextInput, TextInputArea},
+        typography::ErrorText,
+    },
+    fetch::fetch_and_save,
+    state::AppState,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct Assignment {
+    pub id: i64,
+    pub class_id: i64,
+    pub title: String,
+    pub description: String,
+    pub engine_cc: Option<i16>,
+    pub engine_stroke: Option<i16>,
+    pub min_runs: i32,
+    pub deadline: NaiveDateTime,
+    pub created_by: i64,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl Assignment {
+    fn requirement(&self) -> String {
+        let mut requirement = vec![format!("min {} runs", self.min_runs)];
+        if let Some(cc) = self.engine_cc {
+            requirement.push(format!("{cc}cc"));
+        }
+        if let Some(stroke) = self.engine_stroke {
+            requirement.push(format!("{stroke}-stroke"));
+        }
+        requirement.join(", ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct Submission {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub user_id: i64,
+    pub analysis: String,
+    pub grade: Option<f32>,
+    pub comment: Option<String>,
+    pub graded_by: Option<i64>,
+    pub graded_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub nim: String,
+    pub name: String,
+    pub dyno_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct NewAssignment {
+    title: String,
+    description: String,
+    engine_cc: Option<i16>,
+    engine_stroke: Option<i16>,
+    min_runs: i32,
+    deadline: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct SubmitAssignment {
+    dyno_ids: Vec<i64>,
+    analysis: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct GradeSubmission {
+    grade: f32,
+    comment: Option<String>,
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    Local.from_utc_datetime(time).format("%r %v").to_string()
+}
+
+/// value of `<input type="datetime-local">` in local time into utc
+fn parse_deadline(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()
+        .and_then(|x| Local.from_local_datetime(&x).single())
+        .map(|x| x.naive_utc())
+}
+
+fn parse_optional<T: std::str::FromStr>(value: &str) -> Result<Option<T>, T::Err> {
+    match value.trim() {
+        "" => Ok(None),
+        x => x.parse().map(Some),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ClassAssignmentsProps {
+    pub class_id: i64,
+    /// lecturer of the class, can manage the assignment and grade the submission
+    pub lecturing: bool,
+}
+
+/// assignments of the class, lecturer grade the submissions and member submit their dynotests
+#[function_component(ClassAssignments)]
+pub fn class_assignments(props: &ClassAssignmentsProps) -> Html {
+    let ClassAssignmentsProps {
+        class_id,
+        lecturing,
+    } = props.clone();
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let assignments = use_state(Vec::<Assignment>::new);
+    let selected = use_state(Option::<i64>::default);
+    let submissions = use_state(Vec::<Submission>::new);
+    let new_assignment = use_state(<[AttrValue; 6]>::default);
+    let submit = use_state(|| (AttrValue::default(), String::new()));
+    let grading = use_state(|| {
+        (
+            Option::<i64>::None,
+            AttrValue::default(),
+            AttrValue::default(),
+        )
+    });
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (assignments, selected, error) = (assignments.clone(), selected.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |(class_id, _)| {
+                let class_id = *class_id;
+                spawn_local(async move {
+                    match fetch_assignments(&token, class_id).await {
+                        Ok(fetched) => {
+                            if !fetched.iter().any(|x| Some(x.id) == *selected) {
+                                selected.set(None);
+                            }
+                            assignments.set(fetched)
+                        }
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            (class_id, *refresh),
+        );
+    }
+
+    {
+        let (submissions, error) = (submissions.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |(selected, _)| {
+                let Some(assignment_id) = *selected else {
+                    submissions.set(vec![]);
+                    return;
+                };
+                spawn_local(async move {
+                    match fetch_submissions(&token, assignment_id).await {
+                        Ok(fetched) => submissions.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            (*selected, *refresh),
+        );
+    }
+
+    let on_create = {
+        let (error, refresh, new_assignment) =
+            (error.clone(), refresh.clone(), new_assignment.clone());
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let [title, description, engine_cc, engine_stroke, min_runs, deadline] =
+                (*new_assignment).clone();
+            let Some(deadline) = parse_deadline(&deadline) else {
+                error.set("Deadline is not a valid date and time".into());
+                return;
+            };
+            let (Ok(engine_cc), Ok(engine_stroke), Ok(min_runs)) = (
+                parse_optional(&engine_cc),
+                parse_optional(&engine_stroke),
+                parse_optional(&min_runs),
+            ) else {
+                error.set("Engine cc, stroke and minimum runs should be a number".into());
+                return;
+            };
+            let data = NewAssignment {
+                title: title.to_string(),
+                description: description.to_string(),
+                engine_cc,
+                engine_stroke,
+                min_runs: min_runs.unwrap_or(1),
+                deadline,
+            };
+            spawn_local(async move {
+                match create_assignment(&token, class_id, data).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_delete = {
+        let (error, refresh) = (error.clone(), refresh.clone());
+        let token = token.clone();
+        Callback::from(move |id: i64| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            spawn_local(async move {
+                match delete_assignment(&token, id).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_submit = {
+        let (error, refresh, submit) = (error.clone(), refresh.clone(), submit.clone());
+        let selected = *selected;
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let Some(assignment_id) = selected else {
+                return;
+            };
+            let (dyno_ids, analysis) = (*submit).clone();
+            let Ok(dyno_ids) = dyno_ids
+                .split(',')
+                .map(|x| x.trim().parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                error.set("Dynotest ids should be comma separated numbers".into());
+                return;
+            };
+            let data = SubmitAssignment { dyno_ids, analysis };
+            spawn_local(async move {
+                match submit_assignment(&token, assignment_id, data).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_grade = {
+        let (error, refresh, grading) = (error.clone(), refresh.clone(), grading.clone());
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh, grading) = (error.clone(), refresh.clone(), grading.clone());
+            let token = token.clone();
+            let (Some(id), grade, comment) = (*grading).clone() else {
+                return;
+            };
+            let Ok(grade) = grade.trim().parse::<f32>() else {
+                error.set("Grade should be a number".into());
+                return;
+            };
+            let data = GradeSubmission {
+                grade,
+                comment: (!comment.is_empty()).then(|| comment.to_string()),
+            };
+            spawn_local(async move {
+                match grade_submission(&token, id, data).await {
+                    Ok(_) => {
+                        grading.set(Default::default());
+                        refresh.set(*refresh + 1)
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_export = {
+        let (error, token) = (error.clone(), token.clone());
+        Callback::from(move |tp: &'static str| {
+            let (error, token) = (error.clone(), token.clone());
+            spawn_local(async move {
+                let url = format!("/api/classes/{class_id}/grades");
+                if let Err(err) = fetch_and_save(url, tp, token).await {
+                    error.set(err.to_string().into());
+                }
+            })
+        })
+    };
+
+    let selected_assignment =
+        selected.and_then(|id| assignments.iter().find(|x| x.id == id).cloned());
+
+    let assignment_rows = assignments.iter().map(|d| {
+        let id = d.id;
+        let (selected, on_delete) = (selected.clone(), on_delete.clone());
+        html! {
+            <tr key={id} class={if *selected == Some(id) { "active" } else { "" }}>
+                <td>{&d.title}</td>
+                <td>{d.requirement()}</td>
+                <td>{format_time(&d.deadline)}</td>
+                <td>
+                    <button class="btn btn-sm" onclick={move |_| selected.set(Some(id))}>
+                        {"Open"}
+                    </button>
+                    if lecturing {
+                        <button class="btn btn-sm btn-error ml-2" onclick={move |_| on_delete.emit(id)}>
+                            {"Delete"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    let submission_rows = submissions.iter().map(|d| {
+        let id = d.id;
+        let grading = grading.clone();
+        let current = (
+            Some(id),
+            d.grade.map(|x| x.to_string()).unwrap_or_default().into(),
+            d.comment.clone().unwrap_or_default().into(),
+        );
+        html! {
+            <tr key={id}>
+                <td>{&d.nim}</td>
+                <td>{&d.name}</td>
+                <td>{d.dyno_ids.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(", ")}</td>
+                <td class="whitespace-pre-wrap">{&d.analysis}</td>
+                <td>{d.grade.map(|x| x.to_string()).unwrap_or("-".to_owned())}</td>
+                <td>{d.comment.clone().unwrap_or_default()}</td>
+                <td>{format_time(&d.updated_at)}</td>
+                <td>
+                    if lecturing {
+                        <button class="btn btn-sm" onclick={move |_| grading.set(current.clone())}>
+                            {"Grade"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    let new_assignment_input =
+        |idx: usize, title: &'static str, types: &'static str, required: bool| {
+            let new_assignment = new_assignment.clone();
+            html! {
+                <TextInput
+                    {title}
+                    {types}
+                    {required}
+                    value={new_assignment[idx].to_string()}
+                    update_callback={move |s: AttrValue| {
+                        let mut updated = (*new_assignment).clone();
+                        updated[idx] = s;
+                        new_assignment.set(updated);
+                    }}
+                />
+            }
+        };
+
+    html! {
+    <>
+        <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+        if lecturing {
+            <TitleCard class="mt-2" title="New Assignment">
+                <form onsubmit={on_create}>
+                    <div class="grid grid-cols-1 md:grid-cols-3 gap-6">
+                        {new_assignment_input(0, "Title", "text", true)}
+                        {new_assignment_input(5, "Deadline", "datetime-local", true)}
+                        {new_assignment_input(4, "Minimum Runs", "number", false)}
+                        {new_assignment_input(2, "Engine cc", "number", false)}
+                        {new_assignment_input(3, "Engine Stroke", "number", false)}
+                    </div>
+                    <TextInputArea
+                        title="Description"
+                        value={new_assignment[1].clone()}
+                        update_callback={let new_assignment = new_assignment.clone(); move |s: String| {
+                            let mut updated = (*new_assignment).clone();
+                            updated[1] = s.into();
+                            new_assignment.set(updated);
+                        }}
+                    />
+                    <button type="submit" class="btn btn-primary mt-4">{"Create"}</button>
+                </form>
+            </TitleCard>
+        }
+        <TitleCard class="mt-2" title="Assignments">
+            if lecturing {
+                <div class="flex gap-2 mb-4">
+                    <button class="btn btn-sm" onclick={let on_export = on_export.clone(); move |_| on_export.emit("csv")}>
+                        {"Export Grades CSV"}
+                    </button>
+                    <button class="btn btn-sm" onclick={move |_| on_export.emit("excel")}>
+                        {"Export Grades XLSX"}
+                    </button>
+                </div>
+            }
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Title"}</th>
+                        <th>{"Requirement"}</th>
+                        <th>{"Deadline"}</th>
+                        <th></th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for assignment_rows}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+        if let Some(assignment) = selected_assignment {
+            <TitleCard class="mt-2" title={format!("Submissions of {}", assignment.title)}>
+                <p class="whitespace-pre-wrap mb-4">{&assignment.description}</p>
+                if !lecturing {
+                    <form onsubmit={on_submit}>
+                        <TextInput
+                            title="Dynotest Ids"
+                            placeholder="1, 2, 3"
+                            value={submit.0.to_string()}
+                            required={true}
+                            update_callback={let submit = submit.clone(); move |s| submit.set((s, submit.1.clone()))}
+                        />
+                        <TextInputArea
+                            title="Analysis"
+                            value={submit.1.clone()}
+                            update_callback={let submit = submit.clone(); move |s| submit.set((submit.0.clone(), s))}
+                        />
+                        <button type="submit" class="btn btn-primary mt-4">{"Submit"}</button>
+                    </form>
+                }
+                if lecturing && grading.0.is_some() {
+                    <form onsubmit={on_grade}>
+                        <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                            <TextInput
+                                title="Grade"
+                                types="number"
+                                value={grading.1.to_string()}
+                                required={true}
+                                update_callback={let grading = grading.clone(); move |s| grading.set((grading.0, s, grading.2.clone()))}
+                            />
+                            <TextInput
+                                title="Comment"
+                                value={grading.2.to_string()}
+                                update_callback={let grading = grading.clone(); move |s| grading.set((grading.0, grading.1.clone(), s))}
+                            />
+                        </div>
+                        <button type="submit" class="btn btn-primary mt-4">{"Save Grade"}</button>
+                    </form>
+                }
+                <div class="overflow-x-auto">
+                    <table class="table w-full">
+                        <thead>
+                        <tr>
+                            <th>{"NIM"}</th>
+                            <th>{"Nama"}</th>
+                            <th>{"Dynotest"}</th>
+                            <th>{"Analysis"}</th>
+                            <th>{"Grade"}</th>
+                            <th>{"Comment"}</th>
+                            <th>{"Submitted at"}</th>
+                            <th></th>
+                        </tr>
+                        </thead>
+                        <tbody>
+                        {for submission_rows}
+                        </tbody>
+                    </table>
+                </div>
+            </TitleCard>
+        }
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_assignments(token: &str, class_id: i64) -> DynoResult<Vec<Assignment>> {
+    let response = Request::get(&format!("/api/classes/{class_id}/assignments"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_submissions(token: &str, assignment_id: i64) -> DynoResult<Vec<Submission>> {
+    let response = Request::get(&format!("/api/assignments/{assignment_id}/submissions"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn create_assignment(
+    token: &str,
+    class_id: i64,
+    data: NewAssignment,
+) -> DynoResult<Assignment> {
+    let response = Request::post(&format!("/api/classes/{class_id}/assignments"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn delete_assignment(token: &str, id: i64) -> DynoResult<i64> {
+    let response = Request::delete(&format!("/api/assignments/{id}"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn submit_assignment(
+    token: &str,
+    assignment_id: i64,
+    data: SubmitAssignment,
+) -> DynoResult<Submission> {
+    let response = Request::post(&format!("/api/assignments/{assignment_id}/submissions"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn grade_submission(
+    token: &str,
+    id: i64,
+    data: GradeSubmission,
+) -> DynoResult<dyno_core::serde_json::Value> {
+    let response = Request::patch(&format!("/api/submissions/{id}/grade"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -0,0 +1,459 @@
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    dynotests::DynoTest,
+    serde, ApiResponse, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::SubmitEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html,
+};
+use yewdux::prelude::use_store;
+
+use super::assignments::ClassAssignments;
+use crate::{
+    components::{cards::TitleCard, input::TextInput, typography::ErrorText},
+    state::AppState,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct Class {
+    pub id: i64,
+    pub name: String,
+    pub course: String,
+    pub semester: String,
+    pub lecturer_id: i64,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ClassMember {
+    pub user_id: i64,
+    pub nim: String,
+    pub name: String,
+    pub group_name: Option<String>,
+    pub joined_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct NewClass {
+    name: String,
+    course: String,
+    semester: String,
+    lecturer_id: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct EnrollMembers {
+    user_ids: Vec<i64>,
+    group_name: Option<String>,
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    Local.from_utc_datetime(time).format("%r %v").to_string()
+}
+
+/// practicum class of the user, lecturer see the members and the dynotest submitted to the class
+#[function_component(PageClasses)]
+pub fn page_classes() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let is_admin = state.user_session().is_some_and(|x| x.role.is_admin());
+    let my_id = state.user_session().map(|x| x.id);
+    let classes = use_state(Vec::<Class>::new);
+    let selected = use_state(Option::<i64>::default);
+    let members = use_state(Vec::<ClassMember>::new);
+    let dynos = use_state(Vec::<DynoTest>::new);
+    let new_class = use_state(<[AttrValue; 4]>::default);
+    let enroll = use_state(|| (AttrValue::default(), AttrValue::default()));
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (classes, error) = (classes.clone(), error.clone());
+        let token = token.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_classes(&token).await {
+                        Ok(fetched) => classes.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            *refresh,
+        );
+    }
+
+    {
+        let (members, dynos, error) = (members.clone(), dynos.clone(), error.clone());
+        let token = token.clone();
+        let lecturing = selected
+            .and_then(|id| classes.iter().find(|x| x.id == id))
+            .is_some_and(|x| is_admin || Some(x.lecturer_id) == my_id);
+        use_effect_with_deps(
+            move |(selected, _)| {
+                let Some(class_id) = *selected else {
+                    return;
+                };
+                spawn_local(async move {
+                    match fetch_members(&token, class_id).await {
+                        Ok(fetched) => members.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                    if lecturing {
+                        match fetch_class_dynos(&token, class_id).await {
+                            Ok(fetched) => dynos.set(fetched),
+                            Err(err) => error.set(err.to_string().into()),
+                        }
+                    } else {
+                        dynos.set(vec![]);
+                    }
+                })
+            },
+            (*selected, *refresh),
+        );
+    }
+
+    let on_create = {
+        let (error, refresh, new_class) = (error.clone(), refresh.clone(), new_class.clone());
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let [name, course, semester, lecturer_id] = (*new_class).clone();
+            let Ok(lecturer_id) = lecturer_id.parse() else {
+                error.set("Lecturer id should be a number".into());
+                return;
+            };
+            let data = NewClass {
+                name: name.to_string(),
+                course: course.to_string(),
+                semester: semester.to_string(),
+                lecturer_id,
+            };
+            spawn_local(async move {
+                match create_class(&token, data).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_enroll = {
+        let (error, refresh, enroll) = (error.clone(), refresh.clone(), enroll.clone());
+        let selected = *selected;
+        let token = token.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let Some(class_id) = selected else {
+                return;
+            };
+            let (user_ids, group_name) = (*enroll).clone();
+            let Ok(user_ids) = user_ids
+                .split(',')
+                .map(|x| x.trim().parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                error.set("User ids should be comma separated numbers".into());
+                return;
+            };
+            let data = EnrollMembers {
+                user_ids,
+                group_name: (!group_name.is_empty()).then(|| group_name.to_string()),
+            };
+            spawn_local(async move {
+                match enroll_members(&token, class_id, data).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_remove = {
+        let (error, refresh) = (error.clone(), refresh.clone());
+        let selected = *selected;
+        Callback::from(move |user_id: i64| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            let Some(class_id) = selected else {
+                return;
+            };
+            spawn_local(async move {
+                match remove_member(&token, class_id, user_id).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let selected_class = selected.and_then(|id| classes.iter().find(|x| x.id == id).cloned());
+    let lecturing = selected_class
+        .as_ref()
+        .is_some_and(|x| is_admin || Some(x.lecturer_id) == my_id);
+
+    let class_rows = classes.iter().map(|d| {
+        let id = d.id;
+        let selected = selected.clone();
+        html! {
+            <tr key={id} class={if *selected == Some(id) { "active" } else { "" }}>
+                <td>{id}</td>
+                <td>{&d.name}</td>
+                <td>{&d.course}</td>
+                <td>{&d.semester}</td>
+                <td>{d.lecturer_id}</td>
+                <td>{format_time(&d.created_at)}</td>
+                <td>
+                    <button class="btn btn-sm" onclick={move |_| selected.set(Some(id))}>
+                        {"Open"}
+                    </button>
+                </td>
+            </tr>
+        }
+    });
+
+    let member_rows = members.iter().map(|d| {
+        let user_id = d.user_id;
+        let on_remove = on_remove.clone();
+        html! {
+            <tr key={user_id}>
+                <td>{user_id}</td>
+                <td>{&d.nim}</td>
+                <td>{&d.name}</td>
+                <td>{d.group_name.clone().unwrap_or("-".to_owned())}</td>
+                <td>{format_time(&d.joined_at)}</td>
+                <td>
+                    if lecturing {
+                        <button class="btn btn-sm" onclick={move |_| on_remove.emit(user_id)}>
+                            {"Remove"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    let dyno_rows = dynos.iter().map(|d| {
+        let owner = members
+            .iter()
+            .find(|x| x.user_id == d.user_id)
+            .map(|x| x.nim.clone())
+            .unwrap_or(d.user_id.to_string());
+        html! {
+            <tr key={d.id}>
+                <td>{d.id}</td>
+                <td>{owner}</td>
+                <td>{if d.verified { "yes" } else { "no" }}</td>
+                <td>{(d.stop - d.start).num_minutes()}</td>
+                <td>{format_time(&d.created_at)}</td>
+            </tr>
+        }
+    });
+
+    let new_class_input = |idx: usize, title: &'static str, types: &'static str| {
+        let new_class = new_class.clone();
+        html! {
+            <TextInput
+                {title}
+                {types}
+                value={new_class[idx].to_string()}
+                required={true}
+                update_callback={move |s: AttrValue| {
+                    let mut updated = (*new_class).clone();
+                    updated[idx] = s;
+                    new_class.set(updated);
+                }}
+            />
+        }
+    };
+
+    html! {
+    <>
+        <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+        if is_admin {
+            <TitleCard class="mt-2" title="New Class">
+                <form onsubmit={on_create}>
+                    <div class="grid grid-cols-1 md:grid-cols-4 gap-6">
+                        {new_class_input(0, "Name", "text")}
+                        {new_class_input(1, "Course", "text")}
+                        {new_class_input(2, "Semester", "text")}
+                        {new_class_input(3, "Lecturer Id", "number")}
+                    </div>
+                    <button type="submit" class="btn btn-primary mt-4">{"Create"}</button>
+                </form>
+            </TitleCard>
+        }
+        <TitleCard class="mt-2" title="Classes">
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Id"}</th>
+                        <th>{"Name"}</th>
+                        <th>{"Course"}</th>
+                        <th>{"Semester"}</th>
+                        <th>{"Lecturer"}</th>
+                        <th>{"Created at"}</th>
+                        <th></th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for class_rows}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+        if let Some(class) = selected_class {
+            <TitleCard class="mt-2" title={format!("Members of {}", class.name)}>
+                if is_admin {
+                    <form onsubmit={on_enroll}>
+                        <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                            <TextInput
+                                title="User Ids"
+                                placeholder="1, 2, 3"
+                                value={enroll.0.to_string()}
+                                required={true}
+                                update_callback={let enroll = enroll.clone(); move |s| enroll.set((s, enroll.1.clone()))}
+                            />
+                            <TextInput
+                                title="Group"
+                                value={enroll.1.to_string()}
+                                update_callback={let enroll = enroll.clone(); move |s| enroll.set((enroll.0.clone(), s))}
+                            />
+                        </div>
+                        <button type="submit" class="btn btn-primary mt-4">{"Enroll"}</button>
+                    </form>
+                }
+                <div class="overflow-x-auto">
+                    <table class="table w-full">
+                        <thead>
+                        <tr>
+                            <th>{"Id"}</th>
+                            <th>{"NIM"}</th>
+                            <th>{"Nama"}</th>
+                            <th>{"Group"}</th>
+                            <th>{"Joined at"}</th>
+                            <th></th>
+                        </tr>
+                        </thead>
+                        <tbody>
+                        {for member_rows}
+                        </tbody>
+                    </table>
+                </div>
+            </TitleCard>
+            if lecturing {
+                <TitleCard class="mt-2" title="Dynotest of the Class">
+                    <div class="overflow-x-auto">
+                        <table class="table w-full">
+                            <thead>
+                            <tr>
+                                <th>{"Id"}</th>
+                                <th>{"NIM"}</th>
+                                <th>{"Verified"}</th>
+                                <th>{"Duration (m)"}</th>
+                                <th>{"Created at"}</th>
+                            </tr>
+                            </thead>
+                            <tbody>
+                            {for dyno_rows}
+                            </tbody>
+                        </table>
+                    </div>
+                </TitleCard>
+            }
+            <ClassAssignments class_id={class.id} {lecturing} />
+        }
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_classes(token: &str) -> DynoResult<Vec<Class>> {
+    let response = Request::get("/api/classes")
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_members(token: &str, class_id: i64) -> DynoResult<Vec<ClassMember>> {
+    let response = Request::get(&format!("/api/classes/{class_id}/members"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_class_dynos(token: &str, class_id: i64) -> DynoResult<Vec<DynoTest>> {
+    let response = Request::get("/api/dyno")
+        .query([("class_id", class_id.to_string())])
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn create_class(token: &str, data: NewClass) -> DynoResult<Class> {
+    let response = Request::post("/api/classes")
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn enroll_members(
+    token: &str,
+    class_id: i64,
+    data: EnrollMembers,
+) -> DynoResult<Vec<ClassMember>> {
+    let response = Request::post(&format!("/api/classes/{class_id}/members"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn remove_member(token: &str, class_id: i64, user_id: i64) -> DynoResult<i64> {
+    let response = Request::delete(&format!("/api/classes/{class_id}/members/{user_id}"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
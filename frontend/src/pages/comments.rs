@@ -0,0 +1,422 @@
+use dyno_core::{
+    chrono::{Local, NaiveDateTime, TimeZone},
+    serde, ApiResponse, BufferData, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::MouseEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html, Properties,
+};
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        chart::ChartMarker,
+        input::{SelectBox, SelectOption, TextInput, TextInputArea},
+        typography::ErrorText,
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct Comment {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub user_id: i64,
+    pub parent_id: Option<i64>,
+    pub body: String,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub nim: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct Annotation {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub user_id: i64,
+    /// `rpm` or `time`
+    pub anchor: String,
+    pub position: f64,
+    pub label: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct SeriesRange {
+    pub max_rpm: Option<f64>,
+    pub duration: f64,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct DynoSeries {
+    pub data: BufferData,
+    pub range: SeriesRange,
+    pub comments: Vec<Comment>,
+    pub annotations: Vec<Annotation>,
+}
+
+impl DynoSeries {
+    /// place every annotation on the rail of the chart by the range of its anchor
+    pub fn markers(&self) -> Vec<ChartMarker> {
+        self.annotations
+            .iter()
+            .filter_map(|x| {
+                let (max, unit) = match x.anchor.as_str() {
+                    "rpm" => (self.range.max_rpm?, "RPM"),
+                    _ => (self.range.duration, "s"),
+                };
+                (max > 0.0).then(|| ChartMarker {
+                    label: x.label.clone().into(),
+                    position: x.position / max,
+                    title: format!("{} at {:.1} {unit}", x.label, x.position).into(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// permissions of the logged in user from `/api/me/permissions`
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct MyPermissions {
+    permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct CommentRequest {
+    body: String,
+    parent_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct NewAnnotation {
+    anchor: String,
+    position: f64,
+    label: String,
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    Local.from_utc_datetime(time).format("%r %v").to_string()
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct DynoDiscussionProps {
+    pub dyno_id: i64,
+    pub token: String,
+    pub comments: Vec<Comment>,
+    pub annotations: Vec<Annotation>,
+    /// emitted after a comment or annotation is added or deleted, so the series is fetched again
+    pub on_changed: Callback<()>,
+}
+
+/// threaded comments and the chart annotations of a dynotest
+#[function_component(DynoDiscussion)]
+pub fn dyno_discussion(props: &DynoDiscussionProps) -> Html {
+    let (state, _) = use_store::<AppState>();
+    let my_id = state.user_session().map(|x| x.id);
+    let can_manage = use_state(bool::default);
+    let body = use_state(String::new);
+    let reply_to = use_state(Option::<i64>::default);
+    let annotation = use_state(|| ("rpm".to_owned(), AttrValue::default(), AttrValue::default()));
+    let error = use_state(AttrValue::default);
+    let dyno_id = props.dyno_id;
+
+    {
+        let (can_manage, error) = (can_manage.clone(), error.clone());
+        let token = props.token.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_my_permissions(&token).await {
+                        Ok(my) => can_manage.set(my.permissions.iter().any(|x| x == "user.manage")),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            (),
+        );
+    }
+
+    let on_comment = {
+        let (body, reply_to, error) = (body.clone(), reply_to.clone(), error.clone());
+        let (token, on_changed) = (props.token.clone(), props.on_changed.clone());
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let (body, reply_to, error) = (body.clone(), reply_to.clone(), error.clone());
+            let (token, on_changed) = (token.clone(), on_changed.clone());
+            let data = CommentRequest {
+                body: (*body).clone(),
+                parent_id: *reply_to,
+            };
+            spawn_local(async move {
+                match add_comment(&token, dyno_id, data).await {
+                    Ok(_) => {
+                        body.set(String::new());
+                        reply_to.set(None);
+                        on_changed.emit(());
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_annotate = {
+        let (annotation, error) = (annotation.clone(), error.clone());
+        let (token, on_changed) = (props.token.clone(), props.on_changed.clone());
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let (annotation, error) = (annotation.clone(), error.clone());
+            let (token, on_changed) = (token.clone(), on_changed.clone());
+            let (anchor, position, label) = (*annotation).clone();
+            let Ok(position) = position.trim().parse::<f64>() else {
+                error.set("Annotation position should be a number".into());
+                return;
+            };
+            let data = NewAnnotation {
+                anchor,
+                position,
+                label: label.to_string(),
+            };
+            spawn_local(async move {
+                match add_annotation(&token, dyno_id, data).await {
+                    Ok(_) => {
+                        annotation.set(("rpm".to_owned(), Default::default(), Default::default()));
+                        on_changed.emit(());
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_delete = {
+        let error = error.clone();
+        let (token, on_changed) = (props.token.clone(), props.on_changed.clone());
+        Callback::from(move |url: String| {
+            let error = error.clone();
+            let (token, on_changed) = (token.clone(), on_changed.clone());
+            spawn_local(async move {
+                match delete_by_url(&token, &url).await {
+                    Ok(_) => on_changed.emit(()),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    // the backend lets the owner or a `user.manage` holder delete
+    let can_delete = |user_id: i64| *can_manage || Some(user_id) == my_id;
+
+    let annotation_rows = props.annotations.iter().map(|d| {
+        let on_delete = on_delete.clone();
+        let url = format!("/api/annotations/{}", d.id);
+        html! {
+            <tr key={d.id}>
+                <td>{&d.label}</td>
+                <td>{&d.anchor}</td>
+                <td>{format!("{:.1}", d.position)}</td>
+                <td>{format_time(&d.created_at)}</td>
+                <td>
+                    if can_delete(d.user_id) {
+                        <button class="btn btn-sm" onclick={move |e: MouseEvent| {
+                            e.prevent_default();
+                            on_delete.emit(url.clone());
+                        }}>
+                            {"Delete"}
+                        </button>
+                    }
+                </td>
+            </tr>
+        }
+    });
+
+    let thread = render_thread(&props.comments, None, &|comment: &Comment| {
+        let (reply_to, on_delete) = (reply_to.clone(), on_delete.clone());
+        let (id, url) = (comment.id, format!("/api/comments/{}", comment.id));
+        html! {
+            <div class="flex gap-2 mt-1">
+                <button class="btn btn-xs" onclick={move |e: MouseEvent| {
+                    e.prevent_default();
+                    reply_to.set(Some(id));
+                }}>
+                    {"Reply"}
+                </button>
+                if can_delete(comment.user_id) {
+                    <button class="btn btn-xs" onclick={move |e: MouseEvent| {
+                        e.prevent_default();
+                        on_delete.emit(url.clone());
+                    }}>
+                        {"Delete"}
+                    </button>
+                }
+            </div>
+        }
+    });
+
+    html! {
+    <>
+        <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+        <TitleCard class="mt-2" title="Annotations">
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-6">
+                    <SelectBox
+                        title="Anchor"
+                        value={annotation.0.clone()}
+                        update_callback={let annotation = annotation.clone(); move |s| annotation.set((s, annotation.1.clone(), annotation.2.clone()))}
+                    >
+                        <SelectOption name="RPM" value="rpm" />
+                        <SelectOption name="Time (s)" value="time" />
+                    </SelectBox>
+                    <TextInput
+                        title="Position"
+                        types="number"
+                        value={annotation.1.to_string()}
+                        required={true}
+                        update_callback={let annotation = annotation.clone(); move |s| annotation.set((annotation.0.clone(), s, annotation.2.clone()))}
+                    />
+                    <TextInput
+                        title="Label"
+                        placeholder="knock here"
+                        value={annotation.2.to_string()}
+                        required={true}
+                        update_callback={let annotation = annotation.clone(); move |s| annotation.set((annotation.0.clone(), annotation.1.clone(), s))}
+                    />
+            </div>
+            <button class="btn btn-primary mt-4" onclick={on_annotate}>{"Annotate"}</button>
+            <div class="overflow-x-auto">
+                <table class="table w-full">
+                    <thead>
+                    <tr>
+                        <th>{"Label"}</th>
+                        <th>{"Anchor"}</th>
+                        <th>{"Position"}</th>
+                        <th>{"Created at"}</th>
+                        <th></th>
+                    </tr>
+                    </thead>
+                    <tbody>
+                    {for annotation_rows}
+                    </tbody>
+                </table>
+            </div>
+        </TitleCard>
+        <TitleCard class="mt-2" title="Comments">
+            {thread}
+            <div class="mt-4">
+                if let Some(id) = *reply_to {
+                    <div class="flex gap-2 items-center">
+                        <span class="text-sm">{format!("Replying to #{id}")}</span>
+                        <button class="btn btn-xs" onclick={let reply_to = reply_to.clone(); move |e: MouseEvent| {
+                            e.prevent_default();
+                            reply_to.set(None);
+                        }}>
+                            {"Cancel"}
+                        </button>
+                    </div>
+                }
+                <TextInputArea
+                    title="Comment"
+                    value={(*body).clone()}
+                    update_callback={let body = body.clone(); move |s| body.set(s)}
+                />
+                <button class="btn btn-primary mt-4" onclick={on_comment}>{"Send"}</button>
+            </div>
+        </TitleCard>
+    </>
+    }
+}
+
+/// comments which reply `parent`, every reply is indented under the replied comment
+fn render_thread(
+    comments: &[Comment],
+    parent: Option<i64>,
+    actions: &dyn Fn(&Comment) -> Html,
+) -> Html {
+    let replies = comments.iter().filter(|x| x.parent_id == parent).map(|d| {
+        html! {
+            <div key={d.id} class="border-l-2 border-base-300 pl-3 mt-3">
+                <div class="text-sm opacity-70">
+                    {format!("#{} {} ({}) - {}", d.id, d.name, d.nim, format_time(&d.created_at))}
+                </div>
+                <p class="whitespace-pre-wrap">{&d.body}</p>
+                {actions(d)}
+                {render_thread(comments, Some(d.id), actions)}
+            </div>
+        }
+    });
+    html! { <>{for replies}</> }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+pub async fn fetch_series(token: &str, dyno_id: i64) -> DynoResult<DynoSeries> {
+    let response = Request::get(&format!("/api/dyno/{dyno_id}/series"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_my_permissions(token: &str) -> DynoResult<MyPermissions> {
+    let response = Request::get("/api/me/permissions")
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn add_comment(token: &str, dyno_id: i64, data: CommentRequest) -> DynoResult<Vec<Comment>> {
+    let response = Request::post(&format!("/api/dyno/{dyno_id}/comments"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn add_annotation(token: &str, dyno_id: i64, data: NewAnnotation) -> DynoResult<Annotation> {
+    let response = Request::post(&format!("/api/dyno/{dyno_id}/annotations"))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn delete_by_url(token: &str, url: &str) -> DynoResult<i64> {
+    let response = Request::delete(url)
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
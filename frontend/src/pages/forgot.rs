@@ -0,0 +1,254 @@
+use dyno_core::{serde, validate_password, ApiResponse, DynoErr, DynoResult};
+use gloo::net::http::{Request, Response};
+use web_sys::SubmitEvent;
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_callback, use_state, AttrValue,
+    Html,
+};
+use yew_router::prelude::use_location;
+
+use crate::components::{
+    input::TextInput,
+    landing_intro::LandingIntro,
+    typography::{ErrorText, HelperText},
+};
+use crate::{LinkTag, Route};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct ForgotPassword {
+    email: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct ResetPassword {
+    token: String,
+    password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct ResetQuery {
+    token: String,
+}
+
+/// card layout of the public auth pages, same as the login page
+pub(super) fn auth_card(title: &'static str, content: Html) -> Html {
+    html! {
+    <div class="min-h-screen bg-base-200 flex items-center">
+        <div class="card mx-auto w-full max-w-5xl  shadow-xl">
+            <div class="grid  md:grid-cols-2 grid-cols-1  bg-base-100 rounded-xl">
+                <div class="">
+                    <LandingIntro />
+                </div>
+                <div class="py-24 px-10">
+                    <h2 class="text-2xl font-semibold mb-2 text-center">{title}</h2>
+                    {content}
+                    <div class="text-center mt-4">
+                        <LinkTag to={Route::SignIn}>
+                        <span class="inline-block  hover:text-primary hover:underline hover:cursor-pointer transition duration-200">
+                            {"Back to Login"}
+                        </span>
+                        </LinkTag>
+                    </div>
+                </div>
+            </div>
+        </div>
+    </div>
+    }
+}
+
+/// request the password reset link to be sent into the email of the user
+#[function_component(PageForgot)]
+pub fn page_forgot() -> Html {
+    let loading = use_state(bool::default);
+    let email = use_state(AttrValue::default);
+    let error = use_state(AttrValue::default);
+    let message = use_state(Option::<AttrValue>::default);
+
+    let email_setter = use_callback(move |s, dp| dp.set(s), email.clone());
+    let onsubmit = {
+        let email = email.to_string();
+        let loading = loading.setter();
+        let error = error.setter();
+        let message = message.setter();
+        use_callback(
+            move |e: SubmitEvent, email| {
+                e.prevent_default();
+                let (loading, error, message) = (loading.clone(), error.clone(), message.clone());
+                let data = ForgotPassword {
+                    email: email.clone(),
+                };
+                spawn_local(async move {
+                    loading.set(true);
+                    match forgot_submit(data).await {
+                        Ok(msg) => message.set(Some(msg.into())),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                    loading.set(false);
+                })
+            },
+            email,
+        )
+    };
+
+    let content = match message.as_ref() {
+        Some(msg) => html! { <HelperText class="mt-8 text-center">{msg}</HelperText> },
+        None => html! {
+        <form {onsubmit}>
+            <div class="mb-4">
+                <TextInput
+                    types="email"
+                    value={email.to_string()}
+                    class="mt-4"
+                    title="Email"
+                    placeholder="Email"
+                    required={true}
+                    update_callback={email_setter}
+                />
+            </div>
+            <ErrorText class="mt-8" > {error.as_ref()} </ErrorText>
+            <button type="submit"
+                class={classes!("btn", "mt-2", "w-full", "btn-primary",
+                    if *loading { "loading-dots loading-sm" } else { "" })
+            }>
+                {"Send Reset Link"}
+            </button>
+        </form>
+        },
+    };
+    auth_card("Forgot Password", content)
+}
+
+/// set new password from the link `/reset?token=` that is sent into the email
+#[function_component(PageReset)]
+pub fn page_reset() -> Html {
+    let token = use_location()
+        .and_then(|loc| loc.query::<ResetQuery>().ok())
+        .map(|q| q.token)
+        .unwrap_or_default();
+    let loading = use_state(bool::default);
+    let pswd = use_state(AttrValue::default);
+    let confirm_pswd = use_state(AttrValue::default);
+    let validation_pswd = use_state(Option::<AttrValue>::default);
+    let error = use_state(AttrValue::default);
+    let message = use_state(Option::<AttrValue>::default);
+
+    let onupdate_pswd = use_callback(
+        move |s: AttrValue, (dp, v)| {
+            v.set(validate_password(&s).err().map(|x| x.desc.into()));
+            dp.set(s);
+        },
+        (pswd.clone(), validation_pswd.clone()),
+    );
+    let onupdate_confirm_pswd = use_callback(move |s, dp| dp.set(s), confirm_pswd.clone());
+
+    let onsubmit = {
+        let loading = loading.setter();
+        let error = error.setter();
+        let message = message.setter();
+        let data = ResetPassword {
+            token: token.clone(),
+            password: pswd.to_string(),
+        };
+        let validation_ok = validation_pswd.is_none() && pswd == confirm_pswd;
+        use_callback(
+            move |e: SubmitEvent, (data, validation_ok)| {
+                e.prevent_default();
+                if !*validation_ok {
+                    error.set("Input Error, Check your input again".into());
+                    return;
+                }
+                let (loading, error, message) = (loading.clone(), error.clone(), message.clone());
+                let data = data.clone();
+                spawn_local(async move {
+                    loading.set(true);
+                    match reset_submit(data).await {
+                        Ok(msg) => message.set(Some(msg.into())),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                    loading.set(false);
+                })
+            },
+            (data, validation_ok),
+        )
+    };
+
+    let content = if token.is_empty() {
+        html! { <ErrorText class="mt-8">{"Reset link is invalid, request a new one"}</ErrorText> }
+    } else if let Some(msg) = message.as_ref() {
+        html! { <HelperText class="mt-8 text-center">{msg}</HelperText> }
+    } else {
+        html! {
+        <form {onsubmit}>
+            <div class="mb-4">
+                <TextInput
+                    types="password"
+                    value={pswd.to_string()}
+                    class="mt-4"
+                    title="New Password"
+                    border={if validation_pswd.is_some() { "input-error" } else { "" }}
+                    required={true}
+                    update_callback={onupdate_pswd}
+                />
+                if let Some(err) = validation_pswd.as_ref() {
+                    <span class="inline-flex text-sm text-red-700">{err}</span>
+                }
+                <TextInput
+                    types="password"
+                    value={confirm_pswd.to_string()}
+                    class="mt-4"
+                    title="Confirm Password"
+                    border={if pswd != confirm_pswd { "input-error" } else { "" }}
+                    required={true}
+                    update_callback={onupdate_confirm_pswd}
+                />
+            </div>
+            <ErrorText class="mt-8" > {error.as_ref()} </ErrorText>
+            <button type="submit"
+                class={classes!("btn", "mt-2", "w-full", "btn-primary",
+                    if *loading { "loading-dots loading-sm" } else { "" })
+            }>
+                {"Reset Password"}
+            </button>
+        </form>
+        }
+    };
+    auth_card("Reset Password", content)
+}
+
+pub(super) async fn parse_response(response: Response) -> DynoResult<String> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<String>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn forgot_submit(data: ForgotPassword) -> DynoResult<String> {
+    let response = Request::post("/api/auth/forgot")
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn reset_submit(data: ResetPassword) -> DynoResult<String> {
+    let response = Request::post("/api/auth/reset")
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
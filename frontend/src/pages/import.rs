@@ -0,0 +1,365 @@
+use dyno_core::{
+    chrono::NaiveDateTime, serde, ApiResponse, DynoConfig, DynoErr, DynoResult, MotorInfo,
+    MotorType,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::{File, FormData, HtmlInputElement, MouseEvent};
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_callback, use_node_ref,
+    use_state, AttrValue, Callback, Html,
+};
+use yew_router::prelude::use_navigator;
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        input::{SelectBox, SelectOption, TextInput, ToggleInput},
+        typography::{ErrorText, HelperText},
+    },
+    route::Route,
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ColumnMapping {
+    pub time: Option<String>,
+    pub speed: Option<String>,
+    pub odo: Option<String>,
+    pub rpm_roda: Option<String>,
+    pub rpm_engine: Option<String>,
+    pub torque: Option<String>,
+    pub horsepower: Option<String>,
+    pub temp: Option<String>,
+    pub decimal_comma: bool,
+}
+
+impl ColumnMapping {
+    fn get_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        match field {
+            "time" => Some(&mut self.time),
+            "speed" => Some(&mut self.speed),
+            "odo" => Some(&mut self.odo),
+            "rpm_roda" => Some(&mut self.rpm_roda),
+            "rpm_engine" => Some(&mut self.rpm_engine),
+            "torque" => Some(&mut self.torque),
+            "horsepower" => Some(&mut self.horsepower),
+            "temp" => Some(&mut self.temp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct ImportSpec {
+    mapping: ColumnMapping,
+    config: DynoConfig,
+    start: NaiveDateTime,
+}
+
+const MAPPED_FIELDS: [(&str, &str); 8] = [
+    ("time", "Time (s)"),
+    ("speed", "Speed"),
+    ("odo", "Odometer"),
+    ("rpm_roda", "RPM Roda"),
+    ("rpm_engine", "RPM Engine"),
+    ("torque", "Torque"),
+    ("horsepower", "Horsepower"),
+    ("temp", "Temperature"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportStep {
+    Upload,
+    Mapping,
+}
+
+#[function_component(PageImport)]
+pub fn page_import() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let token = format!("Bearer {}", state.token_session().unwrap());
+    let navigator = use_navigator();
+
+    let file_ref = use_node_ref();
+    let step = use_state(|| ImportStep::Upload);
+    let loading = use_state(bool::default);
+    let error = use_state(AttrValue::default);
+    let preview = use_state(ImportPreview::default);
+    let mapping = use_state(ColumnMapping::default);
+    let name = use_state(AttrValue::default);
+    let motor_type = use_state(|| MotorType::Engine);
+    let start = use_state(AttrValue::default);
+
+    let selected_file = {
+        let file_ref = file_ref.clone();
+        move || {
+            file_ref
+                .cast::<HtmlInputElement>()
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0))
+        }
+    };
+
+    let on_preview = {
+        let token = token.clone();
+        let selected_file = selected_file.clone();
+        let (step, loading, error, preview) = (
+            step.clone(),
+            loading.clone(),
+            error.clone(),
+            preview.clone(),
+        );
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let Some(file) = selected_file() else {
+                error.set("Choose .csv or .xlsx file first".into());
+                return;
+            };
+            let token = token.clone();
+            let (step, loading, error, preview) = (
+                step.clone(),
+                loading.clone(),
+                error.clone(),
+                preview.clone(),
+            );
+            spawn_local(async move {
+                loading.set(true);
+                match import_preview(&token, &file).await {
+                    Ok(fetched) => {
+                        error.set(AttrValue::default());
+                        preview.set(fetched);
+                        step.set(ImportStep::Mapping);
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+                loading.set(false);
+            })
+        })
+    };
+
+    let on_submit = {
+        let token = token.clone();
+        let (loading, error) = (loading.clone(), error.clone());
+        let (mapping, name, motor_type, start) = (
+            mapping.clone(),
+            name.clone(),
+            motor_type.clone(),
+            start.clone(),
+        );
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let Some(file) = selected_file() else {
+                error.set("Choose .csv or .xlsx file first".into());
+                return;
+            };
+            let Ok(start) = NaiveDateTime::parse_from_str(start.as_str(), "%Y-%m-%dT%H:%M") else {
+                error.set("Start time of the test is required".into());
+                return;
+            };
+            let spec = ImportSpec {
+                mapping: (*mapping).clone(),
+                config: DynoConfig {
+                    motor_type: *motor_type,
+                    motor_info: MotorInfo {
+                        name: name.to_string(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                start,
+            };
+            let token = token.clone();
+            let (loading, error, navigator) = (loading.clone(), error.clone(), navigator.clone());
+            spawn_local(async move {
+                loading.set(true);
+                match import_submit(&token, &file, &spec).await {
+                    Ok(_id) => {
+                        if let Some(nav) = navigator {
+                            nav.push(&Route::Activities);
+                        }
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+                loading.set(false);
+            })
+        })
+    };
+
+    let on_map = use_callback(
+        move |(field, column): (&'static str, String), mapping| {
+            let mut updated = (**mapping).clone();
+            if let Some(value) = updated.get_mut(field) {
+                *value = (!column.is_empty()).then_some(column);
+            }
+            mapping.set(updated);
+        },
+        mapping.clone(),
+    );
+    let on_decimal_comma = use_callback(
+        move |(), mapping| {
+            let mut updated = (**mapping).clone();
+            updated.decimal_comma = !updated.decimal_comma;
+            mapping.set(updated);
+        },
+        mapping.clone(),
+    );
+    let on_name = use_callback(move |s, name| name.set(s), name.clone());
+    let on_start = use_callback(move |s, start| start.set(s), start.clone());
+    let on_motor_type = use_callback(
+        move |s: String, motor_type| {
+            motor_type.set(if s == "electric" {
+                MotorType::Electric
+            } else {
+                MotorType::Engine
+            })
+        },
+        motor_type.clone(),
+    );
+
+    let preview_table = html! {
+        <div class="overflow-x-auto">
+            <table class="table table-compact w-full">
+                <thead>
+                    <tr>
+                        {for preview.headers.iter().map(|h| html!(<th>{h}</th>))}
+                    </tr>
+                </thead>
+                <tbody>
+                    {for preview.rows.iter().enumerate().map(|(k, row)| html! {
+                        <tr key={k}>
+                            {for row.iter().map(|cell| html!(<td>{cell}</td>))}
+                        </tr>
+                    })}
+                </tbody>
+            </table>
+            <HelperText class="mt-2">
+                {format!("showing {} of {} rows", preview.rows.len(), preview.total_rows)}
+            </HelperText>
+        </div>
+    };
+
+    let mapping_inputs = MAPPED_FIELDS.into_iter().map(|(field, title)| {
+        let on_map = on_map.clone();
+        html! {
+            <SelectBox
+                {title}
+                placeholder="Not Mapped"
+                container_class="w-full"
+                update_callback={Callback::from(move |column: String| on_map.emit((field, column)))}
+            >
+                <SelectOption name="-" value="" />
+                {for preview.headers.iter().map(|h| html!(<SelectOption name={h.clone()} value={h.clone()} />))}
+            </SelectBox>
+        }
+    });
+
+    html! {
+    <>
+        <ul class="steps w-full">
+            <li class="step step-primary">{"Upload"}</li>
+            <li class={classes!("step", if *step == ImportStep::Mapping { "step-primary" } else { "" })}>
+                {"Preview & Mapping"}
+            </li>
+        </ul>
+        <TitleCard class="mt-2" title="Import Spreadsheet Dynotest">
+            <input
+                ref={file_ref}
+                type="file"
+                accept=".csv,.xlsx,.xls,.ods"
+                class="file-input file-input-bordered w-full max-w-xs"
+            />
+            <button
+                class={classes!("btn", "ml-2", "btn-primary", if *loading { "loading" } else { "" })}
+                onclick={on_preview}
+            >
+                {"Preview"}
+            </button>
+            <ErrorText class="mt-4">{error.as_ref()}</ErrorText>
+        </TitleCard>
+        if *step == ImportStep::Mapping {
+            <TitleCard title="Preview">{preview_table}</TitleCard>
+            <TitleCard title="Column Mapping">
+                <div class="grid lg:grid-cols-4 md:grid-cols-2 grid-cols-1 gap-2">
+                    {for mapping_inputs}
+                </div>
+                <ToggleInput
+                    title="Decimal separator is comma (12,5)"
+                    value={mapping.decimal_comma}
+                    checked={mapping.decimal_comma}
+                    update_callback={on_decimal_comma}
+                />
+                <div class="grid lg:grid-cols-3 grid-cols-1 gap-2">
+                    <TextInput title="Motor Name" value={name.to_string()} update_callback={on_name} />
+                    <TextInput title="Start Time" types="datetime-local" value={start.to_string()} update_callback={on_start} />
+                    <SelectBox title="Motor Type" placeholder="Choose Motor Type" container_class="w-full" update_callback={on_motor_type}>
+                        <SelectOption name="Engine" value="engine" />
+                        <SelectOption name="Electric" value="electric" />
+                    </SelectBox>
+                </div>
+                <button
+                    class={classes!("btn", "mt-4", "w-full", "btn-primary", if *loading { "loading" } else { "" })}
+                    onclick={on_submit}
+                >
+                    {"Import"}
+                </button>
+            </TitleCard>
+        }
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn import_preview(token: &str, file: &File) -> DynoResult<ImportPreview> {
+    let form = FormData::new().map_err(|_| DynoErr::api_error("Failed to create FormData"))?;
+    form.append_with_blob_and_filename("file", file, &file.name())
+        .map_err(|_| DynoErr::api_error("Failed to append file into FormData"))?;
+
+    let response = Request::post("/api/dyno/import/preview")
+        .header("Authorization", token)
+        .body(form)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn import_submit(token: &str, file: &File, spec: &ImportSpec) -> DynoResult<i64> {
+    let spec = dyno_core::serde_json::to_string(spec).map_err(DynoErr::api_error)?;
+    let form = FormData::new().map_err(|_| DynoErr::api_error("Failed to create FormData"))?;
+    form.append_with_blob_and_filename("file", file, &file.name())
+        .map_err(|_| DynoErr::api_error("Failed to append file into FormData"))?;
+    form.append_with_str("spec", &spec)
+        .map_err(|_| DynoErr::api_error("Failed to append spec into FormData"))?;
+
+    let response = Request::post("/api/dyno/import")
+        .header("Authorization", token)
+        .body(form)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
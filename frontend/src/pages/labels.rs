@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use dyno_core::{dynotests::DynoTest, serde, ApiResponse, DynoErr, DynoResult};
+use gloo::net::http::{Request, Response};
+use web_sys::MouseEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_state, AttrValue, Callback, Html,
+    Properties,
+};
+
+use crate::components::{
+    input::{TextInput, TextInputArea},
+    typography::ErrorText,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct DynoLabels {
+    pub dyno_id: i64,
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl DynoLabels {
+    /// metadata as `key=value` lines, the same format parsed by [`parse_metadata`]
+    fn metadata_lines(&self) -> String {
+        self.metadata
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn parse_metadata(lines: &str) -> Result<BTreeMap<String, String>, String> {
+    lines
+        .lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(|line| match line.split_once('=') {
+            Some((k, v)) => Ok((k.trim().to_owned(), v.trim().to_owned())),
+            None => Err(format!(
+                "Metadata `{line}` should be written as `key=value`"
+            )),
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct DynoLabelsEditProps {
+    pub labels: DynoLabels,
+    pub token: String,
+    pub on_saved: Callback<DynoLabels>,
+}
+
+/// edit the title, notes, tags and metadata of a dynotest owned by the user
+#[function_component(DynoLabelsEdit)]
+pub fn dyno_labels_edit(props: &DynoLabelsEditProps) -> Html {
+    let title = use_state(|| AttrValue::from(props.labels.title.clone().unwrap_or_default()));
+    let notes = use_state(|| props.labels.notes.clone().unwrap_or_default());
+    let tags = use_state(|| AttrValue::from(props.labels.tags.join(", ")));
+    let metadata = use_state(|| props.labels.metadata_lines());
+    let error = use_state(AttrValue::default);
+
+    let on_save = {
+        let (title, notes, tags, metadata) =
+            (title.clone(), notes.clone(), tags.clone(), metadata.clone());
+        let (error, token) = (error.clone(), props.token.clone());
+        let (dyno_id, on_saved) = (props.labels.dyno_id, props.on_saved.clone());
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let metadata = match parse_metadata(&metadata) {
+                Ok(metadata) => metadata,
+                Err(err) => return error.set(err.into()),
+            };
+            let data = DynoLabels {
+                dyno_id,
+                title: Some(title.to_string()),
+                notes: Some((*notes).clone()),
+                tags: parse_tags(&tags),
+                metadata,
+            };
+            let (error, token, on_saved) = (error.clone(), token.clone(), on_saved.clone());
+            spawn_local(async move {
+                match update_labels(&token, data).await {
+                    Ok(labels) => {
+                        error.set(AttrValue::default());
+                        on_saved.emit(labels);
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    html! {
+        <div class="mt-2">
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                <TextInput
+                    title="Title"
+                    placeholder="after remap"
+                    value={title.to_string()}
+                    update_callback={let title = title.clone(); move |s| title.set(s)}
+                />
+                <TextInput
+                    title="Tags"
+                    placeholder="stock, new exhaust"
+                    value={tags.to_string()}
+                    update_callback={let tags = tags.clone(); move |s| tags.set(s)}
+                />
+            </div>
+            <TextInputArea
+                title="Notes"
+                value={(*notes).clone()}
+                update_callback={let notes = notes.clone(); move |s| notes.set(s)}
+            />
+            <TextInputArea
+                title="Metadata (key=value per line)"
+                placeholder="fuel=pertamax"
+                value={(*metadata).clone()}
+                update_callback={let metadata = metadata.clone(); move |s| metadata.set(s)}
+            />
+            <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+            <button class="btn btn-primary mt-4" onclick={on_save}>{"Save"}</button>
+        </div>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+/// labels of every dynotest owned by the user
+pub async fn fetch_labels(token: &str) -> DynoResult<Vec<DynoLabels>> {
+    let response = Request::get("/api/dyno/labels")
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+/// every dynotest of the user that has the `tag`
+pub async fn fetch_dyno_by_tag(token: &str, tag: &str) -> DynoResult<Vec<DynoTest>> {
+    let response = Request::get("/api/dyno")
+        .query([("all", "true"), ("tag", tag)])
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn update_labels(token: &str, data: DynoLabels) -> DynoResult<DynoLabels> {
+    let response = Request::put(&format!("/api/dyno/{}/labels", data.dyno_id))
+        .header("Authorization", token)
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -1,19 +1,34 @@
 mod activities;
+mod assignments;
+mod classes;
+mod comments;
 mod dashboard;
+mod forgot;
+mod import;
+mod labels;
 mod live;
 mod not_found;
 mod setting_profile;
+mod share;
 mod signin;
 mod signup;
 mod sop;
+mod two_factor;
+mod verify;
 
 pub use activities::PageActivities;
+pub use classes::PageClasses;
 pub use dashboard::PageDashboard;
+pub use forgot::{PageForgot, PageReset};
+pub use import::PageImport;
 pub use live::PageLive;
 pub use not_found::PageNotFound;
 pub use setting_profile::PageSettingProfile;
+pub use share::{PageShare, ShareLinks};
 pub use signin::PageSignIn;
 pub use signup::PageSignUp;
 pub use sop::PageSop;
+pub use two_factor::TwoFactorSettings;
+pub use verify::PageVerify;
 
 pub mod admin;
@@ -15,6 +15,7 @@ use crate::{
         cards::TitleCard,
         input::{TextInput, ToggleInput},
     },
+    pages::TwoFactorSettings,
     state::AppState,
 };
 
@@ -35,6 +36,7 @@ pub fn setting_profile() -> Html {
     let password_val = password.deref().clone();
     let role_val = role.deref().clone();
     let email_val = email.deref().clone();
+    let token = state.token_session().cloned().unwrap_or_default();
     let on_update_async = {
         let id = user.id;
         let nim = nim_val.clone();
@@ -81,6 +83,7 @@ pub fn setting_profile() -> Html {
     let email_val = email_val.unwrap_or(user.email.unwrap_or_default());
 
     html! {
+    <>
     <TitleCard title="Profile Settings" class="mt-2">
 
         <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
@@ -130,5 +133,7 @@ pub fn setting_profile() -> Html {
 
         <div class="mt-16"><button class="btn btn-primary float-right" onclick={on_update}>{"Update"}</button></div>
     </TitleCard>
+    <TwoFactorSettings {token} />
+    </>
     }
 }
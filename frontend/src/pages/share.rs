@@ -0,0 +1,366 @@
+use dyno_core::{
+    chrono::{offset::TimeZone, Local, NaiveDateTime},
+    dynotests::DynoTest,
+    serde, ApiResponse, DynoConfig, DynoErr, DynoPlot, DynoResult, MotorType,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::MouseEvent;
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, AttrValue,
+    Callback, Html, Properties,
+};
+use yew_icons::IconId;
+use yew_router::prelude::use_location;
+
+use crate::{
+    components::{
+        cards::TitleCard,
+        chart::Chart,
+        stats::Stats,
+        typography::{ErrorText, HelperText, Title},
+    },
+    fetch::{fetch_and_save, fetch_data_dyno},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct DynoPeaks {
+    pub max_horsepower: Option<f64>,
+    pub rpm_at_max_horsepower: Option<f64>,
+    pub max_torque: Option<f64>,
+    pub rpm_at_max_torque: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub max_rpm: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct SharedDyno {
+    pub dyno: DynoTest,
+    pub config: Option<DynoConfig>,
+    pub peaks: DynoPeaks,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ShareLink {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub active: bool,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ShareLinkCreated {
+    pub id: i64,
+    pub dyno_id: i64,
+    pub token: String,
+    pub url: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct ShareQuery {
+    token: String,
+}
+
+fn format_peak(value: Option<f64>) -> String {
+    value.map(|x| format!("{x:.2}")).unwrap_or("-".to_owned())
+}
+
+/// public read-only page of a dynotest opened from a share link, no login required
+#[function_component(PageShare)]
+pub fn page_share() -> Html {
+    let token = use_location()
+        .and_then(|loc| loc.query::<ShareQuery>().ok())
+        .map(|q| q.token)
+        .unwrap_or_default();
+    let shared = use_state(|| Option::<SharedDyno>::None);
+    let plot = use_state(DynoPlot::new);
+    let error = use_state(AttrValue::default);
+
+    {
+        let (shared, plot, error) = (shared.clone(), plot.clone(), error.clone());
+        use_effect_with_deps(
+            move |token: &String| {
+                let token = token.clone();
+                spawn_local(async move {
+                    match fetch_shared(&token).await {
+                        Ok(fetched) => {
+                            let data = fetch_data_dyno(&fetched.dyno.data_url, "").await;
+                            plot.set(DynoPlot::new().create_dyno_plot(&data));
+                            shared.set(Some(fetched));
+                        }
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            token,
+        );
+    }
+
+    let Some(shared) = shared.as_ref() else {
+        return html! {
+            <div class="min-h-screen flex items-center justify-center">
+                <TitleCard title="Shared Dynotest">
+                    <ErrorText>{error.as_ref()}</ErrorText>
+                </TitleCard>
+            </div>
+        };
+    };
+
+    let on_download = |tp: &'static str| {
+        let url = shared.dyno.data_url.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let url = url.clone();
+            spawn_local(async move {
+                if let Err(err) = fetch_and_save(url, tp, "").await {
+                    dyno_core::log::error!("{err}");
+                }
+            })
+        })
+    };
+
+    let (name, motor_type) = match shared.config.as_ref() {
+        Some(conf) => (
+            conf.motor_info.name.clone(),
+            match conf.motor_type {
+                MotorType::Electric => "Electric",
+                MotorType::Engine => "Engine",
+            },
+        ),
+        None => ("..".to_owned(), ".."),
+    };
+    let peaks = &shared.peaks;
+
+    html! {
+    <div class="p-6 bg-base-200 min-h-screen">
+        <Title class="text-center">{"Dynotest: "}{&name}{" ("}{motor_type}{")"}</Title>
+        <HelperText class="text-center">
+            {format!(
+                "tested at {}, link valid until {}",
+                Local.from_utc_datetime(&shared.dyno.start).format("%r %v"),
+                Local.from_utc_datetime(&shared.expires_at).format("%r %v"),
+            )}
+        </HelperText>
+        <div class="grid lg:grid-cols-4 mt-4 md:grid-cols-2 grid-cols-1 gap-2">
+            <Stats
+                icon={IconId::HeroiconsOutlineBolt}
+                title="Max Horsepower"
+                value={format_peak(peaks.max_horsepower)}
+                desc={format!("at {} RPM", format_peak(peaks.rpm_at_max_horsepower))}
+            />
+            <Stats
+                icon={IconId::HeroiconsOutlineCog}
+                title="Max Torque"
+                value={format_peak(peaks.max_torque)}
+                desc={format!("at {} RPM", format_peak(peaks.rpm_at_max_torque))}
+            />
+            <Stats
+                icon={IconId::HeroiconsOutlineArrowTrendingUp}
+                title="Max Speed"
+                value={format_peak(peaks.max_speed)}
+                desc="km/h"
+            />
+            <Stats
+                icon={IconId::HeroiconsOutlineArrowPath}
+                title="Max RPM"
+                value={format_peak(peaks.max_rpm)}
+                desc="RPM"
+            />
+        </div>
+        <Chart id={format!("share_{}", shared.dyno.uuid)} title={format!("Graph: {name}")} plot={(*plot).clone()} />
+        if let Some(info) = shared.config.as_ref() {
+            <TitleCard title="Config">
+                <table class="table w-full">
+                    <tbody>
+                        <tr><th>{"Diameter Roller"}</th><td>{info.diameter_roller.to_string()}</td></tr>
+                        <tr><th>{"Diameter Roller Beban"}</th><td>{info.diameter_roller_beban.to_string()}</td></tr>
+                        <tr><th>{"Diameter Gear Encoder"}</th><td>{info.diameter_gear_encoder.to_string()}</td></tr>
+                        <tr><th>{"Diameter Gear Beban"}</th><td>{info.diameter_gear_beban.to_string()}</td></tr>
+                        <tr><th>{"Jarak Gear"}</th><td>{info.jarak_gear.to_string()}</td></tr>
+                        <tr><th>{"Berat Beban"}</th><td>{info.berat_beban.to_string()}</td></tr>
+                    </tbody>
+                </table>
+            </TitleCard>
+        }
+        <div class="flex gap-2 justify-end">
+            <button class="btn" onclick={on_download("bin")}>{"Download Bin"}</button>
+            <button class="btn" onclick={on_download("csv")}>{"Download Csv"}</button>
+            <button class="btn" onclick={on_download("excel")}>{"Download Excel"}</button>
+        </div>
+    </div>
+    }
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct ShareLinksProps {
+    pub dyno_id: i64,
+    pub token: String,
+}
+
+/// list, create, and revoke share links of a dynotest owned by the user
+#[function_component(ShareLinks)]
+pub fn share_links(props: &ShareLinksProps) -> Html {
+    let links = use_state(Vec::<ShareLink>::new);
+    let created = use_state(|| Option::<ShareLinkCreated>::None);
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (links, error) = (links.clone(), error.clone());
+        let (token, dyno_id) = (props.token.clone(), props.dyno_id);
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_links(&token, dyno_id).await {
+                        Ok(fetched) => links.set(fetched),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            *refresh,
+        );
+    }
+
+    let on_create = {
+        let (created, error, refresh) = (created.clone(), error.clone(), refresh.clone());
+        let (token, dyno_id) = (props.token.clone(), props.dyno_id);
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let (created, error, refresh) = (created.clone(), error.clone(), refresh.clone());
+            let token = token.clone();
+            spawn_local(async move {
+                match create_link(&token, dyno_id).await {
+                    Ok(link) => {
+                        created.set(Some(link));
+                        refresh.set(*refresh + 1);
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let on_revoke = {
+        let (error, refresh) = (error.clone(), refresh.clone());
+        let token = props.token.clone();
+        Callback::from(move |id: i64| {
+            let (error, refresh) = (error.clone(), refresh.clone());
+            let token = token.clone();
+            spawn_local(async move {
+                match revoke_link(&token, id).await {
+                    Ok(_) => refresh.set(*refresh + 1),
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let origin = gloo::utils::window()
+        .location()
+        .origin()
+        .unwrap_or_default();
+    html! {
+    <>
+        <button class="btn btn-primary btn-sm" onclick={on_create}>{"Create Share Link"}</button>
+        if let Some(link) = created.as_ref() {
+            <div class="alert mt-2">
+                <span class="break-all">{format!("{origin}{}", link.url)}</span>
+            </div>
+            <HelperText>{"copy this link now, it can not be shown again"}</HelperText>
+        }
+        <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+        <table class="table table-compact w-full mt-2">
+            <thead>
+                <tr>
+                    <th>{"Id"}</th>
+                    <th>{"Active"}</th>
+                    <th>{"Expires At"}</th>
+                    <th>{"Created At"}</th>
+                    <th></th>
+                </tr>
+            </thead>
+            <tbody>
+            {for links.iter().map(|link| {
+                let id = link.id;
+                let on_revoke = on_revoke.clone();
+                html! {
+                    <tr key={id}>
+                        <td>{id}</td>
+                        <td>{if link.active { "yes" } else { "no" }}</td>
+                        <td>{Local.from_utc_datetime(&link.expires_at).format("%r %v").to_string()}</td>
+                        <td>{Local.from_utc_datetime(&link.created_at).format("%r %v").to_string()}</td>
+                        <td>
+                            if link.active {
+                                <button class="btn btn-sm" onclick={move |e: MouseEvent| {
+                                    e.prevent_default();
+                                    on_revoke.emit(id);
+                                }}>{"Revoke"}</button>
+                            }
+                        </td>
+                    </tr>
+                }
+            })}
+            </tbody>
+        </table>
+    </>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn fetch_shared(token: &str) -> DynoResult<SharedDyno> {
+    let response = Request::get(&format!("/api/share/{token}"))
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_links(token: &str, dyno_id: i64) -> DynoResult<Vec<ShareLink>> {
+    let response = Request::get("/api/dyno/links")
+        .query([("dyno_id", dyno_id.to_string())])
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn create_link(token: &str, dyno_id: i64) -> DynoResult<ShareLinkCreated> {
+    let response = Request::post(&format!("/api/dyno/{dyno_id}/links"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn revoke_link(token: &str, id: i64) -> DynoResult<ShareLink> {
+    let response = Request::delete(&format!("/api/dyno/links/{id}"))
+        .header("Authorization", token)
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -3,6 +3,7 @@ use yew::prelude::*;
 use yew_router::prelude::use_navigator;
 use yewdux::prelude::Dispatch;
 
+use super::two_factor::{TwoFactorChallenge, TwoFactorStep};
 use crate::components::{input::TextInput, landing_intro::LandingIntro, typography::ErrorText};
 use crate::state::AppState;
 use crate::{LinkTag, Route};
@@ -10,23 +11,33 @@ use crate::{LinkTag, Route};
 use dyno_core::users::UserLogin;
 use dyno_core::{ApiResponse, DynoErr, DynoResult, UserSession};
 
+/// response of the first login step
+enum SignIn {
+    Token(TokenDetails),
+    TwoFactor(TwoFactorChallenge),
+}
+
 #[function_component(PageSignIn)]
 pub fn signin() -> yew::Html {
     let loading = use_state(bool::default);
     let nim = use_state(AttrValue::default);
     let password = use_state(AttrValue::default);
     let error = use_state(AttrValue::default);
+    let challenge = use_state(Option::<TwoFactorChallenge>::default);
+    let nav = use_navigator();
 
     let onsubmitlogin = {
-        let nav = use_navigator();
+        let nav = nav.clone();
         let nim = nim.to_string();
         let password = password.to_string();
         let loading = loading.setter();
         let error = error.setter();
+        let challenge = challenge.setter();
         Dispatch::<AppState>::new().reduce_mut_future_callback_with(move |state, e: SubmitEvent| {
             e.prevent_default();
             let loading = loading.clone();
             let error = error.clone();
+            let challenge = challenge.clone();
             let data = UserLogin {
                 nim: nim.clone(),
                 password: password.clone(),
@@ -36,12 +47,13 @@ pub fn signin() -> yew::Html {
             Box::pin(async move {
                 loading.set(true);
                 match signin_submit(data).await {
-                    Ok(token) => {
+                    Ok(SignIn::Token(token)) => {
                         state.set_token_details(token);
                         if let Some(nav) = nav {
                             nav.push(&Route::Dashboard);
                         }
                     }
+                    Ok(SignIn::TwoFactor(next)) => challenge.set(Some(next)),
                     Err(err) => error.set(err.to_string().into()),
                 }
                 loading.set(false);
@@ -62,6 +74,14 @@ pub fn signin() -> yew::Html {
         password.clone(),
     );
 
+    let onlogin =
+        Dispatch::<AppState>::new().reduce_mut_callback_with(move |state, token: TokenDetails| {
+            state.set_token_details(token);
+            if let Some(nav) = &nav {
+                nav.push(&Route::Dashboard);
+            }
+        });
+
     let form_input = html! {
     <form onsubmit={onsubmitlogin}>
         <div class="mb-4">
@@ -84,7 +104,7 @@ pub fn signin() -> yew::Html {
         </div>
 
         <div class="text-right text-primary">
-            <LinkTag to={Route::NotFound}>
+            <LinkTag to={Route::Forgot}>
             <span class=
                 "text-sm inline-block hover:text-primary hover:underline hover:cursor-pointer transition duration-200"
             >
@@ -119,8 +139,14 @@ pub fn signin() -> yew::Html {
                     <LandingIntro />
                 </div>
                 <div class="py-24 px-10">
-                    <h2 class="text-2xl font-semibold mb-2 text-center">{"Login"}</h2>
-                    {form_input}
+                    <h2 class="text-2xl font-semibold mb-2 text-center">
+                        {if challenge.is_some() { "Two-Factor Authentication" } else { "Login" }}
+                    </h2>
+                    if let Some(challenge) = challenge.as_ref() {
+                        <TwoFactorStep challenge={challenge.clone()} {onlogin} />
+                    } else {
+                        {form_input}
+                    }
                 </div>
             </div>
         </div>
@@ -128,9 +154,9 @@ pub fn signin() -> yew::Html {
     }
 }
 
-async fn signin_submit(data: UserLogin) -> DynoResult<TokenDetails> {
+async fn signin_submit(data: UserLogin) -> DynoResult<SignIn> {
     if cfg!(debug_assertions) {
-        return Ok(TokenDetails {
+        return Ok(SignIn::Token(TokenDetails {
             user: UserSession {
                 id: 1,
                 uuid: dyno_core::uuid::Uuid::new_v4(),
@@ -139,16 +165,23 @@ async fn signin_submit(data: UserLogin) -> DynoResult<TokenDetails> {
             token_id: dyno_core::uuid::Uuid::new_v4(),
             expires_in: None,
             token: None,
-        });
+        }));
     }
     match gloo::net::http::Request::post("/api/auth/login").json(&data) {
         Ok(req) => match req.send().await {
             Ok(response) => {
-                if response.ok() {
+                // 202 Accepted when the TOTP code is needed in the second step
+                if response.status() == 202 {
+                    response
+                        .json::<ApiResponse<TwoFactorChallenge>>()
+                        .await
+                        .map(|x| SignIn::TwoFactor(x.payload))
+                        .map_err(DynoErr::api_error)
+                } else if response.ok() {
                     response
                         .json::<ApiResponse<TokenDetails>>()
                         .await
-                        .map(|x| x.payload)
+                        .map(|x| SignIn::Token(x.payload))
                         .map_err(DynoErr::api_error)
                 } else {
                     match response
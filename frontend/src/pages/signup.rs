@@ -2,7 +2,7 @@ use crate::{
     components::{
         input::{SelectBox, SelectOption, TextInput},
         landing_intro::LandingIntro,
-        typography::ErrorText,
+        typography::{ErrorText, HelperText},
     },
     LinkTag, Route,
 };
@@ -17,7 +17,6 @@ use yew::{
     classes, function_component, html, platform::spawn_local, use_callback, use_state, AttrValue,
     Html,
 };
-use yew_router::prelude::use_navigator;
 
 macro_rules! create_cb {
     ($validfunc:ident) => {
@@ -42,6 +41,7 @@ pub fn signup() -> Html {
     let validation_pswd = use_state(Option::<AttrValue>::default);
 
     let error = use_state(AttrValue::default);
+    let message = use_state(Option::<AttrValue>::default);
 
     let onupdate_nim = use_callback(
         create_cb!(validate_nim),
@@ -67,36 +67,40 @@ pub fn signup() -> Html {
     );
 
     let onsubmitsignup = {
-        let navigator = use_navigator();
         let loading = loading.setter();
         let error = error.setter();
-        let data = UserRegistration {
-            nim: nim.to_string(),
-            email: nim.to_string(),
-            password: nim.to_string(),
-            confirm_password: nim.to_string(),
-            role: *role,
-        };
-        let validation_ok =
-            validation_nim.is_none() && validation_pswd.is_none() && validation_nim.is_none();
+        let message = message.setter();
+        let fields = (
+            nim.to_string(),
+            email.to_string(),
+            pswd.to_string(),
+            confirm_pswd.to_string(),
+            *role,
+        );
+        let validation_ok = validation_nim.is_none()
+            && validation_email.is_none()
+            && validation_pswd.is_none()
+            && pswd == confirm_pswd;
 
         use_callback(
-            move |e: SubmitEvent, nav| {
+            move |e: SubmitEvent, ((nim, email, pswd, confirm_pswd, role), validation_ok)| {
                 e.prevent_default();
-                if validation_ok {
+                if *validation_ok {
                     let loading = loading.clone();
                     let error = error.clone();
-                    let nav = nav.clone();
-                    let data = data.clone();
+                    let message = message.clone();
+                    let data = UserRegistration {
+                        nim: nim.clone(),
+                        email: email.clone(),
+                        password: pswd.clone(),
+                        confirm_password: confirm_pswd.clone(),
+                        role: *role,
+                    };
 
                     spawn_local(async move {
                         loading.set(true);
                         match signup_submit(data).await {
-                            Ok(_ok) => {
-                                if let Some(nav) = nav {
-                                    nav.push(&Route::Dashboard)
-                                }
-                            }
+                            Ok(msg) => message.set(Some(msg.into())),
                             Err(err) => error.set(err.to_string().into()),
                         }
                         loading.set(false);
@@ -105,7 +109,7 @@ pub fn signup() -> Html {
                     error.set("Input Error, Check your input again".into());
                 }
             },
-            navigator,
+            (fields, validation_ok),
         )
     };
 
@@ -203,11 +207,11 @@ pub fn signup() -> Html {
                     value={role.to_string()}
                     placeholder={"Choose Role"}
                     title={"Role"}
-                    desc={"Choose role for register user, admin is disabled, only admin can change role to admin"}
+                    desc={"Choose requested role, account is registered as user and the role is granted after admin approval"}
                     container_class={"w-72"}
                     update_callback={onupdate_role}
                 >
-                    <SelectOption name={"Admin"} value={"admin"} disable={true} />
+                    <SelectOption name={"Admin"} value={"admin"} />
                     <SelectOption name={"User"} value={"user"} />
                     <SelectOption name={"Guest"} value={"guest"} />
                 </SelectBox>
@@ -243,7 +247,18 @@ pub fn signup() -> Html {
                 </div>
                 <div class="py-24 px-10">
                     <h2 class="text-2xl font-semibold mb-2 text-center">{"Register"}</h2>
-                    {div_form}
+                    if let Some(msg) = message.as_ref() {
+                        <HelperText class="mt-8 text-center">{msg}</HelperText>
+                        <div class="text-center mt-4">
+                            <LinkTag to={Route::SignIn}>
+                                <span class="inline-block hover:text-primary hover:underline hover:cursor-pointer transition duration-200">
+                                    {"Login"}
+                                </span>
+                            </LinkTag>
+                        </div>
+                    } else {
+                        {div_form}
+                    }
                 </div>
             </div>
         </div>
@@ -251,7 +266,7 @@ pub fn signup() -> Html {
     }
 }
 
-async fn signup_submit(data: UserRegistration) -> DynoResult<usize> {
+async fn signup_submit(data: UserRegistration) -> DynoResult<String> {
     match Request::post("/api/auth/register")
         .json(&data)
         .map_err(DynoErr::api_error)
@@ -260,7 +275,7 @@ async fn signup_submit(data: UserRegistration) -> DynoResult<usize> {
             Ok(response) => {
                 if response.ok() {
                     match response
-                        .json::<ApiResponse<usize>>()
+                        .json::<ApiResponse<String>>()
                         .await
                         .map_err(DynoErr::api_error)
                     {
@@ -0,0 +1,419 @@
+use dyno_core::{
+    chrono::NaiveDateTime, crypto::TokenDetails, serde, ApiResponse, DynoErr, DynoResult,
+};
+use gloo::net::http::{Request, Response};
+use web_sys::{MouseEvent, SubmitEvent};
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_callback, use_effect_with_deps,
+    use_state, AttrValue, Callback, Html, Properties,
+};
+
+use crate::components::{
+    cards::TitleCard,
+    input::TextInput,
+    typography::{ErrorText, HelperText},
+};
+
+/// response of `/api/auth/login` when the TOTP code is needed
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct TwoFactorChallenge {
+    pub challenge: String,
+    pub enroll: bool,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct TwoFactorCode {
+    code: String,
+    challenge: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct TwoFactorLogin {
+    challenge: String,
+    code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct TwoFactorSetup {
+    secret: String,
+    uri: String,
+    qr: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct TwoFactorStatus {
+    enabled: bool,
+    required: bool,
+    recovery_codes_left: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct RecoveryCodes {
+    codes: Vec<String>,
+}
+
+fn setup_view(setup: &TwoFactorSetup) -> Html {
+    html! {
+    <div class="flex flex-col items-center mt-4">
+        <HelperText>{"Scan the QR code with your authenticator app, then enter the 6 digit code"}</HelperText>
+        if let Some(qr) = &setup.qr {
+            <img class="w-48 h-48 mt-2" src={format!("data:image/png;base64,{qr}")} alt={setup.uri.clone()} />
+        }
+        <code class="mt-2 text-sm break-all">{&setup.secret}</code>
+    </div>
+    }
+}
+
+fn recovery_view(codes: &[String]) -> Html {
+    html! {
+    <div class="mt-4">
+        <HelperText>{"Save these recovery codes, each code can be used once when the authenticator is lost"}</HelperText>
+        <div class="grid grid-cols-2 gap-2 mt-2 font-mono text-center">
+            {for codes.iter().map(|code| html! { <span>{code}</span> })}
+        </div>
+    </div>
+    }
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct TwoFactorStepProps {
+    pub challenge: TwoFactorChallenge,
+    pub onlogin: Callback<TokenDetails>,
+}
+
+/// second login step, enroll the TOTP first when it is required but not enrolled yet
+#[function_component(TwoFactorStep)]
+pub fn two_factor_step(props: &TwoFactorStepProps) -> Html {
+    let loading = use_state(bool::default);
+    let code = use_state(AttrValue::default);
+    let error = use_state(AttrValue::default);
+    let enrolled = use_state(|| !props.challenge.enroll);
+    let setup = use_state(Option::<TwoFactorSetup>::default);
+    let recovery = use_state(Option::<Vec<String>>::default);
+
+    {
+        let (setup, error) = (setup.clone(), error.clone());
+        let challenge = props.challenge.challenge.clone();
+        use_effect_with_deps(
+            move |enrolled| {
+                if !*enrolled {
+                    spawn_local(async move {
+                        let data = TwoFactorCode {
+                            challenge: Some(challenge),
+                            ..Default::default()
+                        };
+                        match setup_submit(None, data).await {
+                            Ok(fetched) => setup.set(Some(fetched)),
+                            Err(err) => error.set(err.to_string().into()),
+                        }
+                    })
+                }
+            },
+            *enrolled,
+        );
+    }
+
+    let code_setter = use_callback(move |s, dp| dp.set(s), code.clone());
+    let onsubmit = {
+        let loading = loading.setter();
+        let error = error.setter();
+        let code_reset = code.setter();
+        let enrolled_setter = enrolled.setter();
+        let recovery = recovery.setter();
+        let onlogin = props.onlogin.clone();
+        use_callback(
+            move |e: SubmitEvent, (challenge, code, enrolled)| {
+                e.prevent_default();
+                let (loading, error) = (loading.clone(), error.clone());
+                let (code_reset, enrolled_setter) = (code_reset.clone(), enrolled_setter.clone());
+                let (recovery, onlogin) = (recovery.clone(), onlogin.clone());
+                let (challenge, code, enrolled) = (challenge.clone(), code.clone(), *enrolled);
+                spawn_local(async move {
+                    loading.set(true);
+                    if enrolled {
+                        match login_submit(TwoFactorLogin { challenge, code }).await {
+                            Ok(token) => onlogin.emit(token),
+                            Err(err) => error.set(err.to_string().into()),
+                        }
+                    } else {
+                        let data = TwoFactorCode {
+                            code,
+                            challenge: Some(challenge),
+                        };
+                        match enable_submit(None, data).await {
+                            Ok(codes) => {
+                                recovery.set(Some(codes.codes));
+                                enrolled_setter.set(true);
+                                code_reset.set(AttrValue::default());
+                                error.set(AttrValue::default());
+                            }
+                            Err(err) => error.set(err.to_string().into()),
+                        }
+                    }
+                    loading.set(false);
+                })
+            },
+            (
+                props.challenge.challenge.clone(),
+                code.to_string(),
+                *enrolled,
+            ),
+        )
+    };
+    let on_saved = {
+        let recovery = recovery.clone();
+        Callback::from(move |_: MouseEvent| recovery.set(None))
+    };
+
+    if let Some(codes) = recovery.as_ref() {
+        return html! {
+        <>
+            {recovery_view(codes)}
+            <button class="btn mt-4 w-full btn-primary" onclick={on_saved}>
+                {"I have saved the codes, continue login"}
+            </button>
+        </>
+        };
+    }
+
+    html! {
+    <form {onsubmit}>
+        if !*enrolled {
+            if let Some(setup) = setup.as_ref() {
+                {setup_view(setup)}
+            }
+        }
+        <div class="mb-4">
+            <TextInput
+                types="text"
+                value={code.to_string()}
+                class="mt-4"
+                title={if *enrolled { "Authenticator or Recovery Code" } else { "Authenticator Code" }}
+                placeholder="123456"
+                required={true}
+                update_callback={code_setter}
+            />
+        </div>
+        <ErrorText class="mt-8" > {error.as_ref()} </ErrorText>
+        <button type="submit"
+            class={classes!("btn", "mt-2", "w-full", "btn-primary",
+                if *loading { "loading-dots loading-sm" } else { "" })
+        }>
+            {if *enrolled { "Verify" } else { "Enable Two-Factor" }}
+        </button>
+    </form>
+    }
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct TwoFactorSettingsProps {
+    pub token: String,
+}
+
+/// enroll, disable and regenerate recovery codes of the TOTP of the logged in user
+#[function_component(TwoFactorSettings)]
+pub fn two_factor_settings(props: &TwoFactorSettingsProps) -> Html {
+    let status = use_state(Option::<TwoFactorStatus>::default);
+    let setup = use_state(Option::<TwoFactorSetup>::default);
+    let recovery = use_state(Option::<Vec<String>>::default);
+    let code = use_state(AttrValue::default);
+    let error = use_state(AttrValue::default);
+    let refresh = use_state(|| 0u32);
+
+    {
+        let (status, error) = (status.clone(), error.clone());
+        let token = props.token.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match fetch_status(&token).await {
+                        Ok(fetched) => status.set(Some(fetched)),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                })
+            },
+            *refresh,
+        );
+    }
+
+    let code_setter = use_callback(move |s, dp| dp.set(s), code.clone());
+    let on_setup = {
+        let (setup, recovery, error) = (setup.clone(), recovery.clone(), error.clone());
+        let token = props.token.clone();
+        Callback::from(move |_: MouseEvent| {
+            let (setup, recovery, error) = (setup.clone(), recovery.clone(), error.clone());
+            let token = token.clone();
+            spawn_local(async move {
+                match setup_submit(Some(&token), TwoFactorCode::default()).await {
+                    Ok(fetched) => {
+                        recovery.set(None);
+                        setup.set(Some(fetched));
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+    let on_action = {
+        let (setup, recovery, error) = (setup.clone(), recovery.clone(), error.clone());
+        let (code, refresh) = (code.clone(), refresh.clone());
+        let token = props.token.clone();
+        Callback::from(move |action: &'static str| {
+            let (setup, recovery, error) = (setup.clone(), recovery.clone(), error.clone());
+            let (code, refresh) = (code.clone(), refresh.clone());
+            let token = token.clone();
+            let data = TwoFactorCode {
+                code: code.to_string(),
+                challenge: None,
+            };
+            spawn_local(async move {
+                let result = match action {
+                    "enable" => enable_submit(Some(&token), data).await.map(Some),
+                    "recovery" => recovery_submit(&token, data).await.map(Some),
+                    _ => disable_submit(&token, data).await.map(|_| None),
+                };
+                match result {
+                    Ok(codes) => {
+                        setup.set(None);
+                        recovery.set(codes.map(|x| x.codes));
+                        code.set(AttrValue::default());
+                        error.set(AttrValue::default());
+                        refresh.set(*refresh + 1);
+                    }
+                    Err(err) => error.set(err.to_string().into()),
+                }
+            })
+        })
+    };
+
+    let code_input = html! {
+        <TextInput
+            types="text"
+            value={code.to_string()}
+            title="Authenticator Code"
+            placeholder="123456"
+            update_callback={code_setter}
+        />
+    };
+    let content = match (status.as_ref(), setup.as_ref()) {
+        (_, Some(setup)) => {
+            let on_enable = on_action.clone();
+            html! {
+            <>
+                {setup_view(setup)}
+                {code_input}
+                <button class="btn btn-primary mt-4" onclick={move |_| on_enable.emit("enable")}>
+                    {"Enable"}
+                </button>
+            </>
+            }
+        }
+        (Some(status), None) if status.enabled => {
+            let (on_recovery, on_disable) = (on_action.clone(), on_action.clone());
+            html! {
+            <>
+                <HelperText>{format!("Enabled, {} recovery codes left", status.recovery_codes_left)}</HelperText>
+                {code_input}
+                <div class="mt-4 flex gap-2">
+                    <button class="btn btn-sm" onclick={move |_| on_recovery.emit("recovery")}>
+                        {"Regenerate Recovery Codes"}
+                    </button>
+                    if !status.required {
+                        <button class="btn btn-sm btn-error" onclick={move |_| on_disable.emit("disable")}>
+                            {"Disable"}
+                        </button>
+                    }
+                </div>
+            </>
+            }
+        }
+        (Some(status), None) => html! {
+        <>
+            <HelperText>
+                {if status.required { "Required for your account, enable it before the next login" } else { "Disabled" }}
+            </HelperText>
+            <button class="btn btn-primary mt-4" onclick={on_setup}>{"Set Up Authenticator"}</button>
+        </>
+        },
+        (None, None) => html! {},
+    };
+
+    html! {
+    <TitleCard title="Two-Factor Authentication" class="mt-2">
+        {content}
+        if let Some(codes) = recovery.as_ref() {
+            {recovery_view(codes)}
+        }
+        <ErrorText class="mt-2">{error.as_ref()}</ErrorText>
+    </TitleCard>
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> DynoResult<T> {
+    if response.ok() {
+        response
+            .json::<ApiResponse<T>>()
+            .await
+            .map(|x| x.payload)
+            .map_err(DynoErr::api_error)
+    } else {
+        match response.json::<ApiResponse<DynoErr>>().await {
+            Ok(json) => Err(json.payload),
+            Err(err) => Err(DynoErr::api_error(err)),
+        }
+    }
+}
+
+async fn post_json<T: serde::de::DeserializeOwned>(
+    url: &str,
+    token: Option<&str>,
+    data: &impl serde::Serialize,
+) -> DynoResult<T> {
+    let mut request = Request::post(url);
+    if let Some(token) = token {
+        request = request.header("Authorization", &format!("Bearer {token}"));
+    }
+    let response = request
+        .json(data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn fetch_status(token: &str) -> DynoResult<TwoFactorStatus> {
+    let response = Request::get("/api/auth/2fa")
+        .header("Authorization", &format!("Bearer {token}"))
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn setup_submit(token: Option<&str>, data: TwoFactorCode) -> DynoResult<TwoFactorSetup> {
+    post_json("/api/auth/2fa/setup", token, &data).await
+}
+
+async fn enable_submit(token: Option<&str>, data: TwoFactorCode) -> DynoResult<RecoveryCodes> {
+    post_json("/api/auth/2fa/enable", token, &data).await
+}
+
+async fn recovery_submit(token: &str, data: TwoFactorCode) -> DynoResult<RecoveryCodes> {
+    post_json("/api/auth/2fa/recovery", Some(token), &data).await
+}
+
+async fn disable_submit(token: &str, data: TwoFactorCode) -> DynoResult<String> {
+    post_json("/api/auth/2fa/disable", Some(token), &data).await
+}
+
+async fn login_submit(data: TwoFactorLogin) -> DynoResult<TokenDetails> {
+    post_json("/api/auth/2fa/login", None, &data).await
+}
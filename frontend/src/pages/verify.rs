@@ -0,0 +1,146 @@
+use dyno_core::{serde, DynoErr, DynoResult};
+use gloo::net::http::Request;
+use web_sys::SubmitEvent;
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_callback, use_effect_with_deps,
+    use_state, AttrValue, Html,
+};
+use yew_router::prelude::use_location;
+
+use super::forgot::{auth_card, parse_response};
+use crate::components::{
+    input::TextInput,
+    typography::{ErrorText, HelperText},
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct VerifyEmail {
+    token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+struct ResendVerification {
+    email: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+struct VerifyQuery {
+    token: String,
+}
+
+/// verify the email from the link `/verify?token=` that is sent after registration,
+/// expired link can request a new one from the same page.
+#[function_component(PageVerify)]
+pub fn page_verify() -> Html {
+    let token = use_location()
+        .and_then(|loc| loc.query::<VerifyQuery>().ok())
+        .map(|q| q.token)
+        .unwrap_or_default();
+    let verifying = use_state(|| !token.is_empty());
+    let loading = use_state(bool::default);
+    let email = use_state(AttrValue::default);
+    let error = use_state(AttrValue::default);
+    let message = use_state(Option::<AttrValue>::default);
+
+    {
+        let (verifying, error, message) = (verifying.setter(), error.setter(), message.setter());
+        use_effect_with_deps(
+            move |token: &String| {
+                if !token.is_empty() {
+                    let data = VerifyEmail {
+                        token: token.clone(),
+                    };
+                    spawn_local(async move {
+                        match verify_submit(data).await {
+                            Ok(msg) => message.set(Some(msg.into())),
+                            Err(err) => error.set(err.to_string().into()),
+                        }
+                        verifying.set(false);
+                    });
+                }
+            },
+            token,
+        );
+    }
+
+    let email_setter = use_callback(move |s, dp| dp.set(s), email.clone());
+    let onsubmit = {
+        let loading = loading.setter();
+        let error = error.setter();
+        let message = message.setter();
+        use_callback(
+            move |e: SubmitEvent, email| {
+                e.prevent_default();
+                let (loading, error, message) = (loading.clone(), error.clone(), message.clone());
+                let data = ResendVerification {
+                    email: email.clone(),
+                };
+                spawn_local(async move {
+                    loading.set(true);
+                    match resend_submit(data).await {
+                        Ok(msg) => message.set(Some(msg.into())),
+                        Err(err) => error.set(err.to_string().into()),
+                    }
+                    loading.set(false);
+                })
+            },
+            email.to_string(),
+        )
+    };
+
+    let content = if *verifying {
+        html! { <HelperText class="mt-8 text-center">{"Verifying email..."}</HelperText> }
+    } else if let Some(msg) = message.as_ref() {
+        html! { <HelperText class="mt-8 text-center">{msg}</HelperText> }
+    } else {
+        html! {
+        <form {onsubmit}>
+            <HelperText class="mt-4 text-center">
+                {"Enter the registered email to receive a new verification link"}
+            </HelperText>
+            <div class="mb-4">
+                <TextInput
+                    types="email"
+                    value={email.to_string()}
+                    class="mt-4"
+                    title="Email"
+                    placeholder="Email"
+                    required={true}
+                    update_callback={email_setter}
+                />
+            </div>
+            <ErrorText class="mt-8" > {error.as_ref()} </ErrorText>
+            <button type="submit"
+                class={classes!("btn", "mt-2", "w-full", "btn-primary",
+                    if *loading { "loading-dots loading-sm" } else { "" })
+            }>
+                {"Resend Verification Link"}
+            </button>
+        </form>
+        }
+    };
+    auth_card("Verify Email", content)
+}
+
+async fn verify_submit(data: VerifyEmail) -> DynoResult<String> {
+    let response = Request::post("/api/auth/verify")
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
+
+async fn resend_submit(data: ResendVerification) -> DynoResult<String> {
+    let response = Request::post("/api/auth/verify/resend")
+        .json(&data)
+        .map_err(DynoErr::api_error)?
+        .send()
+        .await
+        .map_err(DynoErr::api_error)?;
+    parse_response(response).await
+}
@@ -10,12 +10,24 @@ pub enum Route {
     Dashboard,
     #[at("/activities")]
     Activities,
+    #[at("/activities/import")]
+    Import,
+    #[at("/classes")]
+    Classes,
     #[at("/sop")]
     Sop,
     #[at("/signin")]
     SignIn,
     #[at("/signup")]
     SignUp,
+    #[at("/share")]
+    Share,
+    #[at("/forgot")]
+    Forgot,
+    #[at("/reset")]
+    Reset,
+    #[at("/verify")]
+    Verify,
 
     #[at("/live")]
     Live,
@@ -31,6 +43,14 @@ pub enum Route {
     AdminInfos,
     #[at("/administration/history")]
     AdminHistory,
+    #[at("/administration/approvals")]
+    AdminApprovals,
+    #[at("/administration/api-keys")]
+    AdminApiKeys,
+    #[at("/administration/permissions")]
+    AdminPermissions,
+    #[at("/administration/audit-log")]
+    AdminAuditLog,
 }
 
 pub type LinkTag = Link<Route>;
@@ -70,6 +90,8 @@ thread_local! {
     pub static SIDE_BAR: std::cell::RefCell<Vec<RouteSideBar<'static>>>  = std::cell::RefCell::new(vec![
         route_sidebar!(HeroiconsOutlineSquares2X2, "Dashboard", Dashboard, User, "h-6 w-6"),
         route_sidebar!(HeroiconsOutlineChartBar, "Aktivitas", Activities, User, "h-6 w-6"),
+        route_sidebar!(HeroiconsOutlineArrowUpTray, "Import", Import, User, "h-6 w-6"),
+        route_sidebar!(HeroiconsOutlineAcademicCap, "Kelas", Classes, User, "h-6 w-6"),
         route_sidebar!(HeroiconsOutlineUser, "Profil", SettingProfile, User, "h-5 w-5"),
         route_sidebar!(
             HeroiconsOutlineDocumentDuplicate,
@@ -77,9 +99,13 @@ thread_local! {
             Admin,
             [
                 route_sidebar!(HeroiconsOutlineUsers, "Users", AdminUsers, Admin, "h-5 w-5"),
+                route_sidebar!(HeroiconsOutlineUserPlus, "Approvals", AdminApprovals, Admin, "h-5 w-5"),
+                route_sidebar!(HeroiconsOutlineShieldCheck, "Permissions", AdminPermissions, Admin, "h-5 w-5"),
                 route_sidebar!(HeroiconsOutlineTableCells, "Dynos", AdminDynos, Admin, "h-5 w-5"),
                 route_sidebar!(HeroiconsOutlineCog, "Infos", AdminInfos, Admin, "h-5 w-5"),
                 route_sidebar!(HeroiconsOutlineInboxStack, "History", AdminHistory, Admin, "h-5 w-5"),
+                route_sidebar!(HeroiconsOutlineKey, "Api Keys", AdminApiKeys, Admin, "h-5 w-5"),
+                route_sidebar!(HeroiconsOutlineClipboardDocumentList, "Audit Log", AdminAuditLog, Admin, "h-5 w-5"),
             ]
         ),
         route_sidebar!(HeroiconsOutlineDocumentText, "SOP", Sop, User, "h-6 w-6"),
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+UPDATE dynos SET data_url = '/dyno/' || data_url WHERE data_url NOT LIKE '/dyno/%';
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+UPDATE dynos SET data_url = substr(data_url, 7) WHERE data_url LIKE '/dyno/%';
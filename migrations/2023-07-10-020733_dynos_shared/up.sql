@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE dynos ADD COLUMN shared BOOLEAN NOT NULL DEFAULT 0;
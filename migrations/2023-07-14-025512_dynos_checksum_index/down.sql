@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS dynos_data_url;
+DROP INDEX IF EXISTS dynos_data_checksum;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+CREATE INDEX IF NOT EXISTS dynos_data_checksum ON dynos (data_checksum);
+CREATE INDEX IF NOT EXISTS dynos_data_url ON dynos (data_url);
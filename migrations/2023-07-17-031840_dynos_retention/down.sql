@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dynos DROP COLUMN archived;
+ALTER TABLE dynos DROP COLUMN data_size;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE dynos ADD COLUMN data_size BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE dynos ADD COLUMN archived BOOLEAN NOT NULL DEFAULT 0;
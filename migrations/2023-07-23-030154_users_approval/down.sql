@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS users_status;
+ALTER TABLE users DROP COLUMN requested_role;
+ALTER TABLE users DROP COLUMN status;
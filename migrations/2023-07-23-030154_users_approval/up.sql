@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- account that is registered before approval is required is treated as active
+ALTER TABLE users ADD COLUMN status TEXT NOT NULL DEFAULT 'active';
+ALTER TABLE users ADD COLUMN requested_role TEXT;
+CREATE INDEX IF NOT EXISTS users_status ON users (status);
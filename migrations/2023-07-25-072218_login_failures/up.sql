@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS login_failures (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    nim TEXT NOT NULL,
+    ip TEXT NOT NULL,
+    user_agent TEXT,
+    reason TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS login_failures_nim ON login_failures (nim);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS role_permissions;
+ALTER TABLE users DROP COLUMN access_role;
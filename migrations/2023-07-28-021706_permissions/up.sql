@@ -0,0 +1,15 @@
+-- Your SQL goes here
+ALTER TABLE users ADD COLUMN access_role TEXT NOT NULL DEFAULT 'student';
+UPDATE users SET access_role = 'admin' WHERE LOWER(role) = 'admin';
+
+-- admin always has every permission, it is not listed here
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role TEXT NOT NULL,
+    permission TEXT NOT NULL,
+    PRIMARY KEY (role, permission)
+);
+INSERT INTO role_permissions (role, permission) VALUES
+    ('lecturer', 'dyno.verify'),
+    ('lecturer', 'dyno.read_all'),
+    ('technician', 'dyno.read_all'),
+    ('technician', 'station.operate');
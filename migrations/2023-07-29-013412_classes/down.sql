@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS class_members_user_id;
+DROP TABLE IF EXISTS class_members;
+DROP INDEX IF EXISTS classes_lecturer_id;
+DROP TABLE IF EXISTS classes;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS submission_dynos;
+DROP TABLE IF EXISTS submissions;
+DROP INDEX IF EXISTS assignments_class_id;
+DROP TABLE IF EXISTS assignments;
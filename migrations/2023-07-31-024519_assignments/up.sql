@@ -0,0 +1,38 @@
+-- Your SQL goes here
+-- practicum task of the class, `engine_cc`, `engine_stroke` and `min_runs` is the requirement
+-- of the submitted dynotests
+CREATE TABLE IF NOT EXISTS assignments (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    class_id INTEGER NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    engine_cc SMALLINT,
+    engine_stroke SMALLINT,
+    min_runs INTEGER NOT NULL DEFAULT 1,
+    deadline DATETIME NOT NULL,
+    created_by INTEGER NOT NULL REFERENCES users(id),
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS assignments_class_id ON assignments (class_id);
+
+-- one submission per student per assignment, resubmitting replace the previous one
+CREATE TABLE IF NOT EXISTS submissions (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    assignment_id INTEGER NOT NULL REFERENCES assignments(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    analysis TEXT NOT NULL,
+    grade REAL,
+    comment TEXT,
+    graded_by INTEGER REFERENCES users(id),
+    graded_at DATETIME,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (assignment_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS submission_dynos (
+    submission_id INTEGER NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    dyno_id INTEGER NOT NULL REFERENCES dynos(id) ON DELETE CASCADE,
+    PRIMARY KEY (submission_id, dyno_id)
+);
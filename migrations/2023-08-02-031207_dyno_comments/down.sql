@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS dyno_annotations_dyno_id;
+DROP TABLE IF EXISTS dyno_annotations;
+DROP INDEX IF EXISTS dyno_comments_dyno_id;
+DROP TABLE IF EXISTS dyno_comments;
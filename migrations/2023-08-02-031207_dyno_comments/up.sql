@@ -0,0 +1,24 @@
+-- Your SQL goes here
+-- threaded discussion of a dynotest, reply has the `parent_id` of the replied comment
+CREATE TABLE IF NOT EXISTS dyno_comments (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    dyno_id INTEGER NOT NULL REFERENCES dynos(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    parent_id INTEGER REFERENCES dyno_comments(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS dyno_comments_dyno_id ON dyno_comments (dyno_id);
+
+-- marker on the chart of a dynotest, anchored to an rpm or time (seconds from the start) point
+CREATE TABLE IF NOT EXISTS dyno_annotations (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    dyno_id INTEGER NOT NULL REFERENCES dynos(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    anchor TEXT NOT NULL,
+    position DOUBLE NOT NULL,
+    label TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS dyno_annotations_dyno_id ON dyno_annotations (dyno_id);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS dyno_metadata;
+DROP INDEX IF EXISTS dyno_tags_tag;
+DROP TABLE IF EXISTS dyno_tags;
+ALTER TABLE dynos DROP COLUMN notes;
+ALTER TABLE dynos DROP COLUMN title;
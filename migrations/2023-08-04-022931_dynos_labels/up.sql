@@ -0,0 +1,19 @@
+-- Your SQL goes here
+ALTER TABLE dynos ADD COLUMN title TEXT;
+ALTER TABLE dynos ADD COLUMN notes TEXT;
+
+-- free-form label of a dynotest, ex: `stock`, `after remap`
+CREATE TABLE IF NOT EXISTS dyno_tags (
+    dyno_id INTEGER NOT NULL REFERENCES dynos(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (dyno_id, tag)
+);
+CREATE INDEX IF NOT EXISTS dyno_tags_tag ON dyno_tags (tag);
+
+-- arbitrary key/value of a dynotest, ex: `fuel` = `RON 92`
+CREATE TABLE IF NOT EXISTS dyno_metadata (
+    dyno_id INTEGER NOT NULL REFERENCES dynos(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (dyno_id, key)
+);
@@ -0,0 +1,19 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS search_info_delete;
+DROP TRIGGER IF EXISTS search_info_update;
+DROP TRIGGER IF EXISTS search_info_insert;
+DROP TRIGGER IF EXISTS search_dyno_metadata_delete;
+DROP TRIGGER IF EXISTS search_dyno_metadata_update;
+DROP TRIGGER IF EXISTS search_dyno_metadata_insert;
+DROP TRIGGER IF EXISTS search_dyno_tags_delete;
+DROP TRIGGER IF EXISTS search_dyno_tags_insert;
+DROP TRIGGER IF EXISTS search_dynos_delete;
+DROP TRIGGER IF EXISTS search_dynos_update;
+DROP TRIGGER IF EXISTS search_dynos_insert;
+DROP TRIGGER IF EXISTS search_users_delete;
+DROP TRIGGER IF EXISTS search_users_update;
+DROP TRIGGER IF EXISTS search_users_insert;
+DROP VIEW IF EXISTS search_info_documents;
+DROP VIEW IF EXISTS search_dyno_documents;
+DROP VIEW IF EXISTS search_user_documents;
+DROP TABLE IF EXISTS search_index;
@@ -0,0 +1,109 @@
+-- Your SQL goes here
+-- full-text index of users, dynotests and dyno_info, kept in sync by the triggers below.
+-- `kind` is `user`, `dyno` or `info` and `ref_id` is the id in the table of the kind.
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5 (
+    kind UNINDEXED,
+    ref_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE VIEW IF NOT EXISTS search_user_documents AS
+SELECT id AS ref_id, name AS title, nim || ' ' || COALESCE(email, '') AS body
+FROM users;
+
+CREATE VIEW IF NOT EXISTS search_dyno_documents AS
+SELECT
+    d.id AS ref_id,
+    COALESCE(d.title, '') AS title,
+    COALESCE(d.notes, '')
+        || ' ' || COALESCE((SELECT group_concat(t.tag, ' ') FROM dyno_tags t WHERE t.dyno_id = d.id), '')
+        || ' ' || COALESCE((SELECT group_concat(m.key || ' ' || m.value, ' ') FROM dyno_metadata m WHERE m.dyno_id = d.id), '')
+        AS body
+FROM dynos d;
+
+CREATE VIEW IF NOT EXISTS search_info_documents AS
+SELECT
+    id AS ref_id,
+    COALESCE(name, '') AS title,
+    COALESCE(cc || ' cc', '')
+        || ' ' || COALESCE(stroke || ' stroke', '')
+        || ' ' || COALESCE(cylinder || ' cylinder', '')
+        AS body
+FROM dyno_info;
+
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'user', ref_id, title, body FROM search_user_documents;
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'dyno', ref_id, title, body FROM search_dyno_documents;
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'info', ref_id, title, body FROM search_info_documents;
+
+-- users
+CREATE TRIGGER IF NOT EXISTS search_users_insert AFTER INSERT ON users BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'user', ref_id, title, body FROM search_user_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_users_update AFTER UPDATE OF nim, name, email ON users BEGIN
+    DELETE FROM search_index WHERE kind = 'user' AND ref_id = OLD.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'user', ref_id, title, body FROM search_user_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_users_delete AFTER DELETE ON users BEGIN
+    DELETE FROM search_index WHERE kind = 'user' AND ref_id = OLD.id;
+END;
+
+-- dynos, with its tags and metadata
+CREATE TRIGGER IF NOT EXISTS search_dynos_insert AFTER INSERT ON dynos BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dynos_update AFTER UPDATE OF title, notes ON dynos BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = OLD.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dynos_delete AFTER DELETE ON dynos BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = OLD.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_tags_insert AFTER INSERT ON dyno_tags BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = NEW.dyno_id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_tags_delete AFTER DELETE ON dyno_tags BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = OLD.dyno_id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = OLD.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_insert AFTER INSERT ON dyno_metadata BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = NEW.dyno_id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_update AFTER UPDATE ON dyno_metadata BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = NEW.dyno_id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_delete AFTER DELETE ON dyno_metadata BEGIN
+    DELETE FROM search_index WHERE kind = 'dyno' AND ref_id = OLD.dyno_id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'dyno', ref_id, title, body FROM search_dyno_documents WHERE ref_id = OLD.dyno_id;
+END;
+
+-- dyno_info
+CREATE TRIGGER IF NOT EXISTS search_info_insert AFTER INSERT ON dyno_info BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'info', ref_id, title, body FROM search_info_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_info_update AFTER UPDATE ON dyno_info BEGIN
+    DELETE FROM search_index WHERE kind = 'info' AND ref_id = OLD.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    SELECT 'info', ref_id, title, body FROM search_info_documents WHERE ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_info_delete AFTER DELETE ON dyno_info BEGIN
+    DELETE FROM search_index WHERE kind = 'info' AND ref_id = OLD.id;
+END;
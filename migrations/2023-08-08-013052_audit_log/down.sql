@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS audit_log_no_delete;
+DROP TRIGGER IF EXISTS audit_log_no_update;
+DROP INDEX IF EXISTS audit_log_target;
+DROP INDEX IF EXISTS audit_log_actor_id;
+DROP INDEX IF EXISTS audit_log_created_at;
+DROP TABLE IF EXISTS audit_log;
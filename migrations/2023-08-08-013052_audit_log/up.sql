@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- append-only record of every administrative and data-changing action
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    -- `NULL` when the actor is not logged in, ex: failed login
+    actor_id INTEGER,
+    -- ex: `user.delete`, `dyno.verify`, `auth.login`
+    action TEXT NOT NULL,
+    -- ex: `user`, `dyno`, `class`
+    target_type TEXT,
+    target_id INTEGER,
+    -- JSON of the changed fields `{"field": {"before": .., "after": ..}}`
+    changes TEXT,
+    ip TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS audit_log_created_at ON audit_log (created_at);
+CREATE INDEX IF NOT EXISTS audit_log_actor_id ON audit_log (actor_id);
+CREATE INDEX IF NOT EXISTS audit_log_target ON audit_log (target_type, target_id);
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- time step of the last accepted TOTP code, code of the same or an older step is rejected as replay
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT;
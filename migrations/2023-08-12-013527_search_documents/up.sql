@@ -0,0 +1,154 @@
+-- Your SQL goes here
+-- `search_index` is keyed by the rowid of `search_documents`, so the triggers replace a document
+-- with a rowid lookup instead of scanning the `UNINDEXED` `kind` and `ref_id` columns of the index.
+DROP TRIGGER IF EXISTS search_users_insert;
+DROP TRIGGER IF EXISTS search_users_update;
+DROP TRIGGER IF EXISTS search_users_delete;
+DROP TRIGGER IF EXISTS search_dynos_insert;
+DROP TRIGGER IF EXISTS search_dynos_update;
+DROP TRIGGER IF EXISTS search_dynos_delete;
+DROP TRIGGER IF EXISTS search_dyno_tags_insert;
+DROP TRIGGER IF EXISTS search_dyno_tags_delete;
+DROP TRIGGER IF EXISTS search_dyno_metadata_insert;
+DROP TRIGGER IF EXISTS search_dyno_metadata_update;
+DROP TRIGGER IF EXISTS search_dyno_metadata_delete;
+DROP TRIGGER IF EXISTS search_info_insert;
+DROP TRIGGER IF EXISTS search_info_update;
+DROP TRIGGER IF EXISTS search_info_delete;
+DROP TABLE IF EXISTS search_index;
+
+CREATE TABLE IF NOT EXISTS search_documents (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    -- `user`, `dyno` or `info`
+    kind TEXT NOT NULL,
+    -- id in the table of the kind
+    ref_id INTEGER NOT NULL,
+    UNIQUE (kind, ref_id)
+);
+
+-- full-text index of the documents in the `search_*_documents` views, `rowid` is `search_documents.id`
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5 (
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+INSERT INTO search_documents (kind, ref_id) SELECT 'user', ref_id FROM search_user_documents;
+INSERT INTO search_documents (kind, ref_id) SELECT 'dyno', ref_id FROM search_dyno_documents;
+INSERT INTO search_documents (kind, ref_id) SELECT 'info', ref_id FROM search_info_documents;
+INSERT INTO search_index (rowid, title, body)
+SELECT doc.id, v.title, v.body FROM search_user_documents v
+JOIN search_documents doc ON doc.kind = 'user' AND doc.ref_id = v.ref_id;
+INSERT INTO search_index (rowid, title, body)
+SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id;
+INSERT INTO search_index (rowid, title, body)
+SELECT doc.id, v.title, v.body FROM search_info_documents v
+JOIN search_documents doc ON doc.kind = 'info' AND doc.ref_id = v.ref_id;
+
+-- users
+CREATE TRIGGER IF NOT EXISTS search_users_insert AFTER INSERT ON users BEGIN
+    INSERT OR IGNORE INTO search_documents (kind, ref_id) VALUES ('user', NEW.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_user_documents v
+    JOIN search_documents doc ON doc.kind = 'user' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_users_update AFTER UPDATE OF nim, name, email ON users BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'user' AND ref_id = OLD.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_user_documents v
+    JOIN search_documents doc ON doc.kind = 'user' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_users_delete AFTER DELETE ON users BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'user' AND ref_id = OLD.id);
+    DELETE FROM search_documents WHERE kind = 'user' AND ref_id = OLD.id;
+END;
+
+-- dynos, with its tags and metadata
+CREATE TRIGGER IF NOT EXISTS search_dynos_insert AFTER INSERT ON dynos BEGIN
+    INSERT OR IGNORE INTO search_documents (kind, ref_id) VALUES ('dyno', NEW.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dynos_update AFTER UPDATE OF title, notes ON dynos BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = OLD.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dynos_delete AFTER DELETE ON dynos BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = OLD.id);
+    DELETE FROM search_documents WHERE kind = 'dyno' AND ref_id = OLD.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_tags_insert AFTER INSERT ON dyno_tags BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = NEW.dyno_id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_tags_delete AFTER DELETE ON dyno_tags BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = OLD.dyno_id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = OLD.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_insert AFTER INSERT ON dyno_metadata BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = NEW.dyno_id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_update AFTER UPDATE ON dyno_metadata BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = NEW.dyno_id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.dyno_id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_dyno_metadata_delete AFTER DELETE ON dyno_metadata BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'dyno' AND ref_id = OLD.dyno_id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_dyno_documents v
+    JOIN search_documents doc ON doc.kind = 'dyno' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = OLD.dyno_id;
+END;
+
+-- dyno_info
+CREATE TRIGGER IF NOT EXISTS search_info_insert AFTER INSERT ON dyno_info BEGIN
+    INSERT OR IGNORE INTO search_documents (kind, ref_id) VALUES ('info', NEW.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_info_documents v
+    JOIN search_documents doc ON doc.kind = 'info' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_info_update AFTER UPDATE ON dyno_info BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'info' AND ref_id = OLD.id);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT doc.id, v.title, v.body FROM search_info_documents v
+    JOIN search_documents doc ON doc.kind = 'info' AND doc.ref_id = v.ref_id
+    WHERE v.ref_id = NEW.id;
+END;
+CREATE TRIGGER IF NOT EXISTS search_info_delete AFTER DELETE ON dyno_info BEGIN
+    DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_documents WHERE kind = 'info' AND ref_id = OLD.id);
+    DELETE FROM search_documents WHERE kind = 'info' AND ref_id = OLD.id;
+END;